@@ -4,6 +4,7 @@
 mod modules;
 
 use modules::{
+    alerts::AlertManager,
     cpu::CpuMonitor,
     memory::MemoryMonitor,
     disk::DiskMonitor,
@@ -12,9 +13,29 @@ use modules::{
     gpu::GpuMonitor,
     sensors::SensorsMonitor,
     system::SystemMonitor,
+    MutexRecover,
+    TemperatureUnit,
 };
-use std::sync::Arc;
-use tauri::State;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Background streaming task started by `start_monitoring`
+struct MonitoringTask {
+    handle: tauri::async_runtime::JoinHandle<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MonitoringTask {
+    /// Signal the loop to exit and abort it if it's sleeping
+    fn cancel(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.abort();
+    }
+}
 
 /// Application state containing all monitors (thread-safe)
 pub struct AppState {
@@ -26,19 +47,35 @@ pub struct AppState {
     pub gpu: Arc<GpuMonitor>,
     pub sensors: Arc<SensorsMonitor>,
     pub system: Arc<SystemMonitor>,
+    pub alerts: AlertManager,
+    temperature_unit: Mutex<TemperatureUnit>,
+    active: AtomicBool, // False while the window is hidden; slows streaming down
+    monitoring: Mutex<Option<MonitoringTask>>,
+}
+
+impl AppState {
+    /// Unit used for temperatures returned to the frontend
+    fn temperature_unit(&self) -> TemperatureUnit {
+        *self.temperature_unit.lock_or_recover("Temperature unit")
+    }
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        let gpu = Arc::new(GpuMonitor::new());
         Self {
             cpu: Arc::new(CpuMonitor::new()),
             memory: Arc::new(MemoryMonitor::new()),
             disk: Arc::new(DiskMonitor::new()),
             network: Arc::new(NetworkMonitor::new()),
             process: Arc::new(ProcessMonitor::new()),
-            gpu: Arc::new(GpuMonitor::new()),
-            sensors: Arc::new(SensorsMonitor::new()),
+            gpu: Arc::clone(&gpu),
+            sensors: Arc::new(SensorsMonitor::with_gpu(gpu)),
             system: Arc::new(SystemMonitor::new()),
+            alerts: AlertManager::new(),
+            temperature_unit: Mutex::new(TemperatureUnit::default()),
+            active: AtomicBool::new(true),
+            monitoring: Mutex::new(None),
         }
     }
 }
@@ -50,36 +87,191 @@ impl Default for AppState {
 #[tauri::command]
 async fn get_cpu_info(state: State<'_, AppState>) -> Result<modules::cpu::CpuInfo, String> {
     let cpu = Arc::clone(&state.cpu);
+    let unit = state.temperature_unit();
     tokio::task::spawn_blocking(move || {
-        Ok(cpu.refresh())
+        Ok(cpu.refresh().in_unit(unit))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// CPU usage measured over exactly `window_ms` (clamped to 200-5000 ms)
+/// Blocks for the window, but gives a real reading even as the very first call
+#[tauri::command]
+async fn get_cpu_usage_sampled(window_ms: u64) -> Result<modules::cpu::CpuUsageSample, String> {
+    tokio::task::spawn_blocking(move || Ok(modules::cpu::sample_usage(window_ms)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Per-core frequency history as (timestamp ms, MHz per core), oldest first
+#[tauri::command]
+fn get_cpu_frequency_history(state: State<'_, AppState>) -> Vec<(u64, Vec<u64>)> {
+    state.cpu.frequency_history()
+}
+
+#[tauri::command]
+fn get_cpu_stats(state: State<'_, AppState>, window_secs: u64) -> modules::cpu::CpuStats {
+    state.cpu.stats(window_secs)
+}
+
 // ============================================================================
 // Memory Commands (Async)
 // ============================================================================
 
 #[tauri::command]
-async fn get_memory_info(state: State<'_, AppState>) -> Result<modules::memory::MemoryInfo, String> {
+async fn get_memory_info(
+    state: State<'_, AppState>,
+    top_consumers: Option<usize>,
+) -> Result<modules::memory::MemoryInfo, String> {
     let memory = Arc::clone(&state.memory);
+    let top_consumers = top_consumers.unwrap_or(modules::memory::DEFAULT_TOP_CONSUMERS);
     tokio::task::spawn_blocking(move || {
-        Ok(memory.refresh())
+        Ok(memory.refresh(top_consumers))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+#[tauri::command]
+async fn get_numa_info() -> Result<Vec<modules::memory::NumaNode>, String> {
+    tokio::task::spawn_blocking(|| Ok(modules::memory::get_numa_info()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 // ============================================================================
 // Disk Commands (Async)
 // ============================================================================
 
 #[tauri::command]
-async fn get_disk_info(state: State<'_, AppState>) -> Result<modules::disk::DisksInfo, String> {
+async fn get_disk_info(
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<modules::disk::DisksInfo, String> {
+    let disk = Arc::clone(&state.disk);
+    let unit = state.temperature_unit();
+    tokio::task::spawn_blocking(move || {
+        // Bypass the SMART cache for this call, e.g. right after a self-test
+        if force_refresh.unwrap_or(false) {
+            disk.invalidate_smart_cache();
+        }
+        Ok(disk.refresh().in_unit(unit))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_physical_disks(state: State<'_, AppState>) -> Result<Vec<modules::disk::PhysicalDisk>, String> {
+    let disk = Arc::clone(&state.disk);
+    let unit = state.temperature_unit();
+    tokio::task::spawn_blocking(move || {
+        Ok(disk.physical_disks().into_iter().map(|d| d.in_unit(unit)).collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// List devices smartctl can address (`smartctl --scan`), with their -d type hints
+#[tauri::command]
+async fn list_smart_devices(
+    state: State<'_, AppState>,
+) -> Result<Vec<modules::disk::SmartDevice>, String> {
+    let disk = Arc::clone(&state.disk);
+    tokio::task::spawn_blocking(move || disk.list_smart_devices().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Start a SMART self-test on a block device (needs root or disk group access)
+/// `device_type` is the -d hint from list_smart_devices, needed for RAID members
+#[tauri::command]
+async fn run_smart_self_test(
+    state: State<'_, AppState>,
+    device: String,
+    device_type: Option<String>,
+    kind: modules::disk::SelfTestKind,
+) -> Result<(), String> {
+    let disk = Arc::clone(&state.disk);
+    tokio::task::spawn_blocking(move || {
+        disk.run_smart_self_test(&device, device_type.as_deref(), kind)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// TRIM a mounted filesystem (needs root); returns the number of bytes discarded
+#[tauri::command]
+async fn run_fstrim(state: State<'_, AppState>, mount_point: String) -> Result<u64, String> {
+    let disk = Arc::clone(&state.disk);
+    tokio::task::spawn_blocking(move || {
+        disk.run_fstrim(&mount_point).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Mount a removable device; without a mount point udisksctl chooses one (no root needed)
+/// Returns the mount point
+#[tauri::command]
+async fn mount_device(
+    state: State<'_, AppState>,
+    device: String,
+    mount_point: Option<String>,
+) -> Result<String, String> {
+    let disk = Arc::clone(&state.disk);
+    tokio::task::spawn_blocking(move || {
+        disk.mount_device(&device, mount_point.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Unmount a removable disk by its mount point
+#[tauri::command]
+async fn unmount_device(state: State<'_, AppState>, mount_point: String) -> Result<(), String> {
+    let disk = Arc::clone(&state.disk);
+    tokio::task::spawn_blocking(move || {
+        disk.unmount_device(&mount_point).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_smart_self_test_status(
+    state: State<'_, AppState>,
+    device: String,
+    device_type: Option<String>,
+) -> Result<modules::disk::SmartSelfTestStatus, String> {
     let disk = Arc::clone(&state.disk);
     tokio::task::spawn_blocking(move || {
-        Ok(disk.refresh())
+        disk.smart_self_test_status(&device, device_type.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// How long SMART results are cached before smartctl runs again
+#[tauri::command]
+fn set_smart_cache_duration(state: State<'_, AppState>, secs: u64) {
+    state.disk.set_smart_cache_duration(secs);
+}
+
+/// Hide disks (by device name or mount point) from the list and totals
+#[tauri::command]
+fn set_hidden_disks(state: State<'_, AppState>, names: Vec<String>) {
+    state.disk.set_hidden(names);
+}
+
+/// Sizes of the children of `path`, largest first (du-like)
+#[tauri::command]
+async fn scan_directory(path: String, max_depth: u32) -> Result<Vec<modules::disk::DirEntry>, String> {
+    tokio::task::spawn_blocking(move || {
+        modules::disk::scan_directory(Path::new(&path), max_depth).map_err(|e| e.to_string())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
@@ -90,29 +282,114 @@ async fn get_disk_info(state: State<'_, AppState>) -> Result<modules::disk::Disk
 // ============================================================================
 
 #[tauri::command]
-async fn get_network_info(state: State<'_, AppState>) -> Result<modules::network::NetworkInfo, String> {
+async fn get_network_info(
+    state: State<'_, AppState>,
+    include_loopback: Option<bool>,
+    only_up: Option<bool>,
+) -> Result<modules::network::NetworkInfo, String> {
     let network = Arc::clone(&state.network);
+    let defaults = modules::network::NetworkQuery::default();
+    let query = modules::network::NetworkQuery {
+        include_loopback: include_loopback.unwrap_or(defaults.include_loopback),
+        only_up: only_up.unwrap_or(defaults.only_up),
+    };
     tokio::task::spawn_blocking(move || {
-        Ok(network.refresh())
+        Ok(network.refresh(&query))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+#[tauri::command]
+fn get_network_history(state: State<'_, AppState>) -> HashMap<String, Vec<(u64, f64, f64)>> {
+    state.network.history()
+}
+
+#[tauri::command]
+async fn get_connections(state: State<'_, AppState>) -> Result<Vec<modules::network::Connection>, String> {
+    let network = Arc::clone(&state.network);
+    tokio::task::spawn_blocking(move || {
+        Ok(network.connections())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Socket counts per TCP state; lighter than get_connections, meant for polling
+#[tauri::command]
+async fn get_socket_summary(state: State<'_, AppState>) -> Result<modules::network::SocketSummary, String> {
+    let network = Arc::clone(&state.network);
+    tokio::task::spawn_blocking(move || {
+        Ok(network.socket_summary())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// System-wide packet counters and rates per protocol (IPv4/IPv6, TCP, UDP, ICMP)
+#[tauri::command]
+async fn get_protocol_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<modules::network::ProtocolCounter>, String> {
+    let network = Arc::clone(&state.network);
+    tokio::task::spawn_blocking(move || Ok(network.protocol_stats()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// DNS servers and default gateways
+#[tauri::command]
+async fn get_network_config() -> Result<modules::network::NetworkConfig, String> {
+    tokio::task::spawn_blocking(|| {
+        Ok(modules::network::get_network_config())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Hide interfaces from the list and totals
+#[tauri::command]
+fn set_hidden_interfaces(state: State<'_, AppState>, names: Vec<String>) {
+    state.network.set_hidden(names);
+}
+
 // ============================================================================
 // Process Commands (Async)
 // ============================================================================
 
+/// Always list these PIDs first, regardless of filters; exited ones are dropped
+#[tauri::command]
+fn set_pinned_processes(state: State<'_, AppState>, pids: Vec<u32>) {
+    state.process.set_pinned(pids);
+}
+
 #[tauri::command]
-async fn get_processes(state: State<'_, AppState>) -> Result<modules::process::ProcessList, String> {
+async fn get_processes(
+    state: State<'_, AppState>,
+    query: Option<modules::process::ProcessQuery>,
+) -> Result<modules::process::ProcessList, String> {
     let process = Arc::clone(&state.process);
+    let query = query.unwrap_or_default();
     tokio::task::spawn_blocking(move || {
-        Ok(process.refresh())
+        Ok(process.refresh(&query))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// The N busiest processes, ungrouped; cheaper than get_processes for a summary tile
+#[tauri::command]
+async fn get_top_processes(
+    state: State<'_, AppState>,
+    n: usize,
+    by: modules::process::SortKey,
+) -> Result<Vec<modules::process::ProcessInfo>, String> {
+    let process = Arc::clone(&state.process);
+    tokio::task::spawn_blocking(move || Ok(process.top(n, by)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 #[tauri::command]
 async fn kill_process(state: State<'_, AppState>, pid: u32, force: bool) -> Result<bool, String> {
     let process = Arc::clone(&state.process);
@@ -123,6 +400,40 @@ async fn kill_process(state: State<'_, AppState>, pid: u32, force: bool) -> Resu
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Name of the process if `pid` is still alive (empty when it can't be read), None otherwise
+#[tauri::command]
+fn process_exists(pid: u32) -> Option<String> {
+    modules::process::process_name(pid)
+}
+
+/// Command line and environment of a process, optionally with secrets masked
+#[tauri::command]
+async fn get_process_details(
+    pid: u32,
+    redact_secrets: bool,
+) -> Result<modules::process::ProcessDetails, String> {
+    tokio::task::spawn_blocking(move || {
+        modules::process::process_details(pid, redact_secrets).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Kill every process with the given name (case-insensitive exact match)
+#[tauri::command]
+async fn kill_by_name(
+    state: State<'_, AppState>,
+    name: String,
+    force: bool,
+) -> Result<modules::process::KillByNameResult, String> {
+    let process = Arc::clone(&state.process);
+    tokio::task::spawn_blocking(move || {
+        process.kill_by_name(&name, force).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 #[tauri::command]
 async fn set_process_priority(state: State<'_, AppState>, pid: u32, nice: i32) -> Result<(), String> {
     let process = Arc::clone(&state.process);
@@ -138,10 +449,93 @@ async fn set_process_priority(state: State<'_, AppState>, pid: u32, nice: i32) -
 // ============================================================================
 
 #[tauri::command]
-async fn get_gpu_info(state: State<'_, AppState>) -> Result<modules::gpu::GpusInfo, String> {
+async fn get_gpu_info(
+    state: State<'_, AppState>,
+    query: Option<modules::gpu::GpuQuery>,
+) -> Result<modules::gpu::GpusInfo, String> {
+    let gpu = Arc::clone(&state.gpu);
+    let unit = state.temperature_unit();
+    let query = query.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        Ok(gpu.refresh().filter(&query).in_unit(unit))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_gpu_processes(state: State<'_, AppState>) -> Result<modules::gpu::GpuProcessList, String> {
+    let gpu = Arc::clone(&state.gpu);
+    tokio::task::spawn_blocking(move || {
+        Ok(gpu.processes())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Kill a process from the GPU pane, refusing PIDs that aren't currently using a GPU
+#[tauri::command]
+async fn kill_gpu_process(state: State<'_, AppState>, pid: u32, force: bool) -> Result<bool, String> {
+    let gpu = Arc::clone(&state.gpu);
+    let process = Arc::clone(&state.process);
+    tokio::task::spawn_blocking(move || {
+        gpu.processes().ensure_contains(pid).map_err(|e| e.to_string())?;
+        process.kill_process(pid, force).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+fn get_gpu_history(state: State<'_, AppState>) -> HashMap<String, Vec<modules::gpu::GpuSample>> {
+    let unit = state.temperature_unit();
+    state
+        .gpu
+        .history()
+        .into_iter()
+        .map(|(uuid, samples)| (uuid, samples.into_iter().map(|s| s.in_unit(unit)).collect()))
+        .collect()
+}
+
+/// Set a fixed fan speed (0-100%) on an NVIDIA GPU; needs root/administrator
+#[tauri::command]
+async fn set_gpu_fan_speed(state: State<'_, AppState>, gpu_index: u32, percent: u32) -> Result<(), String> {
+    let gpu = Arc::clone(&state.gpu);
+    tokio::task::spawn_blocking(move || {
+        gpu.set_fan_speed(gpu_index, Some(percent)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Restore automatic fan control on an NVIDIA GPU
+#[tauri::command]
+async fn set_gpu_fan_auto(state: State<'_, AppState>, gpu_index: u32) -> Result<(), String> {
+    let gpu = Arc::clone(&state.gpu);
+    tokio::task::spawn_blocking(move || {
+        gpu.set_fan_speed(gpu_index, None).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Cap an NVIDIA GPU's power draw; must be within power_limit_min/max, needs root/administrator
+#[tauri::command]
+async fn set_gpu_power_limit(state: State<'_, AppState>, gpu_index: u32, milliwatts: u32) -> Result<(), String> {
     let gpu = Arc::clone(&state.gpu);
     tokio::task::spawn_blocking(move || {
-        Ok(gpu.refresh())
+        gpu.set_power_limit(gpu_index, Some(milliwatts)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Restore an NVIDIA GPU's default power limit
+#[tauri::command]
+async fn reset_gpu_power_limit(state: State<'_, AppState>, gpu_index: u32) -> Result<(), String> {
+    let gpu = Arc::clone(&state.gpu);
+    tokio::task::spawn_blocking(move || {
+        gpu.set_power_limit(gpu_index, None).map_err(|e| e.to_string())
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
@@ -154,13 +548,35 @@ async fn get_gpu_info(state: State<'_, AppState>) -> Result<modules::gpu::GpusIn
 #[tauri::command]
 async fn get_sensors_info(state: State<'_, AppState>) -> Result<modules::sensors::SensorsInfo, String> {
     let sensors = Arc::clone(&state.sensors);
+    let unit = state.temperature_unit();
     tokio::task::spawn_blocking(move || {
-        Ok(sensors.refresh())
+        Ok(sensors.refresh().in_unit(unit))
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Raw dump of every hwmon chip and channel (complements the curated sensor list)
+#[tauri::command]
+async fn get_all_hwmon() -> Result<Vec<modules::sensors::HwmonChip>, String> {
+    tokio::task::spawn_blocking(|| {
+        Ok(modules::sensors::read_all_hwmon())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+fn get_sensors_history(state: State<'_, AppState>) -> HashMap<String, modules::sensors::SensorHistory> {
+    let unit = state.temperature_unit();
+    state
+        .sensors
+        .history()
+        .into_iter()
+        .map(|(key, history)| (key, history.in_unit(unit)))
+        .collect()
+}
+
 // ============================================================================
 // System Commands (Sync - no blocking I/O)
 // ============================================================================
@@ -170,6 +586,392 @@ fn get_system_info(state: State<'_, AppState>) -> modules::system::SystemInfo {
     state.system.refresh()
 }
 
+#[tauri::command]
+fn get_hardware_info(state: State<'_, AppState>) -> modules::system::HardwareInfo {
+    state.system.hardware_info()
+}
+
+#[tauri::command]
+fn get_logged_in_users() -> Vec<modules::system::UserSession> {
+    modules::system::get_logged_in_users()
+}
+
+#[tauri::command]
+fn get_kernel_modules() -> Vec<modules::system::KernelModule> {
+    modules::system::get_kernel_modules()
+}
+
+#[tauri::command]
+fn get_battery_info() -> Result<modules::battery::BatteryInfo, String> {
+    modules::battery::get_battery_info().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn system_reboot(state: State<'_, AppState>) -> Result<(), String> {
+    run_power_action(&state, modules::system::PowerAction::Reboot).await
+}
+
+#[tauri::command]
+async fn system_shutdown(state: State<'_, AppState>) -> Result<(), String> {
+    run_power_action(&state, modules::system::PowerAction::Shutdown).await
+}
+
+#[tauri::command]
+async fn system_suspend(state: State<'_, AppState>) -> Result<(), String> {
+    run_power_action(&state, modules::system::PowerAction::Suspend).await
+}
+
+/// What this process may do (root, renice, kill, GPU control) and which tools it can use
+#[tauri::command]
+async fn get_capabilities(
+    state: State<'_, AppState>,
+) -> Result<modules::system::Capabilities, String> {
+    let gpu = Arc::clone(&state.gpu);
+    tokio::task::spawn_blocking(move || {
+        Ok(modules::system::get_capabilities(gpu.nvml_available()))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_boot_performance(
+    state: State<'_, AppState>,
+) -> Result<modules::system::BootPerformance, String> {
+    let system = Arc::clone(&state.system);
+    tokio::task::spawn_blocking(move || system.boot_performance().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_recent_logs(
+    state: State<'_, AppState>,
+    count: usize,
+    min_priority: u8,
+) -> Result<Vec<modules::system::LogEntry>, String> {
+    let system = Arc::clone(&state.system);
+    tokio::task::spawn_blocking(move || {
+        system.recent_logs(count, min_priority).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+async fn run_power_action(
+    state: &State<'_, AppState>,
+    action: modules::system::PowerAction,
+) -> Result<(), String> {
+    let system = Arc::clone(&state.system);
+    tokio::task::spawn_blocking(move || {
+        system.power_action(action).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ============================================================================
+// Snapshot Commands (Async, all monitors refreshed concurrently)
+// ============================================================================
+
+/// Refresh every monitor concurrently; total latency is bounded by the slowest one
+async fn collect_snapshot(
+    state: &AppState,
+    options: modules::snapshot::SnapshotOptions,
+) -> Result<modules::snapshot::Snapshot, String> {
+    let cpu = Arc::clone(&state.cpu);
+    let memory = Arc::clone(&state.memory);
+    let disk = Arc::clone(&state.disk);
+    let network = Arc::clone(&state.network);
+    let process = Arc::clone(&state.process);
+    let gpu = Arc::clone(&state.gpu);
+    let sensors = Arc::clone(&state.sensors);
+
+    let (cpu, memory, disk, network, process, gpu_and_sensors) = tokio::join!(
+        tokio::task::spawn_blocking(move || cpu.refresh()),
+        tokio::task::spawn_blocking(move || memory.refresh(modules::memory::DEFAULT_TOP_CONSUMERS)),
+        tokio::task::spawn_blocking(move || disk.refresh()),
+        tokio::task::spawn_blocking(move || {
+            network.refresh(&modules::network::NetworkQuery::default())
+        }),
+        tokio::task::spawn_blocking(move || {
+            options
+                .include_processes
+                .then(|| process.refresh(&modules::process::ProcessQuery::default()))
+        }),
+        // Sensors summarize the GPU refresh, so they read it once it's done
+        tokio::task::spawn_blocking(move || {
+            let gpu = gpu.refresh();
+            (gpu, sensors.refresh())
+        }),
+    );
+
+    let join_error = |e: tokio::task::JoinError| format!("Task join error: {}", e);
+    let (gpu, sensors) = gpu_and_sensors.map_err(join_error)?;
+
+    Ok(modules::snapshot::Snapshot {
+        cpu: cpu.map_err(join_error)?,
+        memory: memory.map_err(join_error)?,
+        disk: disk.map_err(join_error)?,
+        network: network.map_err(join_error)?,
+        process: process.map_err(join_error)?,
+        gpu,
+        sensors,
+        system: state.system.refresh(),
+    })
+}
+
+#[tauri::command]
+async fn get_snapshot(
+    state: State<'_, AppState>,
+    options: Option<modules::snapshot::SnapshotOptions>,
+) -> Result<modules::snapshot::Snapshot, String> {
+    let snapshot = collect_snapshot(&state, options.unwrap_or_default()).await?;
+    Ok(snapshot.in_unit(state.temperature_unit()))
+}
+
+/// One refresh of every monitor in Prometheus text exposition format
+#[tauri::command]
+async fn metrics_prometheus(state: State<'_, AppState>) -> Result<String, String> {
+    let options = modules::snapshot::SnapshotOptions {
+        include_processes: false,
+    };
+    let snapshot = collect_snapshot(&state, options).await?;
+    Ok(modules::export::to_prometheus(&snapshot))
+}
+
+/// Ok/Warning/Critical status derived from one refresh of every monitor
+#[tauri::command]
+async fn get_health_summary(
+    state: State<'_, AppState>,
+) -> Result<modules::health::HealthSummary, String> {
+    let options = modules::snapshot::SnapshotOptions {
+        include_processes: false,
+    };
+    let snapshot = collect_snapshot(&state, options).await?;
+    Ok(modules::health::summarize(&snapshot))
+}
+
+/// Export in-memory history buffers as CSV or JSON
+#[tauri::command]
+fn export_history(
+    state: State<'_, AppState>,
+    format: modules::export::ExportFormat,
+    metrics: Vec<String>,
+) -> Result<String, String> {
+    modules::export::export_history(&state.cpu, &state.network, format, &metrics)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Streaming Commands (Push-based updates via Tauri events)
+// ============================================================================
+
+// Lower bound for the streaming interval to keep refresh overhead sane
+const MIN_STREAM_INTERVAL_MS: u64 = 250;
+
+// While the window is hidden only every Nth tick refreshes (interval x 5)
+const INACTIVE_SLOWDOWN_TICKS: u32 = 5;
+
+/// Decides which streaming ticks refresh
+/// The ticker keeps the base interval and inactive ticks are skipped, so becoming active
+/// again takes effect on the very next tick
+struct TickThrottle {
+    ticks_since_emit: u32,
+}
+
+impl TickThrottle {
+    fn new() -> Self {
+        // The first tick always refreshes
+        Self {
+            ticks_since_emit: INACTIVE_SLOWDOWN_TICKS,
+        }
+    }
+
+    /// Whether this tick should refresh, given whether the window is currently visible
+    fn tick(&mut self, active: bool) -> bool {
+        self.ticks_since_emit = self.ticks_since_emit.saturating_add(1);
+        if !active && self.ticks_since_emit < INACTIVE_SLOWDOWN_TICKS {
+            return false;
+        }
+        self.ticks_since_emit = 0;
+        true
+    }
+}
+
+/// Refresh every monitor once, emit one event per monitor, then evaluate alert rules
+fn emit_all_updates(app: &AppHandle) {
+    let state = app.state::<AppState>();
+
+    // Sensors summarize this GPU refresh, so it goes first
+    let gpu = state.gpu.refresh();
+    let sensors = state.sensors.refresh();
+
+    let snapshot = modules::snapshot::Snapshot {
+        cpu: state.cpu.refresh(),
+        memory: state.memory.refresh(modules::memory::DEFAULT_TOP_CONSUMERS),
+        disk: state.disk.refresh(),
+        network: state.network.refresh(&modules::network::NetworkQuery::default()),
+        process: Some(state.process.refresh(&modules::process::ProcessQuery::default())),
+        gpu,
+        sensors,
+        system: state.system.refresh(),
+    };
+
+    // Alert thresholds are in Celsius, so rules see the snapshot before conversion
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let alerts = state.alerts.evaluate(&snapshot, now);
+
+    let snapshot = snapshot.in_unit(state.temperature_unit());
+
+    // Emit failures only happen when the app is shutting down, so they're ignored
+    let _ = app.emit("cpu-update", &snapshot.cpu);
+    let _ = app.emit("memory-update", &snapshot.memory);
+    let _ = app.emit("disk-update", &snapshot.disk);
+    let _ = app.emit("network-update", &snapshot.network);
+    let _ = app.emit("process-update", &snapshot.process);
+    let _ = app.emit("gpu-update", &snapshot.gpu);
+    let _ = app.emit("sensors-update", &snapshot.sensors);
+    let _ = app.emit("system-update", &snapshot.system);
+
+    // Start/exit deltas let the process pane update without the full list
+    let changes = state.process.take_changes();
+    if !changes.started.is_empty() {
+        let _ = app.emit("process-started", &changes.started);
+    }
+    if !changes.exited.is_empty() {
+        let _ = app.emit("process-exited", &changes.exited);
+    }
+
+    for (transition, event) in alerts {
+        let name = match transition {
+            modules::alerts::AlertTransition::Triggered => "alert-triggered",
+            modules::alerts::AlertTransition::Cleared => "alert-cleared",
+        };
+        let _ = app.emit(name, &event);
+    }
+}
+
+async fn monitoring_loop(app: AppHandle, interval: Duration, stop: Arc<AtomicBool>) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut throttle = TickThrottle::new();
+
+    loop {
+        ticker.tick().await;
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if !throttle.tick(app.state::<AppState>().active.load(Ordering::Relaxed)) {
+            continue;
+        }
+
+        let app_handle = app.clone();
+        if tokio::task::spawn_blocking(move || emit_all_updates(&app_handle))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Start (or restart) streaming all monitors every `interval_ms`
+#[tauri::command]
+fn start_monitoring(app: AppHandle, state: State<'_, AppState>, interval_ms: u64) -> Result<(), String> {
+    let interval = Duration::from_millis(interval_ms.max(MIN_STREAM_INTERVAL_MS));
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = tauri::async_runtime::spawn(monitoring_loop(app, interval, Arc::clone(&stop)));
+
+    let mut monitoring = state.monitoring.lock_or_recover("Monitoring task");
+    // Replace any running task instead of leaking it
+    if let Some(previous) = monitoring.replace(MonitoringTask { handle, stop }) {
+        previous.cancel();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_monitoring(state: State<'_, AppState>) -> Result<(), String> {
+    let mut monitoring = state.monitoring.lock_or_recover("Monitoring task");
+    if let Some(task) = monitoring.take() {
+        task.cancel();
+    }
+    Ok(())
+}
+
+/// Shortest polling interval (ms) that yields accurate readings from every monitor
+#[tauri::command]
+fn get_recommended_interval() -> u64 {
+    let monitor_min = modules::cpu::MIN_INTERVAL.max(modules::process::MIN_INTERVAL);
+    (monitor_min.as_millis() as u64).max(MIN_STREAM_INTERVAL_MS)
+}
+
+/// Tell the backend whether the window is visible; hidden windows stream 5x slower
+#[tauri::command]
+fn set_active(state: State<'_, AppState>, active: bool) {
+    state.active.store(active, Ordering::Relaxed);
+}
+
+// ============================================================================
+// Settings Commands
+// ============================================================================
+
+#[tauri::command]
+fn set_temperature_unit(state: State<'_, AppState>, unit: TemperatureUnit) {
+    *state.temperature_unit.lock_or_recover("Temperature unit") = unit;
+}
+
+/// Zero the network and disk since-start counters (e.g. before a benchmark)
+#[tauri::command]
+fn reset_counters(state: State<'_, AppState>) {
+    state.network.reset_counters();
+    state.disk.reset_counters();
+}
+
+// ============================================================================
+// Alert Commands (Evaluated by the streaming task)
+// ============================================================================
+
+#[tauri::command]
+fn add_alert_rule(
+    state: State<'_, AppState>,
+    rule: modules::alerts::AlertRule,
+) -> Result<(), String> {
+    state.alerts.add_rule(rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn remove_alert_rule(state: State<'_, AppState>, id: String) -> bool {
+    state.alerts.remove_rule(&id)
+}
+
+#[tauri::command]
+fn list_alert_rules(state: State<'_, AppState>) -> Vec<modules::alerts::AlertRule> {
+    state.alerts.list_rules()
+}
+
+// ============================================================================
+// Formatting Commands (Pure, no monitor state)
+// ============================================================================
+
+/// Byte count as "1.5 GB", or "1.4 GiB" when `binary`
+#[tauri::command]
+fn format_bytes(bytes: u64, binary: bool) -> String {
+    modules::format::format_bytes(bytes, binary)
+}
+
+/// Bytes per second as "12.3 MB/s", or "11.7 MiB/s" when `binary`
+#[tauri::command]
+fn format_rate(bps: f64, binary: bool) -> String {
+    modules::format::format_rate(bps, binary)
+}
+
 // ============================================================================
 // Application Entry Point
 // ============================================================================
@@ -182,23 +984,117 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // CPU
             get_cpu_info,
+            get_cpu_stats,
+            get_cpu_frequency_history,
+            get_cpu_usage_sampled,
             // Memory
             get_memory_info,
+            get_numa_info,
             // Disk
             get_disk_info,
+            get_physical_disks,
+            set_hidden_disks,
+            set_smart_cache_duration,
+            list_smart_devices,
+            run_smart_self_test,
+            get_smart_self_test_status,
+            run_fstrim,
+            mount_device,
+            unmount_device,
+            scan_directory,
             // Network
             get_network_info,
+            get_network_history,
+            get_connections,
+            get_socket_summary,
+            get_protocol_stats,
+            get_network_config,
+            set_hidden_interfaces,
             // Process
             get_processes,
+            get_top_processes,
             kill_process,
+            set_pinned_processes,
+            kill_by_name,
+            process_exists,
+            get_process_details,
             set_process_priority,
             // GPU
             get_gpu_info,
+            get_gpu_processes,
+            kill_gpu_process,
+            get_gpu_history,
+            set_gpu_fan_speed,
+            set_gpu_fan_auto,
+            set_gpu_power_limit,
+            reset_gpu_power_limit,
             // Sensors
             get_sensors_info,
+            get_sensors_history,
+            get_all_hwmon,
             // System
             get_system_info,
+            get_hardware_info,
+            get_logged_in_users,
+            get_kernel_modules,
+            get_battery_info,
+            system_reboot,
+            system_shutdown,
+            system_suspend,
+            get_capabilities,
+            get_boot_performance,
+            get_recent_logs,
+            // Snapshot
+            get_snapshot,
+            metrics_prometheus,
+            get_health_summary,
+            export_history,
+            // Streaming
+            start_monitoring,
+            stop_monitoring,
+            set_active,
+            get_recommended_interval,
+            // Settings
+            set_temperature_unit,
+            reset_counters,
+            // Alerts
+            add_alert_rule,
+            remove_alert_rule,
+            list_alert_rules,
+            // Formatting
+            format_bytes,
+            format_rate,
         ])
         .run(tauri::generate_context!())
         .expect("error while running Nova System Monitor");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_window_slows_streaming_from_the_next_tick() {
+        let mut throttle = TickThrottle::new();
+        let ticks = |throttle: &mut TickThrottle, active: bool, n: usize| -> Vec<bool> {
+            (0..n).map(|_| throttle.tick(active)).collect()
+        };
+
+        assert_eq!(ticks(&mut throttle, true, 3), [true, true, true]);
+        // Hidden: only every 5th tick refreshes
+        assert_eq!(
+            ticks(&mut throttle, false, 10),
+            [false, false, false, false, true, false, false, false, false, true]
+        );
+        assert_eq!(ticks(&mut throttle, false, 2), [false, false]);
+        // Shown again mid-way: no waiting for the slow cycle to finish
+        assert_eq!(ticks(&mut throttle, true, 2), [true, true]);
+
+        // Starting while hidden still refreshes once right away
+        let mut hidden_at_start = TickThrottle::new();
+        assert_eq!(
+            ticks(&mut hidden_at_start, false, 6),
+            [true, false, false, false, false, true]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+// Alerts Module
+// Threshold-based alert rules evaluated against each streamed snapshot
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::modules::{snapshot::Snapshot, LockRecover, MonitorError};
+
+/// How a metric is compared against the threshold
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Comparator {
+    GreaterThan,
+    LessThan,
+}
+
+impl Comparator {
+    fn breached(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::GreaterThan => value > threshold,
+            Comparator::LessThan => value < threshold,
+        }
+    }
+}
+
+/// A user-defined alert rule
+/// Metrics: cpu_usage, cpu_temp, gpu_temp, memory_percent, swap_percent,
+/// disk_usage_max, disk_usage:<mount point>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    pub id: String,
+    pub metric: String,
+    pub comparator: Comparator,
+    pub threshold: f64,
+    pub duration_secs: u64, // Condition must hold this long before the alert changes state
+}
+
+/// Payload of the alert-triggered / alert-cleared events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub value: Option<f64>,
+    pub timestamp: u64, // Unix seconds
+}
+
+/// Alert state change produced by a rule evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertTransition {
+    Triggered,
+    Cleared,
+}
+
+/// Per-rule debounce state
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleState {
+    active: bool,
+    pending_since: Option<u64>, // When the condition first disagreed with `active`
+}
+
+/// Evaluate one rule against the current value
+/// The alert only flips once the condition has disagreed with its current state for
+/// `duration_secs` in both directions, so brief spikes or dips don't flap
+pub fn evaluate_rule(
+    rule: &AlertRule,
+    value: Option<f64>,
+    state: &mut RuleState,
+    now: u64,
+) -> Option<AlertTransition> {
+    // A missing metric (e.g. no GPU) never breaches
+    let breached = value.is_some_and(|v| rule.comparator.breached(v, rule.threshold));
+
+    if breached == state.active {
+        state.pending_since = None;
+        return None;
+    }
+
+    let since = *state.pending_since.get_or_insert(now);
+    if now.saturating_sub(since) < rule.duration_secs {
+        return None;
+    }
+
+    state.active = breached;
+    state.pending_since = None;
+    Some(if breached {
+        AlertTransition::Triggered
+    } else {
+        AlertTransition::Cleared
+    })
+}
+
+/// Current value of a named metric in a snapshot
+pub fn metric_value(snapshot: &Snapshot, metric: &str) -> Option<f64> {
+    let percent = |used: u64, total: u64| (total > 0).then(|| used as f64 / total as f64 * 100.0);
+
+    match metric {
+        "cpu_usage" => Some(snapshot.cpu.global_usage as f64),
+        "cpu_temp" => snapshot.sensors.cpu_temp.map(|t| t as f64),
+        "gpu_temp" => snapshot.sensors.gpu_temp.map(|t| t as f64),
+        "memory_percent" => Some(snapshot.memory.memory_usage_percent as f64),
+        "swap_percent" => percent(snapshot.memory.used_swap, snapshot.memory.total_swap),
+        "disk_usage_max" => snapshot
+            .disk
+            .disks
+            .iter()
+            .map(|d| d.usage_percent as f64)
+            .reduce(f64::max),
+        _ => {
+            let mount_point = metric.strip_prefix("disk_usage:")?;
+            snapshot
+                .disk
+                .disks
+                .iter()
+                .find(|d| d.mount_point == mount_point)
+                .map(|d| d.usage_percent as f64)
+        }
+    }
+}
+
+fn is_known_metric(metric: &str) -> bool {
+    matches!(
+        metric,
+        "cpu_usage"
+            | "cpu_temp"
+            | "gpu_temp"
+            | "memory_percent"
+            | "swap_percent"
+            | "disk_usage_max"
+    ) || metric
+        .strip_prefix("disk_usage:")
+        .is_some_and(|mount| !mount.is_empty())
+}
+
+/// Alert rules and their debounce state
+pub struct AlertManager {
+    rules: RwLock<Vec<AlertRule>>,
+    states: RwLock<HashMap<String, RuleState>>,
+}
+
+impl AlertManager {
+    pub fn new() -> Self {
+        Self {
+            rules: RwLock::new(Vec::new()),
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Add a rule, replacing any existing rule with the same id
+    pub fn add_rule(&self, rule: AlertRule) -> Result<(), MonitorError> {
+        if !is_known_metric(&rule.metric) {
+            return Err(MonitorError::InvalidArgument(format!(
+                "Unknown alert metric '{}'",
+                rule.metric
+            )));
+        }
+
+        let mut rules = self.rules.write_or_recover("Alert rules");
+        rules.retain(|r| r.id != rule.id);
+        self.states
+            .write_or_recover("Alert states")
+            .remove(&rule.id);
+        rules.push(rule);
+        Ok(())
+    }
+
+    /// Remove a rule by id; returns false if it didn't exist
+    pub fn remove_rule(&self, id: &str) -> bool {
+        let mut rules = self.rules.write_or_recover("Alert rules");
+        let before = rules.len();
+        rules.retain(|r| r.id != id);
+        self.states
+            .write_or_recover("Alert states")
+            .remove(id);
+        rules.len() != before
+    }
+
+    pub fn list_rules(&self) -> Vec<AlertRule> {
+        self.rules
+            .read_or_recover("Alert rules")
+            .clone()
+    }
+
+    /// Evaluate all rules against a snapshot and return the alerts that changed state
+    pub fn evaluate(&self, snapshot: &Snapshot, now: u64) -> Vec<(AlertTransition, AlertEvent)> {
+        let rules = self.rules.read_or_recover("Alert rules");
+        let mut states = self.states.write_or_recover("Alert states");
+
+        rules
+            .iter()
+            .filter_map(|rule| {
+                let value = metric_value(snapshot, &rule.metric);
+                let state = states.entry(rule.id.clone()).or_default();
+                evaluate_rule(rule, value, state, now).map(|transition| {
+                    (
+                        transition,
+                        AlertEvent {
+                            rule: rule.clone(),
+                            value,
+                            timestamp: now,
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for AlertManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::disk::DiskInfo;
+
+    fn cpu_rule(duration_secs: u64) -> AlertRule {
+        AlertRule {
+            id: "hot-cpu".into(),
+            metric: "cpu_usage".into(),
+            comparator: Comparator::GreaterThan,
+            threshold: 90.0,
+            duration_secs,
+        }
+    }
+
+    #[test]
+    fn brief_spikes_do_not_trigger() {
+        let rule = cpu_rule(10);
+        let mut state = RuleState::default();
+        assert_eq!(evaluate_rule(&rule, Some(95.0), &mut state, 100), None);
+        assert_eq!(evaluate_rule(&rule, Some(95.0), &mut state, 105), None);
+        // Dropping back resets the pending window
+        assert_eq!(evaluate_rule(&rule, Some(50.0), &mut state, 108), None);
+        assert_eq!(evaluate_rule(&rule, Some(95.0), &mut state, 109), None);
+        assert_eq!(evaluate_rule(&rule, Some(95.0), &mut state, 118), None);
+        assert_eq!(
+            evaluate_rule(&rule, Some(95.0), &mut state, 119),
+            Some(AlertTransition::Triggered)
+        );
+        assert_eq!(evaluate_rule(&rule, Some(95.0), &mut state, 200), None);
+    }
+
+    #[test]
+    fn clearing_is_debounced_too() {
+        let rule = cpu_rule(5);
+        let mut state = RuleState {
+            active: true,
+            pending_since: None,
+        };
+        assert_eq!(evaluate_rule(&rule, Some(10.0), &mut state, 0), None);
+        assert_eq!(evaluate_rule(&rule, Some(99.0), &mut state, 3), None);
+        assert_eq!(evaluate_rule(&rule, Some(10.0), &mut state, 4), None);
+        assert_eq!(
+            evaluate_rule(&rule, Some(10.0), &mut state, 9),
+            Some(AlertTransition::Cleared)
+        );
+    }
+
+    #[test]
+    fn zero_duration_and_missing_metrics() {
+        let rule = cpu_rule(0);
+        let mut state = RuleState::default();
+        assert_eq!(
+            evaluate_rule(&rule, Some(91.0), &mut state, 0),
+            Some(AlertTransition::Triggered)
+        );
+        // A metric that disappears (e.g. unplugged GPU) counts as not breached
+        assert_eq!(
+            evaluate_rule(&rule, None, &mut state, 1),
+            Some(AlertTransition::Cleared)
+        );
+    }
+
+    #[test]
+    fn resolves_metrics_from_a_snapshot() {
+        let mut snapshot = Snapshot::default();
+        snapshot.memory.used_swap = 1;
+        snapshot.memory.total_swap = 4;
+        snapshot.disk.disks = vec![
+            DiskInfo {
+                mount_point: "/".into(),
+                usage_percent: 40.0,
+                ..Default::default()
+            },
+            DiskInfo {
+                mount_point: "/home".into(),
+                usage_percent: 80.0,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(metric_value(&snapshot, "swap_percent"), Some(25.0));
+        assert_eq!(metric_value(&snapshot, "disk_usage_max"), Some(80.0));
+        assert_eq!(metric_value(&snapshot, "disk_usage:/"), Some(40.0));
+        assert_eq!(metric_value(&snapshot, "disk_usage:/mnt"), None);
+        assert_eq!(metric_value(&snapshot, "gpu_temp"), None);
+
+        let manager = AlertManager::new();
+        assert!(matches!(
+            manager.add_rule(AlertRule {
+                metric: "disk_usage:".into(),
+                ..cpu_rule(0)
+            }),
+            Err(MonitorError::InvalidArgument(_))
+        ));
+        manager
+            .add_rule(AlertRule {
+                metric: "disk_usage_max".into(),
+                threshold: 75.0,
+                ..cpu_rule(0)
+            })
+            .unwrap();
+        let events = manager.evaluate(&snapshot, 42);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, AlertTransition::Triggered);
+        assert_eq!(events[0].1.value, Some(80.0));
+    }
+}
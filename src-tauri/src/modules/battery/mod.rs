@@ -0,0 +1,204 @@
+// Battery Module
+// Provides charge level, charging state and health from /sys/class/power_supply
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::modules::MonitorError;
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+/// Battery status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryInfo {
+    pub percent: f32,
+    pub state: String, // Charging, Discharging, Full, Not charging, Unknown
+    pub time_to_empty_secs: Option<u64>,
+    pub time_to_full_secs: Option<u64>,
+    pub power_draw_watts: Option<f32>,
+    pub cycle_count: Option<u32>,
+    pub health_percent: Option<f32>,
+}
+
+/// Read the first battery (BAT*)
+/// Machines without a battery get Unsupported; unreadable sysfs gets Io
+pub fn get_battery_info() -> Result<BatteryInfo, MonitorError> {
+    read_first_battery(Path::new(POWER_SUPPLY_PATH))
+}
+
+/// Read the first BAT* entry under a power_supply class directory
+fn read_first_battery(power_supply: &Path) -> Result<BatteryInfo, MonitorError> {
+    let entries = fs::read_dir(power_supply).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => {
+            MonitorError::Unsupported("No power supply information available".to_string())
+        }
+        _ => MonitorError::Io(format!("Failed to read {}: {}", power_supply.display(), e)),
+    })?;
+
+    let mut batteries: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("BAT"))
+        .map(|e| e.path())
+        .collect();
+    batteries.sort();
+
+    if batteries.is_empty() {
+        return Err(MonitorError::Unsupported("No battery found".to_string()));
+    }
+
+    batteries
+        .iter()
+        .find_map(|dir| read_battery(dir))
+        .ok_or_else(|| MonitorError::Io("Failed to read battery capacity".to_string()))
+}
+
+/// Parse a single power_supply battery directory
+pub fn read_battery(dir: &Path) -> Option<BatteryInfo> {
+    let read_string = |name: &str| -> Option<String> {
+        fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|s| s.trim().to_string())
+    };
+    let read_number = |name: &str| -> Option<f64> { read_string(name)?.parse().ok() };
+
+    let state = read_string("status").unwrap_or_else(|| "Unknown".to_string());
+
+    // Some drivers report charge (µAh/µA) instead of energy (µWh/µW); the ratios are the same
+    let now = read_number("energy_now").or_else(|| read_number("charge_now"));
+    let full = read_number("energy_full").or_else(|| read_number("charge_full"));
+    let full_design =
+        read_number("energy_full_design").or_else(|| read_number("charge_full_design"));
+    let rate = read_number("power_now")
+        .or_else(|| read_number("current_now"))
+        .filter(|r| *r > 0.0);
+
+    let percent = match read_number("capacity") {
+        Some(capacity) => capacity as f32,
+        None => match (now, full) {
+            (Some(now), Some(full)) if full > 0.0 => (now / full * 100.0) as f32,
+            _ => return None,
+        },
+    };
+
+    // Remaining time in hours = charge left (or missing) / rate
+    let hours_to_secs = |hours: f64| (hours * 3600.0) as u64;
+    let time_to_empty_secs = match (state.as_str(), now, rate) {
+        ("Discharging", Some(now), Some(rate)) => Some(hours_to_secs(now / rate)),
+        _ => None,
+    };
+    let time_to_full_secs = match (state.as_str(), now, full, rate) {
+        ("Charging", Some(now), Some(full), Some(rate)) => {
+            Some(hours_to_secs((full - now).max(0.0) / rate))
+        }
+        _ => None,
+    };
+
+    // power_now is in µW; current_now would need voltage to convert
+    let power_draw_watts = read_number("power_now")
+        .or_else(|| {
+            let current = read_number("current_now")?;
+            let voltage = read_number("voltage_now")?;
+            Some(current * voltage / 1_000_000.0)
+        })
+        .map(|uw| (uw / 1_000_000.0) as f32);
+
+    let health_percent = match (full, full_design) {
+        (Some(full), Some(design)) if design > 0.0 => Some((full / design * 100.0) as f32),
+        _ => None,
+    };
+
+    Some(BatteryInfo {
+        percent,
+        state,
+        time_to_empty_secs,
+        time_to_full_secs,
+        power_draw_watts,
+        cycle_count: read_number("cycle_count").map(|c| c as u32),
+        health_percent,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ScratchDir;
+
+    fn fake_battery(files: &[(&str, &str)]) -> ScratchDir {
+        let dir = ScratchDir::new("battery");
+        for (name, contents) in files {
+            dir.write(name, &format!("{}\n", contents));
+        }
+        dir
+    }
+
+    #[test]
+    fn reads_energy_based_battery() {
+        let dir = fake_battery(&[
+            ("status", "Discharging"),
+            ("capacity", "50"),
+            ("energy_now", "25000000"),
+            ("energy_full", "50000000"),
+            ("energy_full_design", "62500000"),
+            ("power_now", "12500000"),
+            ("cycle_count", "321"),
+        ]);
+        let info = read_battery(&dir).unwrap();
+
+        assert_eq!(info.percent, 50.0);
+        assert_eq!(info.state, "Discharging");
+        assert_eq!(info.time_to_empty_secs, Some(7200));
+        assert_eq!(info.time_to_full_secs, None);
+        assert_eq!(info.power_draw_watts, Some(12.5));
+        assert_eq!(info.cycle_count, Some(321));
+        assert_eq!(info.health_percent, Some(80.0));
+    }
+
+    #[test]
+    fn falls_back_to_charge_files_without_capacity() {
+        let dir = fake_battery(&[
+            ("status", "Charging"),
+            ("charge_now", "3000000"),
+            ("charge_full", "4000000"),
+            ("current_now", "2000000"),
+        ]);
+        let info = read_battery(&dir).unwrap();
+
+        assert_eq!(info.percent, 75.0);
+        assert_eq!(info.time_to_full_secs, Some(1800));
+        assert_eq!(info.time_to_empty_secs, None);
+        assert_eq!(info.power_draw_watts, None);
+        assert_eq!(info.health_percent, None);
+    }
+
+    #[test]
+    fn rejects_battery_without_charge_level() {
+        let dir = fake_battery(&[("status", "Unknown")]);
+        assert!(read_battery(&dir).is_none());
+    }
+
+    #[test]
+    fn reports_missing_batteries_as_unsupported() {
+        let scratch = ScratchDir::new("power-supply");
+        let root = scratch.join("power_supply");
+
+        let err = read_first_battery(&root).unwrap_err();
+        assert!(matches!(err, MonitorError::Unsupported(_)));
+
+        // Mains adapter only, as on a desktop
+        fs::create_dir_all(root.join("AC")).unwrap();
+        let err = read_first_battery(&root).unwrap_err();
+        assert_eq!(err.to_string(), "Not supported on this system: No battery found");
+
+        // A battery whose charge level can't be read is an I/O failure, not "no battery"
+        fs::create_dir_all(root.join("BAT0")).unwrap();
+        let err = read_first_battery(&root).unwrap_err();
+        assert!(matches!(err, MonitorError::Io(_)));
+        assert_eq!(err.to_string(), "I/O error: Failed to read battery capacity");
+
+        fs::write(root.join("BAT0").join("capacity"), "88\n").unwrap();
+        assert_eq!(read_first_battery(&root).unwrap().percent, 88.0);
+    }
+}
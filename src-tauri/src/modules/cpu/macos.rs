@@ -0,0 +1,100 @@
+// macOS CPU backend
+// Fills in CPU details sysinfo leaves empty on macOS using sysctl
+
+use std::ffi::CString;
+use libc::c_void;
+
+use super::CpuInfo;
+
+/// Read a string sysctl (e.g. machdep.cpu.brand_string)
+fn sysctl_string(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut len: libc::size_t = 0;
+    // First call sizes the buffer
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 || len == 0 {
+        return None;
+    }
+
+    let mut buf = vec![0u8; len];
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            buf.as_mut_ptr() as *mut c_void,
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+
+    buf.truncate(len);
+    let value = String::from_utf8_lossy(&buf)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Read an integer sysctl (e.g. hw.physicalcpu); handles 32 and 64-bit values
+fn sysctl_u64(name: &str) -> Option<u64> {
+    let name = CString::new(name).ok()?;
+    let mut value: u64 = 0;
+    let mut len: libc::size_t = std::mem::size_of::<u64>();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut u64 as *mut c_void,
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    match (ret, len) {
+        (0, 8) => Some(value),
+        (0, 4) => Some(value as u32 as u64),
+        _ => None,
+    }
+}
+
+/// Fill fields sysinfo couldn't provide; anything still unavailable stays empty/0
+pub fn fill_cpu_info(mut info: CpuInfo) -> CpuInfo {
+    if info.brand.is_empty() {
+        info.brand = sysctl_string("machdep.cpu.brand_string").unwrap_or_default();
+    }
+    if info.vendor.is_empty() {
+        // Apple Silicon has no machdep.cpu.vendor
+        info.vendor = sysctl_string("machdep.cpu.vendor").unwrap_or_else(|| {
+            if cfg!(target_arch = "aarch64") {
+                "Apple".to_string()
+            } else {
+                String::new()
+            }
+        });
+    }
+    if info.physical_cores == 0 {
+        info.physical_cores = sysctl_u64("hw.physicalcpu").unwrap_or(0) as usize;
+    }
+
+    // hw.cpufrequency only exists on Intel Macs; Apple Silicon keeps 0 like other
+    // platforms without frequency data
+    if info.cores.iter().any(|c| c.frequency == 0) {
+        if let Some(hz) = sysctl_u64("hw.cpufrequency") {
+            for core in info.cores.iter_mut().filter(|c| c.frequency == 0) {
+                core.frequency = hz / 1_000_000;
+            }
+        }
+    }
+
+    info
+}
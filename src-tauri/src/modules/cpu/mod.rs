@@ -1,20 +1,34 @@
 // CPU Monitoring Module
 // Provides CPU usage, frequency, and per-core statistics
 
+#[cfg(target_os = "macos")]
+mod macos;
+mod rapl;
+mod times;
+
 use serde::{Deserialize, Serialize};
-use sysinfo::System;
-use std::sync::RwLock;
+use sysinfo::{System, MINIMUM_CPU_UPDATE_INTERVAL};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::modules::{LockRecover, TemperatureUnit};
 
 /// CPU information for a single core
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CpuCore {
     pub name: String,
     pub usage: f32,
     pub frequency: u64, // MHz
+    pub turbo_active: bool, // Running above base frequency (or its median, without one)
 }
 
 /// Overall CPU information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CpuInfo {
     pub name: String,
     pub vendor: String,
@@ -22,54 +36,453 @@ pub struct CpuInfo {
     pub physical_cores: usize,
     pub logical_cores: usize,
     pub global_usage: f32,
+    pub global_usage_procstat: f32, // From /proc/stat deltas; valid on the first refresh too
     pub cores: Vec<CpuCore>,
+    pub core_temps: Vec<Option<f32>>, // Celsius, indexed like `cores`; None without a per-core sensor
+    pub power: PowerInfo,
+    pub times: CoreTimes, // All CPUs combined
+    pub core_times: Vec<CoreTimes>, // Per logical CPU, in /proc/stat order
+}
+
+/// Share of CPU time per state since the previous sample, in percent (sums to ~100)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreTimes {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32, // Idle while waiting on I/O; high values point at slow storage
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32, // Taken by the hypervisor for other guests
+}
+
+impl CoreTimes {
+    /// Percent of time not idle (iowait counts as idle, as in top); 0 without a sample
+    pub fn busy(&self) -> f32 {
+        (self.user + self.nice + self.system + self.irq + self.softirq + self.steal)
+            .clamp(0.0, 100.0)
+    }
+}
+
+/// CPU power draw averaged since the previous refresh (RAPL)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerInfo {
+    pub package_watts: Option<f32>, // Summed over all sockets
+    pub core_watts: Option<f32>,
+    pub dram_watts: Option<f32>,
+}
+
+impl CpuInfo {
+    /// Render the per-core temperatures in the given unit
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        for temp in self.core_temps.iter_mut().flatten() {
+            *temp = unit.convert(*temp);
+        }
+        self
+    }
+}
+
+/// Summary of global CPU usage over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuStats {
+    pub avg: f32,
+    pub min: f32,
+    pub max: f32,
+    pub p95: f32,
+    pub sample_count: usize,
+    pub effective_window_secs: u64, // Less than requested when history is shorter
+}
+
+/// CPU usage measured over one explicit window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuUsageSample {
+    pub global_usage: f32,
+    pub core_usage: Vec<f32>, // Per logical CPU
+    pub window_ms: u64,       // Actual window, after clamping
 }
 
 /// CPU Monitor state
 pub struct CpuMonitor {
     system: RwLock<System>,
+    history: RwLock<VecDeque<(u64, f32)>>, // (timestamp ms, global usage)
+    frequency_history: RwLock<VecDeque<(u64, Vec<u64>)>>, // (timestamp ms, MHz per core)
+    base_frequencies: OnceLock<Vec<Option<u64>>>, // MHz per logical CPU
+    topology: OnceLock<Vec<Option<(u32, u32)>>>, // (package, core id) per logical CPU
+    last_sample: RwLock<Instant>, // When CPU times were last sampled
+    rapl: RwLock<rapl::RaplSampler>,
+    times: RwLock<times::CpuTimesSampler>,
 }
 
+// Number of global usage samples kept in history
+const HISTORY_LEN: usize = 60;
+
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+// Headroom over the reference frequency before a core counts as boosting (5%)
+const TURBO_MARGIN: f64 = 1.05;
+
+// Minimum time between CPU samples; usage over shorter spans is 0 or noisy
+pub const MIN_INTERVAL: Duration = MINIMUM_CPU_UPDATE_INTERVAL;
+
+// Longest window sample_usage blocks for (5 seconds)
+const MAX_SAMPLE_WINDOW: Duration = Duration::from_secs(5);
+
 impl CpuMonitor {
     pub fn new() -> Self {
         let mut system = System::new();
         system.refresh_cpu_all();
+        // Baseline jiffies, so the first refresh already has an interval to compare against
+        let mut times = times::CpuTimesSampler::default();
+        times.sample();
         Self {
             system: RwLock::new(system),
+            history: RwLock::new(VecDeque::with_capacity(HISTORY_LEN)),
+            frequency_history: RwLock::new(VecDeque::with_capacity(HISTORY_LEN)),
+            base_frequencies: OnceLock::new(),
+            topology: OnceLock::new(),
+            last_sample: RwLock::new(Instant::now()),
+            rapl: RwLock::new(rapl::RaplSampler::default()),
+            times: RwLock::new(times),
+        }
+    }
+
+    /// Global usage history as (timestamp ms, usage percent), oldest first
+    pub fn history(&self) -> Vec<(u64, f32)> {
+        self.history
+            .read_or_recover("CPU history")
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Per-core frequency history as (timestamp ms, MHz per core), oldest first
+    pub fn frequency_history(&self) -> Vec<(u64, Vec<u64>)> {
+        self.frequency_history
+            .read_or_recover("CPU frequency history")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Usage statistics over the last `window_secs` of history
+    pub fn stats(&self, window_secs: u64) -> CpuStats {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let cutoff = now.saturating_sub(window_secs.saturating_mul(1000));
+
+        let history = self.history.read_or_recover("CPU history");
+        let window: Vec<(u64, f32)> = history
+            .iter()
+            .copied()
+            .filter(|(timestamp, _)| *timestamp >= cutoff)
+            .collect();
+        drop(history);
+
+        let effective_window_secs = window
+            .first()
+            .map(|(oldest, _)| (now.saturating_sub(*oldest) / 1000).min(window_secs))
+            .unwrap_or(0);
+
+        let mut values: Vec<f32> = window.iter().map(|(_, usage)| *usage).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let (avg, min, max, p95) = if values.is_empty() {
+            (0.0, 0.0, 0.0, 0.0)
+        } else {
+            let n = values.len();
+            // Nearest-rank percentile: the smallest value with at least 95% of samples at or below it
+            let p95_rank = ((n as f64 * 0.95).ceil() as usize).clamp(1, n);
+            (
+                values.iter().sum::<f32>() / n as f32,
+                values[0],
+                values[n - 1],
+                values[p95_rank - 1],
+            )
+        };
+
+        CpuStats {
+            avg,
+            min,
+            max,
+            p95,
+            sample_count: values.len(),
+            effective_window_secs,
         }
     }
 
+    fn record_history(&self, global_usage: f32, frequencies: Vec<u64>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let mut history = self.history.write_or_recover("CPU history");
+        if history.len() >= HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back((timestamp, global_usage));
+        drop(history);
+
+        let mut frequency_history =
+            self.frequency_history.write_or_recover("CPU frequency history");
+        if frequency_history.len() >= HISTORY_LEN {
+            frequency_history.pop_front();
+        }
+        frequency_history.push_back((timestamp, frequencies));
+    }
+
+    /// Median frequency of each core over the history, the turbo reference for cores
+    /// without a known base frequency
+    fn median_frequencies(&self, core_count: usize) -> Vec<Option<u64>> {
+        let history = self.frequency_history.read_or_recover("CPU frequency history");
+        (0..core_count)
+            .map(|core| {
+                let mut values: Vec<u64> = history
+                    .iter()
+                    .filter_map(|(_, frequencies)| frequencies.get(core).copied())
+                    .filter(|&mhz| mhz > 0)
+                    .collect();
+                values.sort_unstable();
+                values.get(values.len() / 2).copied()
+            })
+            .collect()
+    }
+
     pub fn refresh(&self) -> CpuInfo {
-        let mut sys = self.system.write()
-            .expect("CPU monitor RwLock poisoned - this is a fatal error");
-        sys.refresh_cpu_all();
+        let mut sys = self.system.write_or_recover("CPU monitor");
+        let mut last_sample = self.last_sample.write_or_recover("CPU sample");
+
+        // Refreshes arriving within MIN_INTERVAL reuse the previous sample, except the very
+        // first one, which waits out the rest of the interval after the baseline from new()
+        let elapsed = last_sample.elapsed();
+        let first_reading = self.history
+            .read_or_recover("CPU history")
+            .is_empty();
+        let resample = elapsed >= MIN_INTERVAL || first_reading;
+        if resample {
+            if elapsed < MIN_INTERVAL {
+                std::thread::sleep(MIN_INTERVAL - elapsed);
+            }
+            sys.refresh_cpu_all();
+            *last_sample = Instant::now();
+        }
+        drop(last_sample);
         
         let cpus = sys.cpus();
+        let global_usage = if !cpus.is_empty() {
+            cpus.iter().map(|c| c.cpu_usage()).sum::<f32>() / cpus.len() as f32
+        } else {
+            0.0
+        };
+
+        if resample {
+            self.record_history(global_usage, cpus.iter().map(|c| c.frequency()).collect());
+        }
+
+        let base_frequencies = self.base_frequencies.get_or_init(|| {
+            read_base_frequencies(cpus.len(), cpus.first().map(|c| c.brand()).unwrap_or_default())
+        });
+        let median_frequencies = self.median_frequencies(cpus.len());
         let cores: Vec<CpuCore> = cpus
             .iter()
-            .map(|cpu| CpuCore {
+            .enumerate()
+            .map(|(i, cpu)| CpuCore {
                 name: cpu.name().to_string(),
                 usage: cpu.cpu_usage(),
                 frequency: cpu.frequency(),
+                turbo_active: turbo_active(
+                    cpu.frequency(),
+                    base_frequencies.get(i).copied().flatten(),
+                    median_frequencies.get(i).copied().flatten(),
+                ),
             })
             .collect();
 
-        let global_usage = if !cores.is_empty() {
-            cores.iter().map(|c| c.usage).sum::<f32>() / cores.len() as f32
-        } else {
-            0.0
+        // Power and the time breakdown are sampled alongside CPU usage, so all of them
+        // cover the same interval
+        let power = {
+            let mut rapl = self.rapl.write_or_recover("CPU power");
+            if resample {
+                rapl.sample()
+            } else {
+                rapl.last()
+            }
         };
+        let (times, core_times) = {
+            let mut sampler = self.times.write_or_recover("CPU times");
+            if resample {
+                sampler.sample()
+            } else {
+                sampler.last()
+            }
+        };
+
+        // Sibling hyperthreads share a physical core, and with it the core's sensor
+        let topology = self.topology.get_or_init(|| read_topology(cores.len()));
+        let core_temps = align_core_temps(topology, &read_hwmon_core_temps(Path::new(HWMON_PATH)));
 
-        CpuInfo {
+        let info = CpuInfo {
             name: cpus.first().map(|c| c.name().to_string()).unwrap_or_default(),
             vendor: cpus.first().map(|c| c.vendor_id().to_string()).unwrap_or_default(),
             brand: cpus.first().map(|c| c.brand().to_string()).unwrap_or_default(),
             physical_cores: sys.physical_core_count().unwrap_or(0),
             logical_cores: cpus.len(),
             global_usage,
+            global_usage_procstat: times.busy(),
             cores,
+            core_temps,
+            power,
+            times,
+            core_times,
+        };
+
+        #[cfg(target_os = "macos")]
+        let info = macos::fill_cpu_info(info);
+
+        info
+    }
+}
+
+/// CPU usage over a window of `window_ms` (clamped to MIN_INTERVAL..=MAX_SAMPLE_WINDOW),
+/// blocking for that long. A private System takes both samples, so the result doesn't
+/// depend on when CpuMonitor last refreshed, and its history is left untouched.
+pub fn sample_usage(window_ms: u64) -> CpuUsageSample {
+    let window = sample_window(window_ms);
+
+    let mut system = System::new();
+    system.refresh_cpu_usage();
+    std::thread::sleep(window);
+    system.refresh_cpu_usage();
+
+    let core_usage: Vec<f32> = system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+    CpuUsageSample {
+        global_usage: system.global_cpu_usage(),
+        core_usage,
+        window_ms: window.as_millis() as u64,
+    }
+}
+
+/// Requested sampling window, clamped to MIN_INTERVAL..=MAX_SAMPLE_WINDOW
+fn sample_window(window_ms: u64) -> Duration {
+    Duration::from_millis(window_ms).clamp(MIN_INTERVAL, MAX_SAMPLE_WINDOW)
+}
+
+/// (physical package, core id) for each logical CPU from sysfs topology
+fn read_topology(logical_cores: usize) -> Vec<Option<(u32, u32)>> {
+    (0..logical_cores)
+        .map(|cpu| {
+            let dir = Path::new("/sys/devices/system/cpu").join(format!("cpu{}/topology", cpu));
+            let read = |file: &str| -> Option<u32> {
+                fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()
+            };
+            Some((read("physical_package_id").unwrap_or(0), read("core_id")?))
+        })
+        .collect()
+}
+
+/// Base (non-turbo) frequency in MHz for each logical CPU
+/// intel_pstate and amd-pstate expose cpufreq/base_frequency (kHz); other drivers don't, so
+/// the "@ 3.60GHz" in Intel brand strings is used for every core instead
+fn read_base_frequencies(logical_cores: usize, brand: &str) -> Vec<Option<u64>> {
+    let brand_mhz = parse_brand_base_mhz(brand);
+    (0..logical_cores)
+        .map(|cpu| {
+            let path = format!("/sys/devices/system/cpu/cpu{}/cpufreq/base_frequency", cpu);
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|khz| khz.trim().parse::<u64>().ok())
+                .map(|khz| khz / 1000)
+                .or(brand_mhz)
+        })
+        .collect()
+}
+
+/// Base frequency from a brand string like "Intel(R) Core(TM) i7-7700 CPU @ 3.60GHz"
+fn parse_brand_base_mhz(brand: &str) -> Option<u64> {
+    let (_, frequency) = brand.rsplit_once('@')?;
+    let ghz: f64 = frequency.trim().strip_suffix("GHz")?.trim().parse().ok()?;
+    Some((ghz * 1000.0).round() as u64)
+}
+
+/// Whether a core is boosting: TURBO_MARGIN above its base frequency, or above its median
+/// frequency when the base is unknown; never without a frequency reading
+fn turbo_active(frequency: u64, base: Option<u64>, median: Option<u64>) -> bool {
+    match base.or(median) {
+        Some(reference) if frequency > 0 && reference > 0 => {
+            frequency as f64 > reference as f64 * TURBO_MARGIN
+        }
+        _ => false,
+    }
+}
+
+/// Temperature of each logical CPU's physical core, indexed like the topology
+fn align_core_temps(
+    topology: &[Option<(u32, u32)>],
+    temps: &HashMap<(u32, u32), f32>,
+) -> Vec<Option<f32>> {
+    topology
+        .iter()
+        .map(|key| key.and_then(|key| temps.get(&key).copied()))
+        .collect()
+}
+
+/// Per-core temperatures from hwmon "Core N" sensors (coretemp), keyed by (package, core id)
+/// coretemp registers one hwmon device per package (coretemp.<package>); k10temp only
+/// exposes Tctl/Tccd sensors, so AMD CPUs produce no entries
+fn read_hwmon_core_temps(hwmon_root: &Path) -> HashMap<(u32, u32), f32> {
+    let mut temps = HashMap::new();
+    let Ok(hwmons) = fs::read_dir(hwmon_root) else {
+        return temps;
+    };
+
+    for hwmon in hwmons.flatten() {
+        let dir = hwmon.path();
+        let package = fs::read_link(dir.join("device"))
+            .ok()
+            .and_then(|device| {
+                let name = device.file_name()?.to_string_lossy().to_string();
+                name.strip_prefix("coretemp.")?.parse().ok()
+            })
+            .unwrap_or(0);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(prefix) = file_name
+                .strip_suffix("_label")
+                .filter(|p| p.starts_with("temp"))
+            else {
+                continue;
+            };
+            let Some(core_id) = fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|label| parse_core_label(&label))
+            else {
+                continue;
+            };
+            let millidegrees = fs::read_to_string(dir.join(format!("{}_input", prefix)))
+                .ok()
+                .and_then(|v| v.trim().parse::<f32>().ok());
+            if let Some(millidegrees) = millidegrees {
+                temps.insert((package, core_id), millidegrees / 1000.0);
+            }
         }
     }
+    temps
+}
+
+/// Core id from a hwmon label like "Core 3"
+fn parse_core_label(label: &str) -> Option<u32> {
+    label.trim().strip_prefix("Core ")?.trim().parse().ok()
 }
 
 impl Default for CpuMonitor {
@@ -77,3 +490,215 @@ impl Default for CpuMonitor {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ScratchDir;
+
+    fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    #[test]
+    fn stats_summarize_the_requested_window() {
+        let monitor = CpuMonitor::new();
+        let now = now_ms();
+        {
+            let mut history = monitor.history.write().unwrap();
+            // Outside a 60 s window; must not affect any statistic
+            history.push_back((now - 120_000, 100.0));
+            // 1..=20 % in shuffled order, one sample per second from 29 s to 10 s ago
+            for i in 0..20u64 {
+                history.push_back((now - 29_000 + i * 1000, ((i * 7) % 20 + 1) as f32));
+            }
+        }
+
+        let stats = monitor.stats(60);
+        assert_eq!(stats.sample_count, 20);
+        assert_eq!(stats.avg, 10.5);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 20.0);
+        // Nearest rank: ceil(0.95 * 20) = 19th smallest
+        assert_eq!(stats.p95, 19.0);
+        assert_eq!(stats.effective_window_secs, 29);
+
+        let stats = monitor.stats(300);
+        assert_eq!(stats.sample_count, 21);
+        assert_eq!(stats.p95, 20.0);
+        assert_eq!(stats.effective_window_secs, 120);
+    }
+
+    #[test]
+    fn stats_over_empty_history_are_zero() {
+        let monitor = CpuMonitor::new();
+        let stats = monitor.stats(60);
+        assert_eq!(stats.sample_count, 0);
+        assert_eq!(stats.avg, 0.0);
+        assert_eq!(stats.p95, 0.0);
+        assert_eq!(stats.effective_window_secs, 0);
+    }
+
+    #[test]
+    fn aligns_coretemp_sensors_with_logical_cpus() {
+        use std::os::unix::fs::symlink;
+
+        let root = ScratchDir::new("coretemp");
+        // Two packages, each its own coretemp hwmon; package 1 only has Core 0 wired up
+        for (hwmon, package, sensors) in [
+            (
+                "hwmon2",
+                0,
+                &[
+                    ("temp1", "Package id 0", 60000),
+                    ("temp2", "Core 0", 51000),
+                    ("temp3", "Core 1", 53500),
+                ][..],
+            ),
+            ("hwmon3", 1, &[("temp2", "Core 0", 47000)][..]),
+        ] {
+            let dir = root.join(hwmon);
+            fs::create_dir_all(&dir).unwrap();
+            symlink(
+                format!("../../devices/platform/coretemp.{}", package),
+                dir.join("device"),
+            )
+            .unwrap();
+            for (prefix, label, millidegrees) in sensors {
+                fs::write(
+                    dir.join(format!("{}_label", prefix)),
+                    format!("{}\n", label),
+                )
+                .unwrap();
+                fs::write(
+                    dir.join(format!("{}_input", prefix)),
+                    format!("{}\n", millidegrees),
+                )
+                .unwrap();
+            }
+        }
+
+        let temps = read_hwmon_core_temps(&root);
+        assert_eq!(temps.len(), 3);
+        assert_eq!(temps.get(&(0, 1)), Some(&53.5));
+
+        // Hyperthread siblings (cpu0/cpu2, cpu1/cpu3) share their core's reading
+        let topology = [
+            Some((0, 0)),
+            Some((0, 1)),
+            Some((0, 0)),
+            Some((0, 1)),
+            Some((1, 0)),
+            Some((1, 1)),
+            None,
+        ];
+        assert_eq!(
+            align_core_temps(&topology, &temps),
+            [
+                Some(51.0),
+                Some(53.5),
+                Some(51.0),
+                Some(53.5),
+                Some(47.0),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn rapid_refreshes_reuse_the_previous_sample() {
+        let created = Instant::now();
+        let monitor = CpuMonitor::new();
+
+        // The first refresh waits out MIN_INTERVAL after the baseline taken in new()
+        monitor.refresh();
+        assert!(created.elapsed() >= MIN_INTERVAL);
+        assert_eq!(monitor.history().len(), 1);
+
+        let before = Instant::now();
+        monitor.refresh();
+        assert!(before.elapsed() < MIN_INTERVAL);
+        assert_eq!(monitor.history().len(), 1);
+
+        std::thread::sleep(MIN_INTERVAL);
+        monitor.refresh();
+        assert_eq!(monitor.history().len(), 2);
+    }
+
+    #[test]
+    fn procstat_usage_is_valid_on_the_first_refresh() {
+        let monitor = CpuMonitor::new();
+        let info = monitor.refresh();
+
+        // new() took the baseline, so the first refresh already covers a full interval
+        let times = info.times;
+        let total = times.user
+            + times.nice
+            + times.system
+            + times.idle
+            + times.iowait
+            + times.irq
+            + times.softirq
+            + times.steal;
+        assert!((total - 100.0).abs() < 0.1, "CPU times sum to {}", total);
+        assert_eq!(info.global_usage_procstat, times.busy());
+        assert_eq!(info.core_times.len(), info.logical_cores);
+    }
+
+    #[test]
+    fn turbo_is_relative_to_base_or_median_frequency() {
+        // 5% headroom over the 3600 MHz base
+        assert!(turbo_active(4200, Some(3600), None));
+        assert!(!turbo_active(3780, Some(3600), None));
+        assert!(turbo_active(3781, Some(3600), None));
+        // A known base wins over the median
+        assert!(!turbo_active(3000, Some(3600), Some(2000)));
+        assert!(turbo_active(2500, None, Some(2000)));
+        assert!(!turbo_active(2000, None, Some(2000)));
+        // No reference or no reading
+        assert!(!turbo_active(4200, None, None));
+        assert!(!turbo_active(0, Some(3600), None));
+        assert!(!turbo_active(4200, Some(0), None));
+    }
+
+    #[test]
+    fn parses_base_frequency_from_brand() {
+        assert_eq!(parse_brand_base_mhz("Intel(R) Core(TM) i7-7700 CPU @ 3.60GHz"), Some(3600));
+        assert_eq!(parse_brand_base_mhz("Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40 GHz"), Some(2400));
+        assert_eq!(parse_brand_base_mhz("AMD Ryzen 9 7950X 16-Core Processor"), None);
+    }
+
+    #[test]
+    fn serializes_camel_case_field_names() {
+        let json = serde_json::to_value(CpuInfo::default()).unwrap();
+        for key in [
+            "physicalCores",
+            "logicalCores",
+            "globalUsage",
+            "globalUsageProcstat",
+            "coreTemps",
+            "coreTimes",
+        ] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+        assert!(json["power"].get("packageWatts").is_some());
+        assert!(json["times"].get("softirq").is_some());
+        assert!(json.get("global_usage").is_none());
+    }
+
+    #[test]
+    fn sample_window_is_clamped() {
+        assert_eq!(sample_window(0), MIN_INTERVAL);
+        assert_eq!(sample_window(1000), Duration::from_secs(1));
+        assert_eq!(sample_window(60_000), MAX_SAMPLE_WINDOW);
+
+        let sample = sample_usage(0);
+        assert_eq!(sample.window_ms, MIN_INTERVAL.as_millis() as u64);
+        assert!((0.0..=100.0).contains(&sample.global_usage));
+        assert!(!sample.core_usage.is_empty());
+    }
+}
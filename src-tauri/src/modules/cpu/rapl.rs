@@ -0,0 +1,148 @@
+// CPU RAPL backend
+// Derives CPU package/core/DRAM power from powercap energy counters (Intel, and AMD Zen
+// through the same intel-rapl interface)
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use super::PowerInfo;
+
+const POWERCAP_PATH: &str = "/sys/class/powercap";
+
+/// Previous energy reading of one RAPL domain
+struct EnergySample {
+    energy_uj: u64,
+    taken_at: Instant,
+}
+
+/// Energy counters from the last refresh, keyed by powercap zone (intel-rapl:0:1)
+#[derive(Default)]
+pub struct RaplSampler {
+    samples: HashMap<String, EnergySample>,
+    last: PowerInfo,
+}
+
+impl RaplSampler {
+    /// Power since the previous call; every field is None on the first call, without
+    /// RAPL support, or when energy_uj is root-only (the default since kernel 5.10)
+    pub fn sample(&mut self) -> PowerInfo {
+        self.last = self.read_power();
+        self.last.clone()
+    }
+
+    /// Result of the latest sample, for refreshes that don't take a new one
+    pub fn last(&self) -> PowerInfo {
+        self.last.clone()
+    }
+
+    fn read_power(&mut self) -> PowerInfo {
+        let mut power = PowerInfo::default();
+        let Ok(entries) = fs::read_dir(POWERCAP_PATH) else {
+            return power;
+        };
+
+        let now = Instant::now();
+        for entry in entries.flatten() {
+            let zone = entry.file_name().to_string_lossy().to_string();
+            if !zone.starts_with("intel-rapl:") {
+                continue;
+            }
+            let dir = entry.path();
+            let (Some(name), Some(energy_uj)) =
+                (read_trimmed(&dir, "name"), read_u64(&dir, "energy_uj"))
+            else {
+                continue;
+            };
+
+            let previous = self.samples.insert(
+                zone,
+                EnergySample {
+                    energy_uj,
+                    taken_at: now,
+                },
+            );
+            let Some(previous) = previous else {
+                continue;
+            };
+
+            let max_energy_uj = read_u64(&dir, "max_energy_range_uj").unwrap_or(u64::MAX);
+            let watts = energy_to_watts(
+                previous.energy_uj,
+                energy_uj,
+                max_energy_uj,
+                now.duration_since(previous.taken_at).as_secs_f64(),
+            );
+
+            // Multi-socket systems have one package-N zone (and subzones) per socket
+            let total = if name.starts_with("package") {
+                &mut power.package_watts
+            } else if name == "core" {
+                &mut power.core_watts
+            } else if name == "dram" {
+                &mut power.dram_watts
+            } else {
+                continue;
+            };
+            if let Some(watts) = watts {
+                *total = Some(total.unwrap_or(0.0) + watts);
+            }
+        }
+
+        power
+    }
+}
+
+/// Average watts between two energy_uj readings
+/// The counter wraps at max_energy_range_uj, so a smaller reading means it rolled over
+fn energy_to_watts(
+    previous_uj: u64,
+    current_uj: u64,
+    max_energy_uj: u64,
+    secs: f64,
+) -> Option<f32> {
+    if secs <= 0.0 {
+        return None;
+    }
+    let delta_uj = if current_uj >= previous_uj {
+        current_uj - previous_uj
+    } else {
+        max_energy_uj.saturating_sub(previous_uj) + current_uj
+    };
+    Some((delta_uj as f64 / 1_000_000.0 / secs) as f32)
+}
+
+fn read_trimmed(dir: &Path, file: &str) -> Option<String> {
+    fs::read_to_string(dir.join(file))
+        .ok()
+        .map(|v| v.trim().to_string())
+}
+
+fn read_u64(dir: &Path, file: &str) -> Option<u64> {
+    read_trimmed(dir, file)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_energy_deltas_to_watts() {
+        assert_eq!(energy_to_watts(5_000_000, 15_000_000, u64::MAX, 2.0), Some(5.0));
+        assert_eq!(energy_to_watts(7_000_000, 7_000_000, u64::MAX, 1.0), Some(0.0));
+        assert_eq!(energy_to_watts(5_000_000, 15_000_000, u64::MAX, 0.0), None);
+    }
+
+    #[test]
+    fn handles_counter_wraparound() {
+        // A typical package max_energy_range_uj; 1 J before the wrap plus 3 J after it
+        let max_energy_uj = 262_143_328_850;
+        assert_eq!(
+            energy_to_watts(max_energy_uj - 1_000_000, 3_000_000, max_energy_uj, 1.0),
+            Some(4.0)
+        );
+        // Without a known range the counter is assumed to wrap at u64::MAX
+        assert_eq!(energy_to_watts(u64::MAX - 500_000, 1_500_000, u64::MAX, 0.5), Some(4.0));
+    }
+}
@@ -0,0 +1,176 @@
+// CPU time breakdown backend
+// Splits CPU time into user/system/iowait/... from /proc/stat jiffy deltas
+
+use std::fs;
+
+use super::CoreTimes;
+
+/// Cumulative jiffies of one /proc/stat cpu line
+#[derive(Debug, Clone, Copy, Default)]
+struct Jiffies {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+}
+
+impl Jiffies {
+    // guest and guest_nice are already counted in user and nice, so they're left out
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+}
+
+/// Jiffies from the last sample, aggregate first, then cpu0, cpu1, ...
+#[derive(Default)]
+pub struct CpuTimesSampler {
+    previous: Vec<Jiffies>,
+    last: (CoreTimes, Vec<CoreTimes>),
+}
+
+impl CpuTimesSampler {
+    /// (aggregate, per core) percentages since the previous call; all zero on the first
+    /// call or when /proc/stat is unavailable
+    pub fn sample(&mut self) -> (CoreTimes, Vec<CoreTimes>) {
+        let current = fs::read_to_string("/proc/stat")
+            .map(|contents| parse_stat(&contents))
+            .unwrap_or_default();
+        self.last = compute_times(&self.previous, &current);
+        self.previous = current;
+        self.last.clone()
+    }
+
+    /// Result of the latest sample, for refreshes that don't take a new one
+    pub fn last(&self) -> (CoreTimes, Vec<CoreTimes>) {
+        self.last.clone()
+    }
+}
+
+/// Percentages between two snapshots; a CPU without a previous reading gets zeros
+fn compute_times(previous: &[Jiffies], current: &[Jiffies]) -> (CoreTimes, Vec<CoreTimes>) {
+    let mut times = current.iter().enumerate().map(|(i, cur)| {
+        previous
+            .get(i)
+            .map(|prev| percentages(prev, cur))
+            .unwrap_or_default()
+    });
+    let aggregate = times.next().unwrap_or_default();
+    (aggregate, times.collect())
+}
+
+fn percentages(prev: &Jiffies, cur: &Jiffies) -> CoreTimes {
+    let total = cur.total().saturating_sub(prev.total());
+    if total == 0 {
+        return CoreTimes::default();
+    }
+    // Counters can step backwards across CPU hotplug, so each delta saturates at 0
+    let percent = |p: u64, c: u64| c.saturating_sub(p) as f32 / total as f32 * 100.0;
+    CoreTimes {
+        user: percent(prev.user, cur.user),
+        nice: percent(prev.nice, cur.nice),
+        system: percent(prev.system, cur.system),
+        idle: percent(prev.idle, cur.idle),
+        iowait: percent(prev.iowait, cur.iowait),
+        irq: percent(prev.irq, cur.irq),
+        softirq: percent(prev.softirq, cur.softirq),
+        steal: percent(prev.steal, cur.steal),
+    }
+}
+
+/// Parse the "cpu" and "cpuN" lines of /proc/stat, in file order
+/// Older kernels omit trailing columns (steal, guest), which are read as 0
+fn parse_stat(contents: &str) -> Vec<Jiffies> {
+    contents
+        .lines()
+        .take_while(|line| line.starts_with("cpu"))
+        .map(|line| {
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .skip(1)
+                .map(|v| v.parse().unwrap_or(0))
+                .collect();
+            let field = |i: usize| values.get(i).copied().unwrap_or(0);
+            Jiffies {
+                user: field(0),
+                nice: field(1),
+                system: field(2),
+                idle: field(3),
+                iowait: field(4),
+                irq: field(5),
+                softirq: field(6),
+                steal: field(7),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "cpu  100 0 50 800 50 0 0 0 0 0\n\
+                          cpu0 50 0 25 400 25 0 0 0 0 0\n\
+                          cpu1 50 0 25 400 25 0 0 0 0 0\n\
+                          intr 123456 0 0\n";
+    const AFTER: &str = "cpu  160 0 70 900 70 0 0 0 0 0\n\
+                         cpu0 90 0 25 460 25 0 0 0 0 0\n\
+                         cpu1 70 0 45 440 45 0 0 0 0 0\n\
+                         intr 123999 0 0\n";
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn computes_percentages_between_snapshots() {
+        let (aggregate, cores) = compute_times(&parse_stat(BEFORE), &parse_stat(AFTER));
+
+        assert_close(aggregate.user, 30.0);
+        assert_close(aggregate.system, 10.0);
+        assert_close(aggregate.idle, 50.0);
+        assert_close(aggregate.iowait, 10.0);
+        // iowait counts as idle time
+        assert_close(aggregate.busy(), 40.0);
+
+        assert_eq!(cores.len(), 2);
+        assert_close(cores[0].user, 40.0);
+        assert_close(cores[0].idle, 60.0);
+        assert_close(cores[0].iowait, 0.0);
+        assert_close(cores[1].user, 20.0);
+        assert_close(cores[1].system, 20.0);
+        assert_close(cores[1].idle, 40.0);
+        assert_close(cores[1].iowait, 20.0);
+    }
+
+    #[test]
+    fn first_sample_and_unchanged_counters_are_zero() {
+        let (aggregate, cores) = compute_times(&[], &parse_stat(AFTER));
+        assert_eq!(aggregate.busy(), 0.0);
+        assert_eq!(cores.len(), 2);
+        assert!(cores.iter().all(|core| core.idle == 0.0 && core.user == 0.0));
+
+        let (aggregate, _) = compute_times(&parse_stat(AFTER), &parse_stat(AFTER));
+        assert_eq!(aggregate.idle, 0.0);
+    }
+
+    #[test]
+    fn parses_short_stat_lines_from_old_kernels() {
+        let jiffies = parse_stat("cpu 10 20 30 40\nintr 1\ncpu0 1 1 1 1\n");
+        // Parsing stops at the first non-cpu line
+        assert_eq!(jiffies.len(), 1);
+        assert_eq!(jiffies[0].nice, 20);
+        assert_eq!(jiffies[0].steal, 0);
+        assert_eq!(jiffies[0].total(), 100);
+    }
+}
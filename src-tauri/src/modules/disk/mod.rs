@@ -2,13 +2,22 @@
 // Provides disk usage, I/O statistics, mount point information, and SMART data
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::io::ErrorKind;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::process::Output;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 use sysinfo::Disks;
 
+use crate::modules::{
+    since_start, CommandRunner, LockRecover, MonitorError, SystemCommandRunner, TemperatureUnit,
+};
+
 /// SMART health status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SmartHealth {
@@ -19,6 +28,7 @@ pub enum SmartHealth {
 
 /// SMART disk information
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SmartInfo {
     pub health: SmartHealth,
     pub temperature: Option<u32>, // Celsius
@@ -26,8 +36,72 @@ pub struct SmartInfo {
     pub power_cycle_count: Option<u64>,
 }
 
-/// Information about a single disk/partition
+/// SMART self-test type, passed to `smartctl -t`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum SelfTestKind {
+    Short,
+    Long,
+    Conveyance,
+}
+
+impl SelfTestKind {
+    fn smartctl_arg(self) -> &'static str {
+        match self {
+            SelfTestKind::Short => "short",
+            SelfTestKind::Long => "long",
+            SelfTestKind::Conveyance => "conveyance",
+        }
+    }
+}
+
+/// A device smartctl can address, as listed by `smartctl --scan`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartDevice {
+    pub path: String,                // /dev/sda, /dev/nvme0, /dev/bus/0
+    pub device_type: Option<String>, // -d hint, e.g. "sat", "nvme", "megaraid,0"
+    pub description: String,         // smartctl's comment, e.g. "/dev/sda [SAT], ATA device"
+}
+
+impl SmartDevice {
+    /// A device --scan didn't report, addressed by path alone
+    fn untyped(path: String) -> Self {
+        Self {
+            path,
+            device_type: None,
+            description: String::new(),
+        }
+    }
+
+    /// Device arguments for smartctl: "-d <type> <path>", or just the path
+    fn smartctl_args(&self) -> Vec<&str> {
+        match &self.device_type {
+            Some(device_type) => vec!["-d", device_type, &self.path],
+            None => vec![&self.path],
+        }
+    }
+
+    /// RAID members share a path, so the cache tells them apart by type
+    fn cache_key(&self) -> String {
+        match &self.device_type {
+            Some(device_type) => format!("{} -d {}", self.path, device_type),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Progress of the current SMART self-test and the outcome of the latest one
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartSelfTestStatus {
+    pub in_progress: bool,
+    pub percent_remaining: Option<u32>, // Only while a test is running
+    pub last_result: Option<String>,    // e.g. "Completed without error"
+}
+
+/// Information about a single disk/partition
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DiskInfo {
     pub name: String,
     pub mount_point: String,
@@ -37,18 +111,100 @@ pub struct DiskInfo {
     pub used_space: u64,
     pub usage_percent: f32,
     pub is_removable: bool,
+    pub mount_options: Vec<String>, // From /proc/mounts, e.g. ["rw", "noatime"]
+    pub read_only: bool,
+    pub discard_supported: bool, // Device accepts TRIM/discard (SSDs, thin-provisioned storage)
+    pub logical_block_size: Option<u32>, // Bytes; 512 with a 4096 physical size means 512e
+    pub physical_block_size: Option<u32>, // Bytes; align partitions to this
+    pub rotational: bool, // Spinning disk (HDD); false for SSDs and unknown devices
     pub read_bytes: u64,
     pub written_bytes: u64,
+    pub read_since_start: u64,    // Bytes since the app started
+    pub written_since_start: u64, // Bytes since the app started
+    pub read_rate_bps: f64,  // Bytes per second since last refresh
+    pub write_rate_bps: f64, // Bytes per second since last refresh
+    pub avg_latency_ms: f64, // Weighted I/O time per completed op since last refresh
+    pub queue_depth: u32,    // I/Os currently in flight
     pub smart: Option<SmartInfo>,
 }
 
 /// Overall disk statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DisksInfo {
     pub disks: Vec<DiskInfo>,
     pub total_space: u64,
     pub total_used: u64,
     pub total_available: u64,
+    pub total_read_rate_bps: f64,  // Summed once per device, so double mounts don't count twice
+    pub total_write_rate_bps: f64,
+    pub hidden: Vec<String>, // Device names or mount points hidden by the user, sorted
+    pub temperature_unit: String, // Symbol of the unit the SMART temperatures are in
+}
+
+impl DiskInfo {
+    /// Render the SMART temperature in the given unit
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        if let Some(ref mut smart) = self.smart {
+            smart.temperature = smart.temperature.map(|t| unit.convert_whole(t));
+        }
+        self
+    }
+}
+
+impl DisksInfo {
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        self.disks = self.disks.into_iter().map(|d| d.in_unit(unit)).collect();
+        self.temperature_unit = unit.symbol().to_string();
+        self
+    }
+}
+
+/// A physical disk with its partitions grouped underneath
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalDisk {
+    pub name: String,
+    pub model: Option<String>,
+    pub size: u64, // Bytes
+    pub partitions: Vec<DiskInfo>,
+    pub rotational: bool,
+}
+
+impl PhysicalDisk {
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        self.partitions = self.partitions.into_iter().map(|p| p.in_unit(unit)).collect();
+        self
+    }
+}
+
+/// A directory child with its recursive size, from scan_directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirEntry {
+    pub path: String,
+    pub size_bytes: u64, // Recursive for directories, capped at max_depth
+    pub is_dir: bool,
+}
+
+/// Raw counters for a single device from /proc/diskstats
+#[derive(Debug, Clone, Copy, Default)]
+struct DiskIoStats {
+    read_bytes: u64,
+    written_bytes: u64,
+    completed_ios: u64,
+    in_flight: u32,
+    weighted_io_ms: u64,
+}
+
+/// Previous diskstats sample for calculating latency and transfer rates
+#[derive(Debug, Clone, Copy)]
+struct DiskIoSample {
+    read_bytes: u64,
+    written_bytes: u64,
+    completed_ios: u64,
+    weighted_io_ms: u64,
+    taken_at: Instant,
 }
 
 /// SMART cache entry
@@ -60,66 +216,351 @@ struct SmartCache {
 /// Disk Monitor state with SMART caching
 pub struct DiskMonitor {
     disks: RwLock<Option<Disks>>,
-    smart_cache: RwLock<HashMap<String, SmartCache>>,
+    smart_cache: RwLock<HashMap<String, SmartCache>>, // Keyed by SmartDevice::cache_key
+    smart_devices: RwLock<Option<Vec<SmartDevice>>>, // `smartctl --scan` result, until invalidated
+    smartctl_missing: AtomicBool, // Set once smartctl fails to spawn; not retried this session
+    smart_cache_secs: AtomicU64,  // How long SMART results are reused
+    runner: Box<dyn CommandRunner>,
+    last_io_samples: RwLock<HashMap<String, DiskIoSample>>,
+    baselines: RwLock<HashMap<String, (u64, u64)>>, // (read, written) at first refresh
+    hidden: RwLock<HashSet<String>>, // Device names or mount points the user chose to hide
 }
 
-// SMART data cache duration (60 seconds - SMART data doesn't change often)
-const SMART_CACHE_DURATION: Duration = Duration::from_secs(60);
+// Default SMART data cache duration (60 seconds - SMART data doesn't change often)
+const DEFAULT_SMART_CACHE_SECS: u64 = 60;
+
+// Unit of /proc/diskstats and /sys/block/<dev>/size sector counts (512 bytes); the kernel
+// always counts 512-byte sectors here, whatever the device's logical block size
+const KERNEL_SECTOR_SIZE: u64 = 512;
+
+const SYSFS_ROOT: &str = "/sys";
 
 impl DiskMonitor {
     pub fn new() -> Self {
+        Self::with_runner(Box::new(SystemCommandRunner))
+    }
+
+    /// Create a monitor that runs external tools through the given runner
+    pub fn with_runner(runner: Box<dyn CommandRunner>) -> Self {
         Self {
             disks: RwLock::new(None),
             smart_cache: RwLock::new(HashMap::new()),
+            smart_devices: RwLock::new(None),
+            smartctl_missing: AtomicBool::new(false),
+            smart_cache_secs: AtomicU64::new(DEFAULT_SMART_CACHE_SECS),
+            runner,
+            last_io_samples: RwLock::new(HashMap::new()),
+            baselines: RwLock::new(HashMap::new()),
+            hidden: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Change how long SMART results are reused before smartctl runs again
+    pub fn set_smart_cache_duration(&self, secs: u64) {
+        self.smart_cache_secs.store(secs, Ordering::Relaxed);
+    }
+
+    /// Drop cached SMART results so the next refresh queries every disk again
+    /// (e.g. right after a self-test); the refresh repopulates the cache
+    /// The device scan is dropped too, so newly attached drives are picked up
+    pub fn invalidate_smart_cache(&self) {
+        self.smart_cache
+            .write_or_recover("Disk SMART cache")
+            .clear();
+        *self
+            .smart_devices
+            .write_or_recover("Disk SMART devices") = None;
+    }
+
+    /// Devices smartctl can address, from `smartctl --scan`, including NVMe controllers
+    /// and RAID members that don't map to a partition; the result is cached
+    pub fn list_smart_devices(&self) -> Result<Vec<SmartDevice>, MonitorError> {
+        let output = self.run_smartctl(&["--scan"])?;
+        if !output.status.success() {
+            return Err(command_error(&output, "Failed to scan for SMART devices"));
+        }
+        let devices = parse_smart_scan(&String::from_utf8_lossy(&output.stdout));
+        *self
+            .smart_devices
+            .write_or_recover("Disk SMART devices") = Some(devices.clone());
+        Ok(devices)
+    }
+
+    /// Cached scan result, scanning on first use; empty when smartctl is missing or fails
+    fn smart_devices(&self) -> Vec<SmartDevice> {
+        if let Some(devices) = self
+            .smart_devices
+            .read_or_recover("Disk SMART devices")
+            .as_ref()
+        {
+            return devices.clone();
+        }
+        if self.smartctl_missing.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+
+        self.list_smart_devices().unwrap_or_else(|e| {
+            if matches!(e, MonitorError::Unsupported(_)) {
+                self.smartctl_missing.store(true, Ordering::Relaxed);
+            }
+            // Remember the failure so refreshes don't rescan every time
+            *self
+                .smart_devices
+                .write_or_recover("Disk SMART devices") = Some(Vec::new());
+            Vec::new()
+        })
+    }
+
+    /// The device smartctl should be pointed at for a user-supplied path and -d type
+    /// A scanned device is used as is; otherwise only a real block device is accepted, so no
+    /// arbitrary path or option-like string reaches smartctl
+    fn resolve_smart_device(
+        &self,
+        device: &str,
+        device_type: Option<&str>,
+    ) -> Result<SmartDevice, MonitorError> {
+        let scanned = self.smart_devices().into_iter().find(|d| {
+            d.path == device && device_type.is_none_or(|t| d.device_type.as_deref() == Some(t))
+        });
+        match (scanned, device_type) {
+            (Some(found), _) => Ok(found),
+            (None, Some(device_type)) => Err(MonitorError::Io(format!(
+                "{} -d {} was not found by smartctl --scan",
+                device, device_type
+            ))),
+            (None, None) => {
+                validate_block_device(device)?;
+                Ok(SmartDevice::untyped(device.to_string()))
+            }
         }
     }
 
     /// Get SMART data for a disk device (with caching)
+    /// Partitions share their parent disk's entry, so smartctl runs once per physical disk
     fn get_smart_info_cached(&self, device_name: &str) -> Option<SmartInfo> {
+        // Only real block devices have SMART data (e.g. /dev/sda from /dev/sda1)
+        if !device_name.starts_with("/dev/") {
+            return None;
+        }
+        // Disks --scan didn't list are still tried by path, without a type hint
+        let disk = parent_disk_name(device_name);
+        let device = scanned_device_for(&self.smart_devices(), &disk)
+            .unwrap_or_else(|| SmartDevice::untyped(format!("/dev/{}", disk)));
+        let cache_key = device.cache_key();
+
         // Check cache first
         {
-            let cache = self.smart_cache.read().ok()?;
-            if let Some(cached) = cache.get(device_name) {
-                if cached.last_update.elapsed() < SMART_CACHE_DURATION {
+            let cache_duration = Duration::from_secs(self.smart_cache_secs.load(Ordering::Relaxed));
+            let cache = self.smart_cache.read_or_recover("Disk SMART cache");
+            if let Some(cached) = cache.get(&cache_key) {
+                if cached.last_update.elapsed() < cache_duration {
                     return cached.info.clone();
                 }
             }
         }
 
         // Cache miss or expired - fetch new data
-        let smart_info = self.get_smart_info_raw(device_name);
+        let smart_info = self.get_smart_info_raw(&device);
 
         // Update cache
-        if let Ok(mut cache) = self.smart_cache.write() {
-            cache.insert(
-                device_name.to_string(),
-                SmartCache {
-                    info: smart_info.clone(),
-                    last_update: Instant::now(),
-                },
-            );
-        }
+        self.smart_cache.write_or_recover("Disk SMART cache").insert(
+            cache_key,
+            SmartCache {
+                info: smart_info.clone(),
+                last_update: Instant::now(),
+            },
+        );
 
         smart_info
     }
 
-    /// Get SMART data for a disk device (raw, without cache)
-    fn get_smart_info_raw(&self, device_name: &str) -> Option<SmartInfo> {
-        // Extract device path (e.g., /dev/sda from /dev/sda1)
-        let device_path = if device_name.starts_with("/dev/") {
-            // Remove partition number (sda1 -> sda, nvme0n1p1 -> nvme0n1)
-            let base = device_name.trim_end_matches(|c: char| c.is_ascii_digit());
-            let base = base.trim_end_matches('p'); // For nvme0n1p1
-            base.to_string()
+    /// Start a SMART self-test; the drive runs it in the background
+    /// Needs root (or the disk group) to open the device
+    pub fn run_smart_self_test(
+        &self,
+        device: &str,
+        device_type: Option<&str>,
+        kind: SelfTestKind,
+    ) -> Result<(), MonitorError> {
+        let device = self.resolve_smart_device(device, device_type)?;
+        let mut args = vec!["-t", kind.smartctl_arg()];
+        args.extend(device.smartctl_args());
+        let output = self.run_smartctl(&args)?;
+
+        // ATA prints "Testing has begun", NVMe "Self-test has begun"
+        if String::from_utf8_lossy(&output.stdout).contains("has begun") {
+            Ok(())
         } else {
-            return None;
+            Err(command_error(&output, "Failed to start self-test"))
+        }
+    }
+
+    /// Self-test progress (from the capabilities section) and the latest logged result
+    pub fn smart_self_test_status(
+        &self,
+        device: &str,
+        device_type: Option<&str>,
+    ) -> Result<SmartSelfTestStatus, MonitorError> {
+        let device = self.resolve_smart_device(device, device_type)?;
+        let mut args = vec!["-c", "-l", "selftest"];
+        args.extend(device.smartctl_args());
+        let output = self.run_smartctl(&args)?;
+
+        // smartctl sets bit 1 of the exit status when the device couldn't be opened
+        if output.status.code().is_some_and(|code| code & 0b10 != 0) {
+            return Err(command_error(&output, "Failed to read self-test status"));
+        }
+        Ok(parse_self_test_status(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Discard unused blocks of a mounted filesystem with `fstrim -v`, returning the bytes
+    /// trimmed; needs root, and only mount points in the current disk list are accepted
+    pub fn run_fstrim(&self, mount_point: &str) -> Result<u64, MonitorError> {
+        self.mounted_device(mount_point)?;
+
+        let output = self.runner.run("fstrim", &["-v", mount_point]).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                MonitorError::Unsupported("fstrim (util-linux) is not installed".to_string())
+            }
+            _ => MonitorError::SystemAccess(format!("Failed to run fstrim: {}", e)),
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("not supported") {
+                return Err(MonitorError::Unsupported(format!(
+                    "{} does not support discard",
+                    mount_point
+                )));
+            }
+            return Err(command_error(&output, "fstrim failed"));
+        }
+
+        parse_fstrim_output(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+            MonitorError::SystemAccess("Unexpected fstrim output".to_string())
+        })
+    }
+
+    /// Mount a removable block device, returning where it was mounted
+    /// Without a mount point udisksctl picks one (/media/<user>/<label>) and needs no root;
+    /// an explicit mount point goes through `mount`, which does
+    pub fn mount_device(
+        &self,
+        device: &str,
+        mount_point: Option<&str>,
+    ) -> Result<String, MonitorError> {
+        validate_block_device(device)?;
+        validate_removable(device)?;
+        self.mount_validated(device, mount_point)
+    }
+
+    /// mount_device once `device` is known to be a removable block device
+    fn mount_validated(
+        &self,
+        device: &str,
+        mount_point: Option<&str>,
+    ) -> Result<String, MonitorError> {
+        match mount_point {
+            None => {
+                let output = self.run_mount_tool(
+                    "udisksctl",
+                    &["mount", "--block-device", device, "--no-user-interaction"],
+                )?;
+                if !output.status.success() {
+                    return Err(command_error(&output, "Failed to mount"));
+                }
+                parse_udisks_mount_output(&String::from_utf8_lossy(&output.stdout)).ok_or_else(
+                    || MonitorError::SystemAccess("Unexpected udisksctl output".to_string()),
+                )
+            }
+            Some(mount_point) => {
+                // Absolute paths only, so the argument can't be read as a mount option
+                let path = Path::new(mount_point);
+                if !path.is_absolute() || !path.is_dir() {
+                    return Err(MonitorError::Io(format!("{} is not a directory", mount_point)));
+                }
+                let output = self.run_mount_tool("mount", &[device, mount_point])?;
+                if !output.status.success() {
+                    return Err(command_error(&output, "Failed to mount"));
+                }
+                Ok(mount_point.to_string())
+            }
+        }
+    }
+
+    /// Unmount a removable disk by mount point, through udisksctl when it's installed and
+    /// `umount` (needs root) otherwise; only mount points in the current disk list are accepted
+    pub fn unmount_device(&self, mount_point: &str) -> Result<(), MonitorError> {
+        let device = self.mounted_device(mount_point)?;
+        validate_removable(&device)?;
+        self.unmount_validated(&device, mount_point)
+    }
+
+    /// unmount_device once `device` is known to be the removable disk at `mount_point`
+    fn unmount_validated(&self, device: &str, mount_point: &str) -> Result<(), MonitorError> {
+        let output = match self.run_mount_tool(
+            "udisksctl",
+            &["unmount", "--block-device", device, "--no-user-interaction"],
+        ) {
+            Err(MonitorError::Unsupported(_)) => self.run_mount_tool("umount", &[mount_point])?,
+            result => result?,
         };
+        if !output.status.success() {
+            return Err(command_error(&output, "Failed to unmount"));
+        }
+        Ok(())
+    }
+
+    /// Device name (/dev/sdb1) mounted at `mount_point`, from a freshly listed disk set
+    fn mounted_device(&self, mount_point: &str) -> Result<String, MonitorError> {
+        let mut disks_handle = self.disks.write_or_recover("Disk monitor");
+        let disks = disks_handle.get_or_insert_with(Disks::new_with_refreshed_list);
+        disks.refresh_list();
+        disks
+            .iter()
+            .find(|d| d.mount_point() == Path::new(mount_point))
+            .map(|d| d.name().to_string_lossy().to_string())
+            .ok_or_else(|| MonitorError::Io(format!("{} is not a mounted disk", mount_point)))
+    }
+
+    /// Run a mount tool through the runner, mapping a missing binary to Unsupported
+    fn run_mount_tool(&self, program: &str, args: &[&str]) -> Result<Output, MonitorError> {
+        self.runner.run(program, args).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                MonitorError::Unsupported(format!("{} is not installed", program))
+            }
+            _ => MonitorError::SystemAccess(format!("Failed to run {}: {}", program, e)),
+        })
+    }
+
+    /// Run smartctl through the runner, mapping a missing binary to Unsupported
+    fn run_smartctl(&self, args: &[&str]) -> Result<Output, MonitorError> {
+        self.runner.run("smartctl", args).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                MonitorError::Unsupported("smartctl (smartmontools) is not installed".to_string())
+            }
+            _ => MonitorError::SystemAccess(format!("Failed to run smartctl: {}", e)),
+        })
+    }
+
+    /// Get SMART data for a whole-disk device (raw, without cache)
+    fn get_smart_info_raw(&self, device: &SmartDevice) -> Option<SmartInfo> {
+        if self.smartctl_missing.load(Ordering::Relaxed) {
+            return None;
+        }
 
         // Run smartctl (requires smartmontools installed)
-        let output = Command::new("smartctl")
-            .args(["-H", "-A", &device_path])
-            .output()
-            .ok()?;
+        let mut args = vec!["-H", "-A"];
+        args.extend(device.smartctl_args());
+        let output = match self.runner.run("smartctl", &args) {
+            Ok(output) => output,
+            Err(e) => {
+                if e.kind() == ErrorKind::NotFound {
+                    self.smartctl_missing.store(true, Ordering::Relaxed);
+                }
+                return None;
+            }
+        };
 
         if !output.status.success() {
             return None;
@@ -176,11 +617,31 @@ impl DiskMonitor {
         })
     }
 
+    /// Restart the since-start counters and rate/latency sampling from the next refresh
+    /// Locks are taken in refresh's order, so a concurrent refresh sees all or nothing
+    pub fn reset_counters(&self) {
+        let mut last_io_samples = self
+            .last_io_samples
+            .write_or_recover("Disk samples");
+        let mut baselines = self
+            .baselines
+            .write_or_recover("Disk baselines");
+        last_io_samples.clear();
+        baselines.clear();
+    }
+
+    /// Replace the set of hidden disks, matched by device name (/dev/sdb1) or mount point
+    /// Hidden disks are left out of the list and totals
+    pub fn set_hidden(&self, names: Vec<String>) {
+        *self
+            .hidden
+            .write_or_recover("Disk hidden") = names.into_iter().collect();
+    }
+
     pub fn refresh(&self) -> DisksInfo {
         let mut disks_handle = self
             .disks
-            .write()
-            .expect("Disk monitor RwLock poisoned - fatal error");
+            .write_or_recover("Disk monitor");
 
         // Initialize lazily
         if disks_handle.is_none() {
@@ -195,10 +656,30 @@ impl DiskMonitor {
         let mut total_used: u64 = 0;
         let mut total_available: u64 = 0;
 
-        // Batch read disk stats once
+        // Batch read disk stats and mount options once
         let io_stats = Self::get_all_disk_io_stats();
+        let mount_options = read_mount_options();
+        let mut last_io_samples = self
+            .last_io_samples
+            .write_or_recover("Disk samples");
+        let mut baselines = self
+            .baselines
+            .write_or_recover("Disk baselines");
+        let hidden = self
+            .hidden
+            .read_or_recover("Disk hidden");
+        // Built fresh so a device mounted twice is compared against the previous refresh
+        // both times, not against the sample its first mount just stored
+        let mut io_samples = HashMap::new();
+        let now = Instant::now();
 
         for disk in disks_ref.iter() {
+            let device_name = disk.name().to_string_lossy().to_string();
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            if hidden.contains(&device_name) || hidden.contains(&mount_point) {
+                continue;
+            }
+
             let disk_total = disk.total_space();
             let disk_available = disk.available_space();
             let disk_used = disk_total.saturating_sub(disk_available);
@@ -209,24 +690,74 @@ impl DiskMonitor {
                 0.0
             };
 
-            let device_name = disk.name().to_string_lossy().to_string();
             // Use cached SMART info to prevent blocking
             let smart = self.get_smart_info_cached(&device_name);
 
-            // Lookup I/O stats from the batch map
-            let (read_bytes, written_bytes) = io_stats.get(&device_name).copied().unwrap_or((0, 0));
+            // diskstats rows are keyed by bare kernel name (sda1, dm-0); partitions have
+            // their own rows
+            let kernel_name = kernel_device_name(&device_name);
+            let stats = io_stats.get(&kernel_name).copied().unwrap_or_default();
+
+            // Calculate latency and rates against the previous sample
+            let last = last_io_samples.get(&device_name);
+            let avg_latency_ms = last
+                .map(|last| Self::average_latency_ms(last, &stats))
+                .unwrap_or(0.0);
+            let (read_rate_bps, write_rate_bps) = last
+                .map(|last| Self::transfer_rates(last, &stats, now))
+                .unwrap_or((0.0, 0.0));
+
+            io_samples.insert(
+                device_name.clone(),
+                DiskIoSample {
+                    read_bytes: stats.read_bytes,
+                    written_bytes: stats.written_bytes,
+                    completed_ios: stats.completed_ios,
+                    weighted_io_ms: stats.weighted_io_ms,
+                    taken_at: now,
+                },
+            );
+
+            let (read_since_start, written_since_start) =
+                since_start(&mut baselines, &device_name, (stats.read_bytes, stats.written_bytes));
+
+            let options = mount_options.get(&mount_point).cloned().unwrap_or_default();
+            let read_only = options.iter().any(|o| o == "ro");
+            let queue = queue_dir(&kernel_name);
+            let queue_value = |attr: &str| {
+                fs::read_to_string(queue.join(attr)).ok().map(|v| v.trim().to_string())
+            };
+            let discard_supported = queue_value("discard_max_bytes")
+                .and_then(|v| v.parse::<u64>().ok())
+                .is_some_and(|max| max > 0);
+            let logical_block_size = queue_value("logical_block_size").and_then(|v| v.parse().ok());
+            let physical_block_size =
+                queue_value("physical_block_size").and_then(|v| v.parse().ok());
+            let rotational = queue_value("rotational").is_some_and(|v| v == "1");
 
             disks.push(DiskInfo {
                 name: device_name,
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                mount_point,
                 file_system: disk.file_system().to_string_lossy().to_string(),
                 total_space: disk_total,
                 available_space: disk_available,
                 used_space: disk_used,
                 usage_percent,
                 is_removable: disk.is_removable(),
-                read_bytes,
-                written_bytes,
+                mount_options: options,
+                read_only,
+                discard_supported,
+                logical_block_size,
+                physical_block_size,
+                rotational,
+                read_bytes: stats.read_bytes,
+                written_bytes: stats.written_bytes,
+                read_since_start,
+                written_since_start,
+                read_rate_bps,
+                write_rate_bps,
+                avg_latency_ms,
+                queue_depth: stats.in_flight,
                 smart,
             });
 
@@ -235,41 +766,1061 @@ impl DiskMonitor {
             total_used += disk_used;
         }
 
+        *last_io_samples = io_samples;
+
+        let mut hidden: Vec<String> = hidden.iter().cloned().collect();
+        hidden.sort();
+
+        let (total_read_rate_bps, total_write_rate_bps) = total_transfer_rates(&disks);
+
         DisksInfo {
             disks,
             total_space,
             total_used,
             total_available,
+            total_read_rate_bps,
+            total_write_rate_bps,
+            hidden,
+            temperature_unit: TemperatureUnit::Celsius.symbol().to_string(),
         }
     }
 
+    /// Refresh disks and group the partitions under their physical disk
+    pub fn physical_disks(&self) -> Vec<PhysicalDisk> {
+        let info = self.refresh();
+
+        group_by_physical_disk(info.disks)
+            .into_iter()
+            .map(|(name, partitions)| {
+                let sys_path = Path::new("/sys/block").join(&name);
+
+                let model = fs::read_to_string(sys_path.join("device/model"))
+                    .ok()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty());
+
+                let size = fs::read_to_string(sys_path.join("size"))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u64>().ok())
+                    .map(|sectors| sectors * KERNEL_SECTOR_SIZE)
+                    .unwrap_or(0);
+
+                let rotational = fs::read_to_string(sys_path.join("queue/rotational"))
+                    .map(|s| s.trim() == "1")
+                    .unwrap_or(false);
+
+                PhysicalDisk {
+                    name,
+                    model,
+                    size,
+                    partitions,
+                    rotational,
+                }
+            })
+            .collect()
+    }
+
+    /// Average latency between two samples (weighted I/O time / completed ops)
+    /// Returns 0 when no I/O completed in the interval
+    fn average_latency_ms(last: &DiskIoSample, current: &DiskIoStats) -> f64 {
+        let ops_delta = current.completed_ios.saturating_sub(last.completed_ios);
+        if ops_delta == 0 {
+            return 0.0;
+        }
+        let time_delta = current.weighted_io_ms.saturating_sub(last.weighted_io_ms);
+        time_delta as f64 / ops_delta as f64
+    }
+
+    /// Read and write throughput in bytes per second between two samples
+    fn transfer_rates(last: &DiskIoSample, current: &DiskIoStats, now: Instant) -> (f64, f64) {
+        let secs = now.duration_since(last.taken_at).as_secs_f64();
+        if secs <= 0.0 {
+            return (0.0, 0.0);
+        }
+        (
+            current.read_bytes.saturating_sub(last.read_bytes) as f64 / secs,
+            current.written_bytes.saturating_sub(last.written_bytes) as f64 / secs,
+        )
+    }
+
     /// Read all I/O stats from /proc/diskstats once
-    /// Returns a map of device_name -> (read_bytes, written_bytes)
-    fn get_all_disk_io_stats() -> HashMap<String, (u64, u64)> {
+    /// Returns a map of device_name -> raw I/O counters
+    fn get_all_disk_io_stats() -> HashMap<String, DiskIoStats> {
+        fs::read_to_string("/proc/diskstats")
+            .map(|content| Self::parse_diskstats(&content))
+            .unwrap_or_default()
+    }
+
+    fn parse_diskstats(content: &str) -> HashMap<String, DiskIoStats> {
         let mut stats = HashMap::new();
-        if let Ok(content) = fs::read_to_string("/proc/diskstats") {
-            for line in content.lines() {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 14 {
-                    // Field 3 is device name
-                    let device_name = parts[2].to_string();
-
-                    // Field 6: sectors read
-                    // Field 10: sectors written
-                    let sectors_read = parts[5].parse::<u64>().unwrap_or(0);
-                    let sectors_written = parts[9].parse::<u64>().unwrap_or(0);
-
-                    // Assuming 512 byte sectors is the standard unit for diskstats
-                    stats.insert(device_name, (sectors_read * 512, sectors_written * 512));
-                }
+        for line in content.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 14 {
+                // Field 3 is device name
+                let device_name = parts[2].to_string();
+
+                let field = |i: usize| parts[i].parse::<u64>().unwrap_or(0);
+
+                // Field 4: reads completed, Field 8: writes completed
+                // Field 6: sectors read, Field 10: sectors written
+                // Field 12: I/Os in progress, Field 14: weighted ms doing I/O
+                let sectors_read = field(5);
+                let sectors_written = field(9);
+
+                stats.insert(
+                    device_name,
+                    DiskIoStats {
+                        read_bytes: sectors_read * KERNEL_SECTOR_SIZE,
+                        written_bytes: sectors_written * KERNEL_SECTOR_SIZE,
+                        completed_ios: field(3) + field(7),
+                        in_flight: field(11) as u32,
+                        weighted_io_ms: field(13),
+                    },
+                );
             }
         }
         stats
     }
 }
 
+/// du-like scan: immediate children of `path` with their recursive sizes, largest first
+/// Symlinks are never followed (so loops are impossible) and count as their own size;
+/// unreadable entries are skipped. Each child directory is descended at most `max_depth`
+/// levels, so sizes of deeper trees are lower bounds.
+/// Sizes are allocated space (st_blocks, like du), and other filesystems mounted below
+/// `path` are skipped (like du -x), so /proc or a network mount never inflates a scan of /.
+pub fn scan_directory(path: &Path, max_depth: u32) -> Result<Vec<DirEntry>, MonitorError> {
+    let read_error = |e: std::io::Error| {
+        MonitorError::Io(format!("Cannot read directory {}: {}", path.display(), e))
+    };
+    let root_dev = fs::metadata(path).map_err(read_error)?.dev();
+    let children = fs::read_dir(path).map_err(read_error)?;
+
+    let mut entries: Vec<DirEntry> = children
+        .flatten()
+        .filter_map(|child| {
+            let metadata = child.metadata().ok()?; // Doesn't traverse symlinks
+            if metadata.dev() != root_dev {
+                return None;
+            }
+            let child_path = child.path();
+            let is_dir = metadata.is_dir();
+            let size_bytes = if is_dir {
+                directory_size(child_path.clone(), max_depth, root_dev)
+            } else {
+                allocated_size(&metadata)
+            };
+            Some(DirEntry {
+                path: child_path.to_string_lossy().to_string(),
+                size_bytes,
+                is_dir,
+            })
+        })
+        .collect();
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    Ok(entries)
+}
+
+/// Total size of the files under `dir` on device `dev`, descending at most `depth` more
+/// levels. Iterative so deep trees can't overflow the stack
+fn directory_size(dir: PathBuf, depth: u32, dev: u64) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![(dir, depth)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(children) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for child in children.flatten() {
+            let Ok(metadata) = child.metadata() else {
+                continue;
+            };
+            if metadata.dev() != dev {
+                continue;
+            }
+            if metadata.is_dir() {
+                if depth > 0 {
+                    pending.push((child.path(), depth - 1));
+                }
+            } else {
+                total += allocated_size(&metadata);
+            }
+        }
+    }
+    total
+}
+
+/// Space a file occupies on disk; st_blocks is always in 512-byte units, so sparse files
+/// count what they use rather than their apparent length
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    metadata.blocks() * 512
+}
+
+/// Only pass real block devices to smartctl, never arbitrary paths or option-like strings
+fn validate_block_device(device: &str) -> Result<(), MonitorError> {
+    let is_block_device = device.starts_with("/dev/")
+        && fs::metadata(device).is_ok_and(|m| m.file_type().is_block_device());
+    if is_block_device {
+        Ok(())
+    } else {
+        Err(MonitorError::Io(format!("{} is not a block device", device)))
+    }
+}
+
+/// Parse `smartctl --scan` output, one device per line:
+/// "/dev/sda -d sat # /dev/sda [SAT], ATA device"
+/// "/dev/bus/0 -d megaraid,0 # /dev/bus/0 [megaraid_disk_00], SCSI device"
+fn parse_smart_scan(output: &str) -> Vec<SmartDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (args, comment) = line.split_once('#').unwrap_or((line, ""));
+            let args: Vec<&str> = args.split_whitespace().collect();
+            let path = args.first().filter(|path| path.starts_with("/dev/"))?;
+            Some(SmartDevice {
+                path: path.to_string(),
+                device_type: args
+                    .windows(2)
+                    .find(|pair| pair[0] == "-d")
+                    .map(|pair| pair[1].to_string()),
+                description: comment.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Scanned device holding a whole disk (sda, nvme0n1)
+/// --scan lists NVMe controllers (/dev/nvme0), so a namespace (nvme0n1) maps to its controller
+fn scanned_device_for(devices: &[SmartDevice], disk: &str) -> Option<SmartDevice> {
+    let path = format!("/dev/{}", disk);
+    let is_namespace_of = |device: &SmartDevice| {
+        let controller = device.path.trim_start_matches("/dev/");
+        controller.starts_with("nvme")
+            && disk
+                .strip_prefix(controller)
+                .and_then(|rest| rest.strip_prefix('n'))
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+    };
+    devices
+        .iter()
+        .find(|device| device.path == path)
+        .or_else(|| devices.iter().find(|device| is_namespace_of(device)))
+        .cloned()
+}
+
+/// Mounting is limited to devices the kernel flags as removable
+/// (/sys/block/<disk>/removable), so system disks can't be remounted from the UI
+fn validate_removable(device: &str) -> Result<(), MonitorError> {
+    let removable = fs::read_to_string(
+        Path::new("/sys/block")
+            .join(parent_disk_name(&kernel_device_name(device)))
+            .join("removable"),
+    )
+    .is_ok_and(|v| v.trim() == "1");
+    if removable {
+        Ok(())
+    } else {
+        Err(MonitorError::PermissionDenied(format!(
+            "{} is not a removable device",
+            device
+        )))
+    }
+}
+
+/// Mount point from `udisksctl mount` output: "Mounted /dev/sdb1 at /media/user/USB."
+/// (older udisks ends the line with a period)
+fn parse_udisks_mount_output(output: &str) -> Option<String> {
+    let (_, mount_point) = output.trim().split_once(" at ")?;
+    let mount_point = mount_point.strip_suffix('.').unwrap_or(mount_point);
+    (!mount_point.is_empty()).then(|| mount_point.to_string())
+}
+
+/// Turn a failed smartctl/fstrim/mount run into DeviceBusy, PermissionDenied or SystemAccess
+fn command_error(output: &Output, context: &str) -> MonitorError {
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let message = text
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("no output")
+        .to_string();
+
+    // umount says "target is busy", udisks "Device or resource busy" / "DeviceBusy"
+    let busy = ["target is busy", "Device or resource busy", "DeviceBusy"];
+    // mount without root: "must be superuser"; udisks without polkit approval: "NotAuthorized"
+    let denied = [
+        "Permission denied",
+        "Operation not permitted",
+        "must be superuser",
+        "NotAuthorized",
+    ];
+    if busy.iter().any(|pattern| text.contains(pattern)) {
+        MonitorError::DeviceBusy(format!("{}: {}", context, message))
+    } else if denied.iter().any(|pattern| text.contains(pattern)) {
+        MonitorError::PermissionDenied(format!("{}: {}", context, message))
+    } else {
+        MonitorError::SystemAccess(format!("{}: {}", context, message))
+    }
+}
+
+/// Bytes trimmed from `fstrim -v` output: "/home: 1.2 GiB (1288490188 bytes) trimmed"
+/// (newer util-linux appends " on /dev/sda2")
+fn parse_fstrim_output(output: &str) -> Option<u64> {
+    let (_, rest) = output.split_once('(')?;
+    let (bytes, _) = rest.split_once(" bytes)")?;
+    bytes.trim().parse().ok()
+}
+
+/// Kernel name of the device behind a sysinfo disk name, as used by /proc/diskstats and
+/// /sys/block: "/dev/sda1" -> "sda1", "/dev/mapper/vg-root" -> "dm-0"
+/// Symlinks (/dev/mapper, /dev/disk/by-*) are resolved; names that aren't device paths
+/// are returned without any /dev/ prefix
+fn kernel_device_name(device_name: &str) -> String {
+    fs::canonicalize(device_name)
+        .ok()
+        .and_then(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| device_name.trim_start_matches("/dev/").to_string())
+}
+
+/// /sys/block/<dev>/queue for a kernel device name (discard, block sizes, rotational)
+/// Partitions have no queue of their own, so it's the parent disk's
+fn queue_dir(kernel_name: &str) -> PathBuf {
+    queue_dir_in(Path::new(SYSFS_ROOT), kernel_name)
+}
+
+fn queue_dir_in(sysfs: &Path, kernel_name: &str) -> PathBuf {
+    let own = sysfs.join("block").join(kernel_name);
+    let is_partition = sysfs
+        .join("class/block")
+        .join(kernel_name)
+        .join("partition")
+        .exists();
+    if own.exists() || !is_partition {
+        return own.join("queue");
+    }
+    sysfs
+        .join("block")
+        .join(parent_disk_name_in(sysfs, kernel_name))
+        .join("queue")
+}
+
+/// Parse `smartctl -c -l selftest` output (ATA and NVMe)
+/// ATA: "Self-test execution status: ( 249) Self-test routine in progress..." followed
+/// by "90% of test remaining."; NVMe: "Self-test status: Short self-test in progress
+/// (45% completed)". The newest log entry is the first row under the "Num" header.
+fn parse_self_test_status(output: &str) -> SmartSelfTestStatus {
+    let lines: Vec<&str> = output.lines().map(str::trim).collect();
+
+    let status_index = lines.iter().position(|l| {
+        l.starts_with("Self-test execution status") || l.starts_with("Self-test status")
+    });
+    // NVMe reports an idle device as "No self-test in progress"
+    let in_progress = status_index
+        .is_some_and(|i| lines[i].contains("in progress") && !lines[i].contains("No self-test"));
+
+    let percent_before = |text: &str, suffix: &str| -> Option<u32> {
+        let end = text.find(suffix)?;
+        let start = text[..end]
+            .rfind(|c: char| !c.is_ascii_digit())
+            .map_or(0, |i| i + 1);
+        text[start..end].parse().ok()
+    };
+    let percent_remaining = status_index.filter(|_| in_progress).and_then(|i| {
+        lines[i..]
+            .iter()
+            .take(3)
+            .find_map(|l| percent_before(l, "% of test remaining"))
+            .or_else(|| percent_before(lines[i], "% completed").map(|done| 100 - done.min(100)))
+    });
+
+    let last_result = lines
+        .iter()
+        .position(|l| l.starts_with("Num"))
+        .and_then(|header| lines[header + 1..].iter().find(|l| !l.is_empty()))
+        .and_then(|row| {
+            // Columns are separated by runs of spaces; single spaces occur inside them
+            row.split("  ")
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .nth(2)
+                .map(str::to_string)
+        });
+
+    SmartSelfTestStatus {
+        in_progress,
+        percent_remaining,
+        last_result,
+    }
+}
+
+/// Mount options per mount point from /proc/mounts; empty if it can't be read
+fn read_mount_options() -> HashMap<String, Vec<String>> {
+    fs::read_to_string("/proc/mounts")
+        .map(|contents| parse_mount_options(&contents))
+        .unwrap_or_default()
+}
+
+/// Parse /proc/mounts lines: "device mount_point fs_type options dump pass"
+/// When several mounts share a mount point (bind or over-mounts) the last line wins,
+/// since it is the one currently visible at that path
+fn parse_mount_options(contents: &str) -> HashMap<String, Vec<String>> {
+    let mut mounts = HashMap::new();
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(_device), Some(mount_point), Some(_fs_type), Some(options)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let options = options
+            .split(',')
+            .filter(|o| !o.is_empty())
+            .map(str::to_string)
+            .collect();
+        mounts.insert(unescape_mount_field(mount_point), options);
+    }
+    mounts
+}
+
+/// Decode the octal escapes the kernel uses in /proc/mounts (\040 for space, \011 for tab, ...)
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|octal| u8::from_str_radix(std::str::from_utf8(octal).ok()?, 8).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Read/write rate of the whole disk list, counting each physical device once
+/// Partitions have their own diskstats rows, so a physical disk's activity is the sum over
+/// its distinct partitions; repeated mounts of one device are skipped, and a mounted whole
+/// disk (no partition table) already includes everything below it
+fn total_transfer_rates(disks: &[DiskInfo]) -> (f64, f64) {
+    let mut totals = (0.0, 0.0);
+    for (disk_name, entries) in group_by_physical_disk(disks) {
+        let counted: Vec<&DiskInfo> =
+            match entries.iter().find(|d| kernel_device_name(&d.name) == disk_name) {
+                Some(whole_disk) => vec![*whole_disk],
+                None => {
+                    let mut seen = HashSet::new();
+                    entries.into_iter().filter(|d| seen.insert(d.name.as_str())).collect()
+                }
+            };
+        for disk in counted {
+            totals.0 += disk.read_rate_bps;
+            totals.1 += disk.write_rate_bps;
+        }
+    }
+    totals
+}
+
+/// Group disks under their physical disk (sda1, sda2 -> sda), sorted by disk name
+/// Entries without a /dev/ device (tmpfs, overlay, ...) are left out
+fn group_by_physical_disk<T: Borrow<DiskInfo>>(
+    disks: impl IntoIterator<Item = T>,
+) -> BTreeMap<String, Vec<T>> {
+    let mut grouped: BTreeMap<String, Vec<T>> = BTreeMap::new();
+    for disk in disks {
+        let name = &disk.borrow().name;
+        if !name.starts_with("/dev/") {
+            continue;
+        }
+        grouped.entry(parent_disk_name(name)).or_default().push(disk);
+    }
+    grouped
+}
+
+/// Map a partition name to its parent disk (e.g. sda1 -> sda, nvme0n1p2 -> nvme0n1)
+/// Whole-disk names are returned unchanged, without the /dev/ prefix
+pub fn parent_disk_name(name: &str) -> String {
+    parent_disk_name_in(Path::new(SYSFS_ROOT), name)
+}
+
+/// The kernel knows which devices are partitions: their /sys/class/block entry has a
+/// `partition` file and sits inside the parent disk's directory. Names sysfs doesn't
+/// know (other platforms, /dev/mapper aliases) fall back to the name's suffix.
+fn parent_disk_name_in(sysfs: &Path, name: &str) -> String {
+    let name = name.trim_start_matches("/dev/");
+    let entry = sysfs.join("class/block").join(name);
+    if entry.exists() {
+        if !entry.join("partition").exists() {
+            return name.to_string();
+        }
+        if let Some(parent) = fs::canonicalize(entry.join(".."))
+            .ok()
+            .and_then(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        {
+            return parent;
+        }
+    }
+    parent_disk_name_from_suffix(name)
+}
+
+fn parent_disk_name_from_suffix(name: &str) -> String {
+    // Devices whose disk name ends in a digit use a "pN" partition suffix
+    if let Some(pos) = name.rfind('p') {
+        let (base, part) = (&name[..pos], &name[pos + 1..]);
+        if !part.is_empty()
+            && part.chars().all(|c| c.is_ascii_digit())
+            && base.ends_with(|c: char| c.is_ascii_digit())
+        {
+            return base.to_string();
+        }
+    }
+
+    // Whole disks whose name ends in an instance number (dm-0 and dm-1 are different disks)
+    const DIGIT_SUFFIXED: [&str; 8] = ["nvme", "mmcblk", "loop", "nbd", "md", "dm-", "sr", "zram"];
+    if DIGIT_SUFFIXED.iter().any(|prefix| name.starts_with(prefix)) {
+        return name.to_string();
+    }
+
+    // sda1 -> sda, vdb2 -> vdb
+    name.trim_end_matches(|c: char| c.is_ascii_digit()).to_string()
+}
+
 impl Default for DiskMonitor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::{FakeCommandRunner, ScratchDir};
+    use std::os::unix::fs::symlink;
+
+    const SCAN_OUTPUT: &str = "/dev/sdz -d sat # /dev/sdz [SAT], ATA device\n";
+
+    const ATA_SMART_OUTPUT: &str = "\
+smartctl 7.3 2022-02-28 r5338 [x86_64-linux-6.1.0] (local build)
+
+=== START OF READ SMART DATA SECTION ===
+SMART overall-health self-assessment test result: PASSED
+
+ID# ATTRIBUTE_NAME          FLAG     VALUE WORST THRESH TYPE      UPDATED  WHEN_FAILED RAW_VALUE
+  5 Reallocated_Sector_Ct   0x0033   100   100   010    Pre-fail  Always       -       0
+  9 Power_On_Hours          0x0032   095   095   000    Old_age   Always       -       21543
+ 12 Power_Cycle_Count       0x0032   099   099   000    Old_age   Always       -       1234
+194 Temperature_Celsius     0x0022   066   052   000    Old_age   Always       -       34 (Min/Max 18/48)
+";
+
+    /// Monitor whose smartctl is the returned fake, with /dev/sdz already scanned
+    fn monitor_with_smartctl() -> (DiskMonitor, FakeCommandRunner) {
+        let runner = FakeCommandRunner::default();
+        runner.respond("smartctl --scan", 0, SCAN_OUTPUT);
+        runner.respond("smartctl -H -A", 0, ATA_SMART_OUTPUT);
+        (DiskMonitor::with_runner(Box::new(runner.clone())), runner)
+    }
+
+    /// Fake sysfs with a partitioned sda, dm-0 and dm-1 and an optical drive
+    fn fake_sysfs() -> ScratchDir {
+        let sysfs = ScratchDir::new("disk-sysfs");
+        for device in ["devices/sda/sda1", "devices/virtual/dm-0", "devices/virtual/dm-1"] {
+            fs::create_dir_all(sysfs.join(device)).unwrap();
+        }
+        fs::create_dir_all(sysfs.join("devices/sr0")).unwrap();
+        fs::write(sysfs.join("devices/sda/sda1/partition"), "1\n").unwrap();
+        fs::create_dir_all(sysfs.join("class/block")).unwrap();
+        fs::create_dir_all(sysfs.join("block")).unwrap();
+        for (name, target) in [
+            ("sda", "devices/sda"),
+            ("sda1", "devices/sda/sda1"),
+            ("dm-0", "devices/virtual/dm-0"),
+            ("dm-1", "devices/virtual/dm-1"),
+            ("sr0", "devices/sr0"),
+        ] {
+            symlink(sysfs.join(target), sysfs.join("class/block").join(name)).unwrap();
+            if !target.ends_with("sda1") {
+                symlink(sysfs.join(target), sysfs.join("block").join(name)).unwrap();
+            }
+        }
+        sysfs
+    }
+
+    #[test]
+    fn parent_disk_name_resolves_through_sysfs() {
+        let sysfs = fake_sysfs();
+        assert_eq!(parent_disk_name_in(&sysfs, "/dev/sda1"), "sda");
+        assert_eq!(parent_disk_name_in(&sysfs, "/dev/sda"), "sda");
+        assert_eq!(parent_disk_name_in(&sysfs, "dm-0"), "dm-0");
+        assert_eq!(parent_disk_name_in(&sysfs, "dm-1"), "dm-1");
+        assert_eq!(parent_disk_name_in(&sysfs, "sr0"), "sr0");
+    }
+
+    #[test]
+    fn parent_disk_name_falls_back_to_suffix() {
+        let sysfs = Path::new("/nonexistent/sys");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/sdb2"), "sdb");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/nvme0n1p2"), "nvme0n1");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/nvme0n1"), "nvme0n1");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/mmcblk0p1"), "mmcblk0");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/dm-0"), "dm-0");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/dm-1"), "dm-1");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/sr0"), "sr0");
+        assert_eq!(parent_disk_name_in(sysfs, "/dev/zram0"), "zram0");
+    }
+
+    #[test]
+    fn diskstats_latency_and_rates() {
+        //   major minor name reads merged sectors ms writes merged sectors ms in_flight
+        //   io_ms weighted_ms
+        let first = DiskMonitor::parse_diskstats(
+            "   8       0 sda 1000 0 20000 500 2000 0 40000 1500 0 1800 2000\n\
+                8       1 sda1 10 0 80 5 0 0 0 0 0 5 5\n",
+        );
+        let second = DiskMonitor::parse_diskstats(
+            "   8       0 sda 1060 0 22048 560 2040 0 44144 1900 3 2200 2500\n",
+        );
+        assert_eq!(first.len(), 2);
+
+        let before = first["sda"];
+        let after = second["sda"];
+        assert_eq!(after.read_bytes, 22048 * 512);
+        assert_eq!(after.completed_ios, 3100);
+        assert_eq!(after.in_flight, 3);
+
+        let taken_at = Instant::now();
+        let last = DiskIoSample {
+            read_bytes: before.read_bytes,
+            written_bytes: before.written_bytes,
+            completed_ios: before.completed_ios,
+            weighted_io_ms: before.weighted_io_ms,
+            taken_at,
+        };
+        // 500 ms of weighted I/O time over 100 completed ops
+        assert_eq!(DiskMonitor::average_latency_ms(&last, &after), 5.0);
+        // 2048 sectors read and 4144 written over two seconds
+        let (read, write) =
+            DiskMonitor::transfer_rates(&last, &after, taken_at + Duration::from_secs(2));
+        assert_eq!(read, 2048.0 * 512.0 / 2.0);
+        assert_eq!(write, 4144.0 * 512.0 / 2.0);
+
+        // No completed ops in the interval: no latency rather than a division by zero
+        assert_eq!(DiskMonitor::average_latency_ms(&last, &before), 0.0);
+    }
+
+    #[test]
+    fn smart_info_comes_from_smartctl_attributes() {
+        let (monitor, runner) = monitor_with_smartctl();
+        let smart = monitor.get_smart_info_cached("/dev/sdz1").unwrap();
+        assert!(matches!(smart.health, SmartHealth::Passed));
+        assert_eq!(smart.temperature, Some(34));
+        assert_eq!(smart.power_on_hours, Some(21543));
+        assert_eq!(smart.power_cycle_count, Some(1234));
+        // The -d hint from the scan is passed through for the partition's parent disk
+        assert_eq!(runner.calls(), ["smartctl --scan", "smartctl -H -A -d sat /dev/sdz"]);
+    }
+
+    #[test]
+    fn failing_smartctl_yields_no_data() {
+        let (monitor, runner) = monitor_with_smartctl();
+        runner.respond("smartctl -H -A", 2, "Smartctl open device: /dev/sdz failed\n");
+        assert!(monitor.get_smart_info_cached("/dev/sdz").is_none());
+    }
+
+    #[test]
+    fn missing_smartctl_is_only_probed_once() {
+        let runner = FakeCommandRunner::default();
+        let monitor = DiskMonitor::with_runner(Box::new(runner.clone()));
+        assert!(monitor.get_smart_info_cached("/dev/sdz").is_none());
+        monitor.invalidate_smart_cache();
+        assert!(monitor.get_smart_info_cached("/dev/sdz").is_none());
+        assert_eq!(runner.calls(), ["smartctl --scan"]);
+        assert!(matches!(monitor.list_smart_devices(), Err(MonitorError::Unsupported(_))));
+    }
+
+    #[test]
+    fn parses_mount_options_and_unescapes_paths() {
+        let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime,errors=remount-ro 0 0\n\
+                      /dev/sdb1 /media/user/My\\040Drive vfat ro,nosuid,nodev 0 0\n\
+                      /dev/sdc1 /mnt/tab\\011dir ext4 rw 0 0\n\
+                      /dev/sdd1 /srv ext4 rw,noatime 0 0\n\
+                      /dev/sde1 /srv xfs ro 0 0\n\
+                      truncated-line\n";
+        let options = parse_mount_options(mounts);
+        assert_eq!(options["/"], ["rw", "relatime", "errors=remount-ro"]);
+        assert_eq!(options["/media/user/My Drive"], ["ro", "nosuid", "nodev"]);
+        assert_eq!(options["/mnt/tab\tdir"], ["rw"]);
+        // Over-mount: the last line is the visible one
+        assert_eq!(options["/srv"], ["ro"]);
+        assert_eq!(options.len(), 4);
+
+        assert_eq!(unescape_mount_field("/a\\134b"), "/a\\b");
+        assert_eq!(unescape_mount_field("/new\\012line"), "/new\nline");
+        // Not an octal escape: kept as is
+        assert_eq!(unescape_mount_field("/odd\\09x"), "/odd\\09x");
+        assert_eq!(unescape_mount_field("/end\\04"), "/end\\04");
+    }
+
+    #[test]
+    fn scan_directory_sums_allocated_space() {
+        let root = ScratchDir::new("disk-scan");
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::write(root.join("a/file"), vec![1u8; 5000]).unwrap();
+        fs::write(root.join("a/b/c/deep"), vec![1u8; 9000]).unwrap();
+        fs::write(root.join("top"), vec![1u8; 100]).unwrap();
+        // Sparse: a megabyte long, nothing allocated
+        fs::File::create(root.join("sparse")).unwrap().set_len(1 << 20).unwrap();
+
+        let allocated = |path: &str| fs::metadata(root.join(path)).unwrap().blocks() * 512;
+        let size_of = |entries: &[DirEntry], name: &str| {
+            let path = root.join(name).to_string_lossy().to_string();
+            entries.iter().find(|e| e.path == path).unwrap().size_bytes
+        };
+
+        let shallow = scan_directory(&root, 0).unwrap();
+        assert_eq!(shallow.len(), 3);
+        assert_eq!(size_of(&shallow, "a"), allocated("a/file"));
+        assert_eq!(size_of(&shallow, "top"), allocated("top"));
+        assert!(size_of(&shallow, "sparse") < 1 << 20);
+        assert!(shallow.windows(2).all(|w| w[0].size_bytes >= w[1].size_bytes));
+
+        let deep = scan_directory(&root, 2).unwrap();
+        assert_eq!(size_of(&deep, "a"), allocated("a/file") + allocated("a/b/c/deep"));
+    }
+
+    #[test]
+    fn hidden_disks_leave_the_list_and_totals() {
+        let (monitor, _runner) = monitor_with_smartctl();
+        let disks = monitor.refresh().disks;
+
+        // Hide the first disk by device name and the second by mount point
+        let mut hide = vec!["/zz/not-mounted".to_string()];
+        hide.extend(disks.first().map(|d| d.name.clone()));
+        hide.extend(disks.get(1).map(|d| d.mount_point.clone()));
+        monitor.set_hidden(hide.clone());
+
+        let info = monitor.refresh();
+        let is_hidden = |d: &DiskInfo| hide.contains(&d.name) || hide.contains(&d.mount_point);
+        let shown: Vec<_> = disks.iter().filter(|d| !is_hidden(d)).collect();
+        assert_eq!(info.disks.len(), shown.len());
+        assert!(!info.disks.iter().any(is_hidden));
+        assert_eq!(info.total_space, shown.iter().map(|d| d.total_space).sum::<u64>());
+        hide.sort();
+        assert_eq!(info.hidden, hide);
+    }
+
+    #[test]
+    fn force_refresh_reruns_smartctl_within_the_cache_window() {
+        let (monitor, runner) = monitor_with_smartctl();
+        monitor.set_smart_cache_duration(3600);
+        let smart_calls = |runner: &FakeCommandRunner| {
+            runner.calls().iter().filter(|c| c.starts_with("smartctl -H")).count()
+        };
+
+        monitor.get_smart_info_cached("/dev/sdz");
+        monitor.get_smart_info_cached("/dev/sdz1");
+        assert_eq!(smart_calls(&runner), 1);
+
+        // What a force refresh does: the next read runs smartctl and caches the new data
+        runner.respond("smartctl -H -A", 0, &ATA_SMART_OUTPUT.replace("PASSED", "FAILED!"));
+        monitor.invalidate_smart_cache();
+        let smart = monitor.get_smart_info_cached("/dev/sdz").unwrap();
+        assert!(matches!(smart.health, SmartHealth::Failed));
+        assert_eq!(smart_calls(&runner), 2);
+
+        let cached = monitor.get_smart_info_cached("/dev/sdz").unwrap();
+        assert!(matches!(cached.health, SmartHealth::Failed));
+        assert_eq!(smart_calls(&runner), 2);
+    }
+
+    #[test]
+    fn parses_self_test_progress_and_last_result() {
+        let ata = |status: &str, log_row: &str| {
+            format!(
+                "General SMART Values:\n\
+                 Offline data collection status:  (0x00)\tOffline data collection activity\n\
+                 \t\t\t\t\twas never started.\n\
+                 Self-test execution status:      {}\n\
+                 Total time to complete Offline\n\
+                 data collection: \t\t(  600) seconds.\n\
+                 \n\
+                 SMART Self-test log structure revision number 1\n\
+                 Num  Test_Description    Status                  Remaining  LifeTime(hours)  \
+                 LBA_of_first_error\n\
+                 {}\n",
+                status, log_row
+            )
+        };
+        let completed = "# 1  Short offline       Completed without error       00%     12345  -";
+
+        let running = parse_self_test_status(&ata(
+            "( 249)\tSelf-test routine in progress...\n\t\t\t\t\t90% of test remaining.",
+            completed,
+        ));
+        assert!(running.in_progress);
+        assert_eq!(running.percent_remaining, Some(90));
+        assert_eq!(running.last_result.as_deref(), Some("Completed without error"));
+
+        let done = parse_self_test_status(&ata(
+            "(   0)\tThe previous self-test routine completed\n\t\t\t\t\twithout error.",
+            completed,
+        ));
+        assert!(!done.in_progress);
+        assert_eq!(done.percent_remaining, None);
+        assert_eq!(done.last_result.as_deref(), Some("Completed without error"));
+
+        let aborted = parse_self_test_status(&ata(
+            "(  16)\tThe previous self-test routine was aborted by\n\t\t\t\t\tthe host.",
+            "# 1  Extended offline    Aborted by host               90%     12350  -",
+        ));
+        assert!(!aborted.in_progress);
+        assert_eq!(aborted.last_result.as_deref(), Some("Aborted by host"));
+
+        let nvme = parse_self_test_status(
+            "Self-test status: Short self-test in progress (45% completed)\n\
+             Num  Test_Description  Status                       Power_on_Hours  Failing_LBA\n \
+             0   Short             Completed without error                1234            -\n",
+        );
+        assert!(nvme.in_progress);
+        assert_eq!(nvme.percent_remaining, Some(55));
+        assert_eq!(nvme.last_result.as_deref(), Some("Completed without error"));
+
+        // No log yet
+        let empty = parse_self_test_status("Self-test status: No self-test in progress\n");
+        assert!(!empty.in_progress);
+        assert_eq!((empty.percent_remaining, empty.last_result), (None, None));
+    }
+
+    #[test]
+    fn self_test_start_is_confirmed_by_smartctl() {
+        let (monitor, runner) = monitor_with_smartctl();
+        runner.respond("smartctl -t", 0, "Testing has begun.\nPlease wait 2 minutes.\n");
+        monitor.run_smart_self_test("/dev/sdz", None, SelfTestKind::Short).unwrap();
+        assert_eq!(runner.calls().last().unwrap(), "smartctl -t short -d sat /dev/sdz");
+
+        let denied = "Smartctl open device: /dev/sdz failed: Permission denied\n";
+        runner.respond("smartctl -t", 4, denied);
+        assert!(matches!(
+            monitor.run_smart_self_test("/dev/sdz", None, SelfTestKind::Long),
+            Err(MonitorError::PermissionDenied(_))
+        ));
+        // Devices the scan didn't report must be real block devices
+        assert!(monitor.run_smart_self_test("--scan", None, SelfTestKind::Short).is_err());
+    }
+
+    #[test]
+    fn parses_fstrim_byte_counts() {
+        assert_eq!(
+            parse_fstrim_output("/home: 1.2 GiB (1288490188 bytes) trimmed\n"),
+            Some(1_288_490_188)
+        );
+        // util-linux 2.36+ names the device too
+        assert_eq!(
+            parse_fstrim_output("/: 0 B (0 bytes) trimmed on /dev/nvme0n1p2\n"),
+            Some(0)
+        );
+        assert_eq!(parse_fstrim_output("/boot: 120 MiB trimmed\n"), None);
+        assert_eq!(parse_fstrim_output("/srv: (lots bytes) trimmed\n"), None);
+        assert_eq!(parse_fstrim_output(""), None);
+    }
+
+    #[test]
+    fn total_rates_count_each_physical_device_once() {
+        let disk = |name: &str, mount_point: &str, read: f64, write: f64| DiskInfo {
+            name: name.to_string(),
+            mount_point: mount_point.to_string(),
+            read_rate_bps: read,
+            write_rate_bps: write,
+            ..Default::default()
+        };
+        let disks = [
+            // Two partitions of one disk, one of them bind-mounted a second time
+            disk("/dev/sdy1", "/", 100.0, 10.0),
+            disk("/dev/sdy2", "/home", 50.0, 5.0),
+            disk("/dev/sdy2", "/srv/home", 50.0, 5.0),
+            // A whole-disk filesystem next to a partition of the same disk: the disk's
+            // diskstats row already covers the partition
+            disk("/dev/sdx", "/mnt/raw", 400.0, 40.0),
+            disk("/dev/sdx1", "/mnt/part", 300.0, 30.0),
+            disk("tmpfs", "/tmp", 999.0, 999.0),
+        ];
+        assert_eq!(total_transfer_rates(&disks), (550.0, 55.0));
+        assert_eq!(total_transfer_rates(&disks[..2]), (150.0, 15.0));
+        assert_eq!(total_transfer_rates(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn reset_counters_restarts_since_start_and_rates() {
+        let (monitor, _runner) = monitor_with_smartctl();
+        monitor.refresh();
+        monitor.reset_counters();
+
+        let info = monitor.refresh();
+        for disk in &info.disks {
+            assert_eq!((disk.read_since_start, disk.written_since_start), (0, 0), "{}", disk.name);
+            assert_eq!((disk.read_rate_bps, disk.write_rate_bps), (0.0, 0.0));
+        }
+        assert_eq!((info.total_read_rate_bps, info.total_write_rate_bps), (0.0, 0.0));
+    }
+
+    #[test]
+    fn parses_smartctl_scan_output() {
+        let devices = parse_smart_scan(
+            "/dev/sda -d sat # /dev/sda [SAT], ATA device\n\
+             /dev/nvme0 -d nvme # /dev/nvme0, NVMe device\n\
+             /dev/bus/0 -d megaraid,0 # /dev/bus/0 [megaraid_disk_00], SCSI device\n\
+             # comment-only line\n\
+             /dev/sdb\n",
+        );
+        assert_eq!(devices.len(), 4);
+        assert_eq!(devices[0].path, "/dev/sda");
+        assert_eq!(devices[0].device_type.as_deref(), Some("sat"));
+        assert_eq!(devices[0].description, "/dev/sda [SAT], ATA device");
+        assert_eq!(devices[2].path, "/dev/bus/0");
+        assert_eq!(devices[2].device_type.as_deref(), Some("megaraid,0"));
+        assert_eq!(devices[3].device_type, None);
+        assert_eq!(devices[3].description, "");
+    }
+
+    #[test]
+    fn nvme_namespaces_map_to_their_scanned_controller() {
+        let devices = parse_smart_scan(
+            "/dev/sda -d sat # /dev/sda [SAT], ATA device\n\
+             /dev/nvme0 -d nvme # /dev/nvme0, NVMe device\n\
+             /dev/nvme1 -d nvme # /dev/nvme1, NVMe device\n",
+        );
+        let path_for = |disk: &str| scanned_device_for(&devices, disk).map(|device| device.path);
+
+        assert_eq!(path_for("sda").as_deref(), Some("/dev/sda"));
+        assert_eq!(path_for("nvme0n1").as_deref(), Some("/dev/nvme0"));
+        assert_eq!(path_for("nvme1n2").as_deref(), Some("/dev/nvme1"));
+        // nvme10n1 must not match the nvme1 controller
+        assert_eq!(path_for("nvme10n1"), None);
+        assert_eq!(path_for("nvme0n"), None);
+        assert_eq!(path_for("sdb"), None);
+    }
+
+    #[test]
+    fn serializes_camel_case_field_names() {
+        let json = serde_json::to_value(DiskInfo::default()).unwrap();
+        for key in [
+            "mountPoint",
+            "fileSystem",
+            "totalSpace",
+            "usagePercent",
+            "isRemovable",
+            "logicalBlockSize",
+            "readSinceStart",
+            "readRateBps",
+            "avgLatencyMs",
+            "queueDepth",
+        ] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+        assert!(json.as_object().unwrap().keys().all(|key| !key.contains('_')));
+    }
+
+    #[test]
+    fn queue_dir_prefers_the_device_own_queue() {
+        let sysfs = fake_sysfs();
+        assert_eq!(queue_dir_in(&sysfs, "sda"), sysfs.join("block/sda/queue"));
+        assert_eq!(queue_dir_in(&sysfs, "sda1"), sysfs.join("block/sda/queue"));
+        assert_eq!(queue_dir_in(&sysfs, "dm-0"), sysfs.join("block/dm-0/queue"));
+        assert_eq!(queue_dir_in(&sysfs, "sr0"), sysfs.join("block/sr0/queue"));
+    }
+
+    #[test]
+    fn kernel_device_names_resolve_symlinks() {
+        let dev = ScratchDir::new("dev");
+        dev.write("dm-0", "");
+        dev.write("sda1", "");
+        std::fs::create_dir_all(dev.join("mapper")).unwrap();
+        std::fs::create_dir_all(dev.join("disk/by-uuid")).unwrap();
+        std::os::unix::fs::symlink("../dm-0", dev.join("mapper/vg-root")).unwrap();
+        std::os::unix::fs::symlink("../../sda1", dev.join("disk/by-uuid/0a1b-2c3d")).unwrap();
+
+        let name = |path: &str| kernel_device_name(dev.join(path).to_str().unwrap());
+        assert_eq!(name("mapper/vg-root"), "dm-0");
+        assert_eq!(name("disk/by-uuid/0a1b-2c3d"), "sda1");
+        assert_eq!(name("sda1"), "sda1");
+
+        // Devices that don't exist here, and sysinfo names that aren't paths
+        assert_eq!(kernel_device_name("/dev/nvme7n1p3"), "nvme7n1p3");
+        assert_eq!(kernel_device_name("overlay"), "overlay");
+    }
+
+    #[test]
+    fn parses_udisks_mount_points() {
+        assert_eq!(
+            parse_udisks_mount_output("Mounted /dev/sdb1 at /media/user/USB STICK\n").as_deref(),
+            Some("/media/user/USB STICK")
+        );
+        // Older udisks ends the line with a period
+        assert_eq!(
+            parse_udisks_mount_output("Mounted /dev/sdb1 at /media/user/USB.\n").as_deref(),
+            Some("/media/user/USB")
+        );
+        assert_eq!(parse_udisks_mount_output("Mounted /dev/sdb1 at .\n"), None);
+        assert_eq!(parse_udisks_mount_output("Error mounting /dev/sdb1\n"), None);
+    }
+
+    #[test]
+    fn mounts_and_unmounts_through_udisks_or_mount() {
+        let runner = FakeCommandRunner::default();
+        let monitor = DiskMonitor::with_runner(Box::new(runner.clone()));
+
+        runner.respond("udisksctl mount", 0, "Mounted /dev/sdb1 at /media/user/USB.\n");
+        assert_eq!(monitor.mount_validated("/dev/sdb1", None).unwrap(), "/media/user/USB");
+        let target = ScratchDir::new("mount-target");
+        let target = target.to_str().unwrap();
+        runner.respond("mount /dev/sdb1", 0, "");
+        assert_eq!(monitor.mount_validated("/dev/sdb1", Some(target)).unwrap(), target);
+        // Relative targets could be read as mount options
+        assert!(monitor.mount_validated("/dev/sdb1", Some("media/usb")).is_err());
+
+        runner.respond("udisksctl unmount", 0, "Unmounted /dev/sdb1.\n");
+        monitor.unmount_validated("/dev/sdb1", "/media/user/USB").unwrap();
+        let busy = "Error unmounting /dev/sdb1: GDBus.Error:org.freedesktop.UDisks2.Error.\
+                    DeviceBusy: Error unmounting /dev/sdb1: target is busy\n";
+        runner.respond("udisksctl unmount", 1, busy);
+        assert!(matches!(
+            monitor.unmount_validated("/dev/sdb1", "/media/user/USB"),
+            Err(MonitorError::DeviceBusy(_))
+        ));
+        assert_eq!(
+            runner.calls(),
+            [
+                "udisksctl mount --block-device /dev/sdb1 --no-user-interaction".to_string(),
+                format!("mount /dev/sdb1 {}", target),
+                "udisksctl unmount --block-device /dev/sdb1 --no-user-interaction".to_string(),
+                "udisksctl unmount --block-device /dev/sdb1 --no-user-interaction".to_string(),
+            ]
+        );
+
+        // Without udisksctl, unmounting falls back to umount
+        let runner = FakeCommandRunner::default();
+        let monitor = DiskMonitor::with_runner(Box::new(runner.clone()));
+        runner.respond("umount", 32, "umount: /media/usb: must be superuser to unmount.\n");
+        assert!(matches!(
+            monitor.unmount_validated("/dev/sdb1", "/media/usb"),
+            Err(MonitorError::PermissionDenied(_))
+        ));
+        assert_eq!(runner.calls().last().unwrap(), "umount /media/usb");
+        assert!(matches!(
+            monitor.mount_validated("/dev/sdb1", None),
+            Err(MonitorError::Unsupported(_))
+        ));
+    }
+}
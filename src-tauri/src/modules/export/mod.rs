@@ -0,0 +1,663 @@
+// Export Module
+// Serializes metrics into external formats (Prometheus exposition text, CSV/JSON history)
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::modules::{
+    cpu::CpuMonitor, disk::DiskInfo, gpu::GpuInfo, network::NetworkInterface,
+    network::NetworkMonitor, sensors::SensorType, snapshot::Snapshot, MonitorError,
+};
+
+/// History export format
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Samples of one metric series as (timestamp ms, value)
+type Series = Vec<(u64, f64)>;
+
+/// Metric rows keyed by timestamp; a series may be missing at a given timestamp
+type Rows = BTreeMap<u64, HashMap<String, f64>>;
+
+/// Extracts one sample value from a per-item struct
+type Field<T> = fn(&T) -> f64;
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+/// Writes metric families in the Prometheus text exposition format
+struct PrometheusWriter {
+    out: String,
+}
+
+impl PrometheusWriter {
+    fn new() -> Self {
+        Self { out: String::new() }
+    }
+
+    /// Start a metric family; all its samples must follow before the next family
+    fn family(&mut self, name: &str, help: &str, kind: MetricKind) {
+        let kind = match kind {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        };
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", sanitize_label_name(k), escape_label_value(v)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+
+    /// Family with a single unlabeled sample
+    fn single(&mut self, name: &str, help: &str, kind: MetricKind, value: f64) {
+        self.family(name, help, kind);
+        self.sample(name, &[], value);
+    }
+}
+
+/// Label names must match [a-zA-Z_][a-zA-Z0-9_]*
+fn sanitize_label_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Escape backslash, double quote and newline in label values
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format a sample value; Rust's float formatting is locale-independent
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render a snapshot in the Prometheus text exposition format
+pub fn to_prometheus(snapshot: &Snapshot) -> String {
+    use MetricKind::{Counter, Gauge};
+    let mut w = PrometheusWriter::new();
+
+    // CPU
+    w.single(
+        "nova_cpu_global_usage_percent",
+        "Global CPU usage in percent.",
+        Gauge,
+        snapshot.cpu.global_usage as f64,
+    );
+    w.family(
+        "nova_cpu_usage_percent",
+        "Per-core CPU usage in percent.",
+        Gauge,
+    );
+    for (i, core) in snapshot.cpu.cores.iter().enumerate() {
+        w.sample(
+            "nova_cpu_usage_percent",
+            &[("core", &i.to_string())],
+            core.usage as f64,
+        );
+    }
+    w.family(
+        "nova_cpu_frequency_mhz",
+        "Per-core CPU frequency in MHz.",
+        Gauge,
+    );
+    for (i, core) in snapshot.cpu.cores.iter().enumerate() {
+        w.sample(
+            "nova_cpu_frequency_mhz",
+            &[("core", &i.to_string())],
+            core.frequency as f64,
+        );
+    }
+
+    // Memory
+    let memory = &snapshot.memory;
+    w.single(
+        "nova_memory_total_bytes",
+        "Total RAM in bytes.",
+        Gauge,
+        memory.total_memory as f64,
+    );
+    w.single(
+        "nova_memory_used_bytes",
+        "Used RAM in bytes.",
+        Gauge,
+        memory.used_memory as f64,
+    );
+    w.single(
+        "nova_memory_available_bytes",
+        "Available RAM in bytes.",
+        Gauge,
+        memory.available_memory as f64,
+    );
+    w.single(
+        "nova_swap_total_bytes",
+        "Total swap in bytes.",
+        Gauge,
+        memory.total_swap as f64,
+    );
+    w.single(
+        "nova_swap_used_bytes",
+        "Used swap in bytes.",
+        Gauge,
+        memory.used_swap as f64,
+    );
+
+    // Disk
+    let disk_families: [(&str, &str, MetricKind, Field<DiskInfo>); 4] = [
+        (
+            "nova_disk_total_bytes",
+            "Filesystem size in bytes.",
+            Gauge,
+            |d| d.total_space as f64,
+        ),
+        (
+            "nova_disk_used_bytes",
+            "Filesystem used space in bytes.",
+            Gauge,
+            |d| d.used_space as f64,
+        ),
+        (
+            "nova_disk_read_bytes_total",
+            "Bytes read since boot.",
+            Counter,
+            |d| d.read_bytes as f64,
+        ),
+        (
+            "nova_disk_written_bytes_total",
+            "Bytes written since boot.",
+            Counter,
+            |d| d.written_bytes as f64,
+        ),
+    ];
+    for (name, help, kind, value) in disk_families {
+        w.family(name, help, kind);
+        for d in &snapshot.disk.disks {
+            w.sample(
+                name,
+                &[("device", &d.name), ("mountpoint", &d.mount_point)],
+                value(d),
+            );
+        }
+    }
+
+    // Network
+    let network_families: [(&str, &str, MetricKind, Field<NetworkInterface>); 4] = [
+        (
+            "nova_network_received_bytes_total",
+            "Bytes received since boot.",
+            Counter,
+            |i| i.received_bytes as f64,
+        ),
+        (
+            "nova_network_transmitted_bytes_total",
+            "Bytes transmitted since boot.",
+            Counter,
+            |i| i.transmitted_bytes as f64,
+        ),
+        (
+            "nova_network_download_bytes_per_second",
+            "Current download rate.",
+            Gauge,
+            |i| i.download_rate_bps,
+        ),
+        (
+            "nova_network_upload_bytes_per_second",
+            "Current upload rate.",
+            Gauge,
+            |i| i.upload_rate_bps,
+        ),
+    ];
+    for (name, help, kind, value) in network_families {
+        w.family(name, help, kind);
+        for iface in &snapshot.network.interfaces {
+            w.sample(name, &[("interface", &iface.name)], value(iface));
+        }
+    }
+
+    // GPU
+    let gpu_families: [(&str, &str, MetricKind, Field<GpuInfo>); 5] = [
+        (
+            "nova_gpu_utilization_percent",
+            "GPU utilization in percent.",
+            Gauge,
+            |g| g.utilization_gpu as f64,
+        ),
+        (
+            "nova_gpu_memory_used_bytes",
+            "GPU memory used in bytes.",
+            Gauge,
+            |g| g.memory_used as f64,
+        ),
+        (
+            "nova_gpu_memory_total_bytes",
+            "GPU memory size in bytes.",
+            Gauge,
+            |g| g.memory_total as f64,
+        ),
+        (
+            "nova_gpu_temperature_celsius",
+            "GPU temperature in Celsius.",
+            Gauge,
+            |g| g.temperature as f64,
+        ),
+        (
+            "nova_gpu_power_watts",
+            "GPU power draw in watts.",
+            Gauge,
+            |g| g.power_usage as f64 / 1000.0,
+        ),
+    ];
+    for (name, help, kind, value) in gpu_families {
+        w.family(name, help, kind);
+        for gpu in &snapshot.gpu.gpus {
+            w.sample(name, &[("gpu", &gpu.uuid), ("name", &gpu.name)], value(gpu));
+        }
+    }
+
+    // Sensors
+    w.family(
+        "nova_sensor_temperature_celsius",
+        "Hardware sensor temperature in Celsius.",
+        Gauge,
+    );
+    // Labels repeat across chips (two NVMe drives both report "Composite"), so each
+    // series also carries its chip and its position in the sensor list
+    for (i, sensor) in snapshot.sensors.sensors.iter().enumerate() {
+        if matches!(sensor.sensor_type, SensorType::Temperature) {
+            w.sample(
+                "nova_sensor_temperature_celsius",
+                &[
+                    ("sensor", &sensor.label),
+                    ("chip", sensor.chip.as_deref().unwrap_or("")),
+                    ("index", &i.to_string()),
+                ],
+                sensor.value as f64,
+            );
+        }
+    }
+
+    // System
+    w.single(
+        "nova_system_uptime_seconds",
+        "Seconds since boot.",
+        Gauge,
+        snapshot.system.uptime as f64,
+    );
+
+    w.out
+}
+
+/// Export the requested history series from the in-memory ring buffers
+/// Metric names: `cpu_usage`, `network.<interface>.download`, `network.<interface>.upload`
+pub fn export_history(
+    cpu: &CpuMonitor,
+    network: &NetworkMonitor,
+    format: ExportFormat,
+    metrics: &[String],
+) -> Result<String, MonitorError> {
+    if metrics.is_empty() {
+        return Err(MonitorError::SystemAccess(
+            "No metrics requested for export".to_string(),
+        ));
+    }
+
+    let network_history = network.history();
+    let mut rows: Rows = BTreeMap::new();
+
+    for metric in metrics {
+        let series = resolve_series(metric, cpu, &network_history).ok_or_else(|| {
+            MonitorError::SystemAccess(format!(
+                "Unknown metric '{}'. Available: cpu_usage, network.<interface>.download, network.<interface>.upload",
+                metric
+            ))
+        })?;
+
+        for (timestamp, value) in series {
+            rows.entry(timestamp)
+                .or_default()
+                .insert(metric.clone(), value);
+        }
+    }
+
+    Ok(match format {
+        ExportFormat::Csv => write_csv(metrics, &rows),
+        ExportFormat::Json => write_json(metrics, &rows),
+    })
+}
+
+/// Look up one named series in the history buffers
+fn resolve_series(
+    metric: &str,
+    cpu: &CpuMonitor,
+    network_history: &HashMap<String, Vec<(u64, f64, f64)>>,
+) -> Option<Series> {
+    if metric == "cpu_usage" {
+        return Some(
+            cpu.history()
+                .into_iter()
+                .map(|(t, usage)| (t, usage as f64))
+                .collect(),
+        );
+    }
+
+    // Interface names may contain dots (VLANs like eth0.100), so split on the last one
+    let (interface, field) = metric.strip_prefix("network.")?.rsplit_once('.')?;
+    let samples = network_history.get(interface)?;
+    match field {
+        "download" => Some(samples.iter().map(|(t, down, _)| (*t, *down)).collect()),
+        "upload" => Some(samples.iter().map(|(t, _, up)| (*t, *up)).collect()),
+        _ => None,
+    }
+}
+
+/// CSV with a timestamp column plus one column per metric; missing samples are empty cells
+fn write_csv(metrics: &[String], rows: &Rows) -> String {
+    let mut out = String::from("timestamp");
+    for metric in metrics {
+        out.push(',');
+        out.push_str(&csv_field(metric));
+    }
+    out.push('\n');
+
+    for (timestamp, values) in rows {
+        out.push_str(&timestamp.to_string());
+        for metric in metrics {
+            out.push(',');
+            if let Some(value) = values.get(metric) {
+                out.push_str(&value.to_string());
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// JSON array of `{timestamp, <metric>: value, ...}` objects; missing samples are omitted
+fn write_json(metrics: &[String], rows: &Rows) -> String {
+    let objects: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|(timestamp, values)| {
+            let mut object = serde_json::Map::new();
+            object.insert("timestamp".to_string(), (*timestamp).into());
+            for metric in metrics {
+                if let Some(value) = values.get(metric) {
+                    object.insert(metric.clone(), (*value).into());
+                }
+            }
+            serde_json::Value::Object(object)
+        })
+        .collect();
+
+    serde_json::Value::Array(objects).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::{
+        cpu::{CpuCore, CpuInfo},
+        disk::DisksInfo,
+        memory::MemoryInfo,
+        network::NetworkInfo,
+        sensors::{SensorReading, SensorsInfo},
+        system::SystemInfo,
+    };
+
+    fn sensor(
+        chip: Option<&str>,
+        label: &str,
+        sensor_type: SensorType,
+        value: f32,
+    ) -> SensorReading {
+        SensorReading {
+            label: label.to_string(),
+            chip: chip.map(str::to_string),
+            unit: sensor_type.unit().to_string(),
+            sensor_type,
+            value,
+            max_value: None,
+            critical_value: None,
+            session_min: value,
+            session_max: value,
+        }
+    }
+
+    fn known_snapshot() -> Snapshot {
+        Snapshot {
+            cpu: CpuInfo {
+                global_usage: 12.5,
+                cores: vec![
+                    CpuCore {
+                        name: "cpu0".into(),
+                        usage: 25.0,
+                        frequency: 3600,
+                        ..Default::default()
+                    },
+                    CpuCore {
+                        name: "cpu1".into(),
+                        usage: 0.0,
+                        frequency: 800,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            memory: MemoryInfo {
+                total_memory: 16_000_000_000,
+                used_memory: 4_000_000_000,
+                available_memory: 12_000_000_000,
+                ..Default::default()
+            },
+            disk: DisksInfo {
+                disks: vec![DiskInfo {
+                    name: "/dev/sda1".into(),
+                    mount_point: "/".into(),
+                    total_space: 1000,
+                    used_space: 250,
+                    read_bytes: 4096,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            network: NetworkInfo {
+                interfaces: vec![NetworkInterface {
+                    name: "eth0".into(),
+                    received_bytes: 1500,
+                    download_rate_bps: 125.5,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            sensors: SensorsInfo {
+                sensors: vec![
+                    sensor(Some("nvme"), "Composite", SensorType::Temperature, 41.0),
+                    sensor(Some("nvme"), "Composite", SensorType::Temperature, 38.5),
+                    sensor(Some("nct6798"), "fan1", SensorType::Fan, 900.0),
+                    sensor(None, "acpitz \"zone\"", SensorType::Temperature, 30.0),
+                ],
+                ..Default::default()
+            },
+            system: SystemInfo { uptime: 3600, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prometheus_output_is_exact() {
+        let expected = r#"# HELP nova_cpu_global_usage_percent Global CPU usage in percent.
+# TYPE nova_cpu_global_usage_percent gauge
+nova_cpu_global_usage_percent 12.5
+# HELP nova_cpu_usage_percent Per-core CPU usage in percent.
+# TYPE nova_cpu_usage_percent gauge
+nova_cpu_usage_percent{core="0"} 25
+nova_cpu_usage_percent{core="1"} 0
+# HELP nova_cpu_frequency_mhz Per-core CPU frequency in MHz.
+# TYPE nova_cpu_frequency_mhz gauge
+nova_cpu_frequency_mhz{core="0"} 3600
+nova_cpu_frequency_mhz{core="1"} 800
+# HELP nova_memory_total_bytes Total RAM in bytes.
+# TYPE nova_memory_total_bytes gauge
+nova_memory_total_bytes 16000000000
+# HELP nova_memory_used_bytes Used RAM in bytes.
+# TYPE nova_memory_used_bytes gauge
+nova_memory_used_bytes 4000000000
+# HELP nova_memory_available_bytes Available RAM in bytes.
+# TYPE nova_memory_available_bytes gauge
+nova_memory_available_bytes 12000000000
+# HELP nova_swap_total_bytes Total swap in bytes.
+# TYPE nova_swap_total_bytes gauge
+nova_swap_total_bytes 0
+# HELP nova_swap_used_bytes Used swap in bytes.
+# TYPE nova_swap_used_bytes gauge
+nova_swap_used_bytes 0
+# HELP nova_disk_total_bytes Filesystem size in bytes.
+# TYPE nova_disk_total_bytes gauge
+nova_disk_total_bytes{device="/dev/sda1",mountpoint="/"} 1000
+# HELP nova_disk_used_bytes Filesystem used space in bytes.
+# TYPE nova_disk_used_bytes gauge
+nova_disk_used_bytes{device="/dev/sda1",mountpoint="/"} 250
+# HELP nova_disk_read_bytes_total Bytes read since boot.
+# TYPE nova_disk_read_bytes_total counter
+nova_disk_read_bytes_total{device="/dev/sda1",mountpoint="/"} 4096
+# HELP nova_disk_written_bytes_total Bytes written since boot.
+# TYPE nova_disk_written_bytes_total counter
+nova_disk_written_bytes_total{device="/dev/sda1",mountpoint="/"} 0
+# HELP nova_network_received_bytes_total Bytes received since boot.
+# TYPE nova_network_received_bytes_total counter
+nova_network_received_bytes_total{interface="eth0"} 1500
+# HELP nova_network_transmitted_bytes_total Bytes transmitted since boot.
+# TYPE nova_network_transmitted_bytes_total counter
+nova_network_transmitted_bytes_total{interface="eth0"} 0
+# HELP nova_network_download_bytes_per_second Current download rate.
+# TYPE nova_network_download_bytes_per_second gauge
+nova_network_download_bytes_per_second{interface="eth0"} 125.5
+# HELP nova_network_upload_bytes_per_second Current upload rate.
+# TYPE nova_network_upload_bytes_per_second gauge
+nova_network_upload_bytes_per_second{interface="eth0"} 0
+# HELP nova_gpu_utilization_percent GPU utilization in percent.
+# TYPE nova_gpu_utilization_percent gauge
+# HELP nova_gpu_memory_used_bytes GPU memory used in bytes.
+# TYPE nova_gpu_memory_used_bytes gauge
+# HELP nova_gpu_memory_total_bytes GPU memory size in bytes.
+# TYPE nova_gpu_memory_total_bytes gauge
+# HELP nova_gpu_temperature_celsius GPU temperature in Celsius.
+# TYPE nova_gpu_temperature_celsius gauge
+# HELP nova_gpu_power_watts GPU power draw in watts.
+# TYPE nova_gpu_power_watts gauge
+# HELP nova_sensor_temperature_celsius Hardware sensor temperature in Celsius.
+# TYPE nova_sensor_temperature_celsius gauge
+nova_sensor_temperature_celsius{sensor="Composite",chip="nvme",index="0"} 41
+nova_sensor_temperature_celsius{sensor="Composite",chip="nvme",index="1"} 38.5
+nova_sensor_temperature_celsius{sensor="acpitz \"zone\"",chip="",index="3"} 30
+# HELP nova_system_uptime_seconds Seconds since boot.
+# TYPE nova_system_uptime_seconds gauge
+nova_system_uptime_seconds 3600
+"#;
+        assert_eq!(to_prometheus(&known_snapshot()), expected);
+    }
+
+    #[test]
+    fn label_names_and_values_are_sanitized() {
+        assert_eq!(sanitize_label_name("mount-point"), "mount_point");
+        assert_eq!(sanitize_label_name("0core"), "_0core");
+        assert_eq!(escape_label_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_value(0.1 + 0.2), "0.30000000000000004");
+    }
+
+    fn history_rows() -> (Vec<String>, Rows) {
+        let metrics = vec!["cpu_usage".to_string(), "network.eth0,1.download".to_string()];
+        let mut rows = Rows::new();
+        rows.insert(2000, HashMap::from([(metrics[0].clone(), 12.5)]));
+        rows.insert(
+            1000,
+            HashMap::from([(metrics[0].clone(), 10.0), (metrics[1].clone(), 2048.0)]),
+        );
+        (metrics, rows)
+    }
+
+    #[test]
+    fn csv_has_one_column_per_metric() {
+        let (metrics, rows) = history_rows();
+        assert_eq!(
+            write_csv(&metrics, &rows),
+            "timestamp,cpu_usage,\"network.eth0,1.download\"\n1000,10,2048\n2000,12.5,\n"
+        );
+    }
+
+    #[test]
+    fn json_omits_missing_samples() {
+        let (metrics, rows) = history_rows();
+        let json: serde_json::Value = serde_json::from_str(&write_json(&metrics, &rows)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"timestamp": 1000, "cpu_usage": 10.0, "network.eth0,1.download": 2048.0},
+                {"timestamp": 2000, "cpu_usage": 12.5},
+            ])
+        );
+    }
+
+    #[test]
+    fn export_rejects_empty_and_unknown_metrics() {
+        let (cpu, network) = (CpuMonitor::new(), NetworkMonitor::new());
+        let export = |metrics: &[&str]| {
+            let metrics: Vec<String> = metrics.iter().map(|m| m.to_string()).collect();
+            export_history(&cpu, &network, ExportFormat::Csv, &metrics)
+        };
+        assert!(matches!(export(&[]), Err(MonitorError::SystemAccess(_))));
+        let Err(MonitorError::SystemAccess(message)) = export(&["network.eth0.latency"]) else {
+            panic!("unknown metric should be rejected");
+        };
+        assert!(message.contains("network.eth0.latency") && message.contains("cpu_usage"));
+        assert!(export(&["cpu_usage"]).unwrap().starts_with("timestamp,cpu_usage\n"));
+    }
+}
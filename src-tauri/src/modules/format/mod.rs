@@ -0,0 +1,93 @@
+// Format Module
+// Human-readable byte counts and transfer rates, so every view formats them the same way
+
+// Unit names for powers of 1000 and of 1024; u64::MAX is ~18.4 EB, so EB is the largest
+const DECIMAL_UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const BINARY_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+/// Byte count as "1.5 GB" (powers of 1000) or "1.4 GiB" (powers of 1024 when `binary`)
+/// Counts below one kilobyte are shown as whole bytes ("512 B")
+pub fn format_bytes(bytes: u64, binary: bool) -> String {
+    format_scaled(bytes as f64, binary)
+}
+
+/// Bytes per second as "12.3 MB/s"; negative, NaN and infinite rates (e.g. from a counter
+/// reset between samples) are shown as "0 B/s"
+pub fn format_rate(bps: f64, binary: bool) -> String {
+    let bps = if bps.is_finite() && bps > 0.0 {
+        bps
+    } else {
+        0.0
+    };
+    format!("{}/s", format_scaled(bps, binary))
+}
+
+fn format_scaled(value: f64, binary: bool) -> String {
+    let (base, units) = if binary {
+        (1024.0, &BINARY_UNITS)
+    } else {
+        (1000.0, &DECIMAL_UNITS)
+    };
+
+    let mut scaled = value;
+    let mut unit = 0;
+    while scaled >= base && unit < units.len() - 1 {
+        scaled /= base;
+        unit += 1;
+    }
+
+    // Bytes get no decimals and larger units one; when rounding would print a full
+    // base ("1000.0 KB", "1024 B"), the next unit up is used instead
+    let decimals = |unit: usize| if unit == 0 { 0 } else { 1 };
+    let factor = 10f64.powi(decimals(unit));
+    if (scaled * factor).round() / factor >= base && unit < units.len() - 1 {
+        scaled /= base;
+        unit += 1;
+    }
+
+    format!("{:.*} {}", decimals(unit) as usize, scaled, units[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_switch_units_at_the_base() {
+        assert_eq!(format_bytes(0, false), "0 B");
+        assert_eq!(format_bytes(999, false), "999 B");
+        assert_eq!(format_bytes(1000, false), "1.0 KB");
+        assert_eq!(format_bytes(1023, true), "1023 B");
+        assert_eq!(format_bytes(1024, true), "1.0 KiB");
+        assert_eq!(format_bytes(1536, true), "1.5 KiB");
+        assert_eq!(format_bytes(1_250_000_000, false), "1.2 GB");
+        assert_eq!(format_bytes(1 << 50, true), "1.0 PiB");
+        assert_eq!(format_bytes(u64::MAX, false), "18.4 EB");
+        assert_eq!(format_bytes(u64::MAX, true), "16.0 EiB");
+    }
+
+    #[test]
+    fn rounding_rolls_over_into_the_next_unit() {
+        assert_eq!(format_bytes(999_949, false), "999.9 KB");
+        // 999.95 KB would print as "1000.0 KB"
+        assert_eq!(format_bytes(999_950, false), "1.0 MB");
+        assert_eq!(format_bytes(1_048_524, true), "1023.9 KiB");
+        assert_eq!(format_bytes(1_048_525, true), "1.0 MiB");
+    }
+
+    #[test]
+    fn rates_clamp_to_zero_and_round_whole_bytes() {
+        assert_eq!(format_rate(0.0, false), "0 B/s");
+        assert_eq!(format_rate(0.4, false), "0 B/s");
+        assert_eq!(format_rate(0.6, false), "1 B/s");
+        assert_eq!(format_rate(999.4, false), "999 B/s");
+        assert_eq!(format_rate(999.6, false), "1.0 KB/s");
+        assert_eq!(format_rate(12_345_678.0, false), "12.3 MB/s");
+        assert_eq!(format_rate(12_345_678.0, true), "11.8 MiB/s");
+        // Counter resets between samples can produce these
+        assert_eq!(format_rate(-2048.0, true), "0 B/s");
+        assert_eq!(format_rate(f64::NAN, false), "0 B/s");
+        assert_eq!(format_rate(f64::INFINITY, false), "0 B/s");
+        assert_eq!(format_rate(-0.0, false), "0 B/s");
+    }
+}
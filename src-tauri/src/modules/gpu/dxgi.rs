@@ -0,0 +1,374 @@
+// GPU Windows Backend
+// Enumerates AMD and Intel adapters via DXGI (NVIDIA is covered by NVML) and reads their
+// utilization from the "GPU Engine" performance counters
+// The driver version is the user-mode driver (UMD) version DXGI reports through
+// CheckInterfaceSupport; WMI's Win32_VideoController isn't queried
+
+use std::collections::HashMap;
+
+use super::{GpuInfo, GpuVendor};
+use windows::core::{Interface, PCWSTR};
+use windows::Win32::Foundation::LUID;
+use windows::Win32::Graphics::Direct3D::D3D_FEATURE_LEVEL_11_0;
+use windows::Win32::Graphics::Direct3D12::{
+    D3D12CreateDevice, ID3D12Device, D3D12_FEATURE_ARCHITECTURE, D3D12_FEATURE_DATA_ARCHITECTURE,
+};
+use windows::Win32::Graphics::Dxgi::{
+    CreateDXGIFactory1, IDXGIAdapter1, IDXGIAdapter3, IDXGIDevice, IDXGIFactory1,
+    DXGI_ADAPTER_FLAG_SOFTWARE, DXGI_MEMORY_SEGMENT_GROUP_LOCAL, DXGI_QUERY_VIDEO_MEMORY_INFO,
+};
+use windows::Win32::System::Performance::{
+    PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterArrayW,
+    PdhOpenQueryW, PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE, PDH_HCOUNTER, PDH_HQUERY,
+    PDH_MORE_DATA,
+};
+
+// PCI vendor IDs
+const AMD_VENDOR_ID: u32 = 0x1002;
+const INTEL_VENDOR_ID: u32 = 0x8086;
+
+// Per-process, per-engine utilization; instances look like
+// pid_1234_luid_0x00000000_0x0000C2A1_phys_0_eng_0_engtype_3D
+const GPU_ENGINE_COUNTER: &str = "\\GPU Engine(*)\\Utilization Percentage";
+
+/// DXGI backend state kept across refreshes
+#[derive(Default)]
+pub(super) struct DxgiState {
+    engine_counters: Option<EngineCounters>,
+    engine_counters_failed: bool, // PDH query couldn't be set up; not retried
+    uma: HashMap<(u32, i32), bool>, // Adapter LUID -> shares system memory
+}
+
+/// Open PDH query on the GPU Engine counters; the counter is a rate, so values appear
+/// from the second collection on
+struct EngineCounters {
+    query: PDH_HQUERY,
+    counter: PDH_HCOUNTER,
+}
+
+// The PDH handles are only touched through GpuMonitor's state lock
+unsafe impl Send for EngineCounters {}
+unsafe impl Sync for EngineCounters {}
+
+impl EngineCounters {
+    fn open() -> Result<Self, String> {
+        let mut query = PDH_HQUERY::default();
+        let status = unsafe { PdhOpenQueryW(PCWSTR::null(), 0, &mut query) };
+        if status != 0 {
+            return Err(format!("PdhOpenQuery failed with 0x{:08X}", status));
+        }
+
+        let path: Vec<u16> = GPU_ENGINE_COUNTER.encode_utf16().chain([0]).collect();
+        let mut counter = PDH_HCOUNTER::default();
+        let status =
+            unsafe { PdhAddEnglishCounterW(query, PCWSTR(path.as_ptr()), 0, &mut counter) };
+        if status != 0 {
+            unsafe { PdhCloseQuery(query) };
+            return Err(format!("GPU Engine counters unavailable (0x{:08X})", status));
+        }
+
+        // Prime the rate counter so the next refresh has a value
+        unsafe { PdhCollectQueryData(query) };
+        Ok(Self { query, counter })
+    }
+
+    /// Collect a sample and return (instance name, utilization percent) pairs
+    fn sample(&self) -> Vec<(String, f64)> {
+        if unsafe { PdhCollectQueryData(self.query) } != 0 {
+            return Vec::new();
+        }
+
+        let mut buffer_size = 0u32;
+        let mut item_count = 0u32;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(
+                self.counter,
+                PDH_FMT_DOUBLE,
+                &mut buffer_size,
+                &mut item_count,
+                None,
+            )
+        };
+        if status != PDH_MORE_DATA {
+            return Vec::new();
+        }
+
+        // Instance names are stored in the same buffer, after the items
+        let item_size = std::mem::size_of::<PDH_FMT_COUNTERVALUE_ITEM_W>();
+        let mut items: Vec<PDH_FMT_COUNTERVALUE_ITEM_W> =
+            Vec::with_capacity(buffer_size as usize / item_size + 1);
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(
+                self.counter,
+                PDH_FMT_DOUBLE,
+                &mut buffer_size,
+                &mut item_count,
+                Some(items.as_mut_ptr()),
+            )
+        };
+        if status != 0 {
+            return Vec::new();
+        }
+        unsafe { items.set_len(item_count as usize) };
+
+        items
+            .iter()
+            .filter(|item| item.FmtValue.CStatus == 0)
+            .filter_map(|item| {
+                let name = unsafe { item.szName.to_string() }.ok()?;
+                Some((name, unsafe { item.FmtValue.Anonymous.doubleValue }))
+            })
+            .collect()
+    }
+}
+
+impl Drop for EngineCounters {
+    fn drop(&mut self) {
+        unsafe { PdhCloseQuery(self.query) };
+    }
+}
+
+/// Get AMD/Intel GPU information via DXGI adapter enumeration
+/// Fills the driver version from the adapter's UMD version when NVML didn't provide one
+pub(super) fn get_dxgi_gpus(
+    state: &mut DxgiState,
+    driver_version: &mut Option<String>,
+    errors: &mut Vec<String>,
+) -> Vec<GpuInfo> {
+    let mut gpus = Vec::new();
+
+    if state.engine_counters.is_none() && !state.engine_counters_failed {
+        match EngineCounters::open() {
+            Ok(counters) => state.engine_counters = Some(counters),
+            Err(e) => {
+                errors.push(format!("DXGI: GPU utilization unavailable: {}", e));
+                state.engine_counters_failed = true;
+            }
+        }
+    }
+    let utilization = state
+        .engine_counters
+        .as_ref()
+        .map(|counters| engine_utilization(&counters.sample()))
+        .unwrap_or_default();
+
+    let factory: IDXGIFactory1 = match unsafe { CreateDXGIFactory1() } {
+        Ok(factory) => factory,
+        Err(e) => {
+            errors.push(format!("DXGI: Failed to create factory: {}", e));
+            return gpus;
+        }
+    };
+
+    let mut amd_index = 0;
+    let mut intel_index = 0;
+    let mut adapter_index = 0;
+
+    while let Ok(adapter) = unsafe { factory.EnumAdapters1(adapter_index) } {
+        adapter_index += 1;
+
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+
+        // Skip the Microsoft Basic Render Driver and other software adapters
+        if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0 {
+            continue;
+        }
+
+        let (vendor, index) = match desc.VendorId {
+            AMD_VENDOR_ID => {
+                amd_index += 1;
+                (GpuVendor::Amd, amd_index - 1)
+            }
+            INTEL_VENDOR_ID => {
+                intel_index += 1;
+                (GpuVendor::Intel, intel_index - 1)
+            }
+            _ => continue,
+        };
+
+        let luid = luid_key(desc.AdapterLuid);
+        let name = String::from_utf16_lossy(&desc.Description)
+            .trim_end_matches('\0')
+            .to_string();
+
+        let memory_total = desc.DedicatedVideoMemory as u64;
+
+        // Current VRAM usage needs DXGI 1.4 (IDXGIAdapter3)
+        let memory_used = adapter
+            .cast::<IDXGIAdapter3>()
+            .ok()
+            .and_then(|adapter3| {
+                let mut info = DXGI_QUERY_VIDEO_MEMORY_INFO {
+                    Budget: 0,
+                    CurrentUsage: 0,
+                    AvailableForReservation: 0,
+                    CurrentReservation: 0,
+                };
+                unsafe {
+                    adapter3.QueryVideoMemoryInfo(0, DXGI_MEMORY_SEGMENT_GROUP_LOCAL, &mut info)
+                }
+                .ok()
+                .map(|_| info.CurrentUsage)
+            })
+            .unwrap_or(0);
+
+        let memory_free = memory_total.saturating_sub(memory_used);
+        let utilization_memory = if memory_total > 0 {
+            ((memory_used as f64 / memory_total as f64) * 100.0) as u32
+        } else {
+            0
+        };
+
+        if driver_version.is_none() {
+            *driver_version = unsafe { adapter.CheckInterfaceSupport(&IDXGIDevice::IID) }
+                .ok()
+                .map(format_driver_version);
+        }
+
+        // D3D12 reports whether the adapter uses unified memory; the device-id table
+        // covers adapters without a D3D12 driver
+        let is_integrated = *state.uma.entry(luid).or_insert_with(|| {
+            adapter_is_uma(&adapter)
+                .unwrap_or_else(|| vendor == GpuVendor::Amd && super::is_amd_igpu(desc.DeviceId))
+        });
+
+        let prefix = match vendor {
+            GpuVendor::Amd => "amd",
+            _ => "intel",
+        };
+
+        gpus.push(GpuInfo {
+            index,
+            name,
+            vendor,
+            is_integrated,
+            uuid: format!("{}-{}", prefix, index),
+            utilization_gpu: utilization.get(&luid).copied().unwrap_or(0),
+            utilization_memory,
+            memory_total,
+            memory_used,
+            memory_free,
+            temperature: 0,
+            temperature_hotspot: None,
+            temperature_memory: None,
+            power_usage: 0,
+            power_limit: 0,
+            power_limit_min: None,
+            power_limit_max: None,
+            fan_speed: None,
+            clock_graphics: 0,
+            clock_memory: 0,
+            encoder_utilization: None,
+            decoder_utilization: None,
+            encoder_sessions: None,
+            encoder_fps: None,
+            throttle_reasons: Vec::new(),
+            ecc_errors_corrected: None,
+            ecc_errors_uncorrected: None,
+            pcie_gen: None,
+            pcie_width: None,
+        });
+    }
+
+    if !gpus.is_empty() {
+        errors.push(
+            "DXGI: GPU temperature and clocks are not available on Windows"
+                .to_string(),
+        );
+    }
+
+    gpus
+}
+
+/// Key an adapter by its LUID, which the GPU Engine counter instances also carry
+fn luid_key(luid: LUID) -> (u32, i32) {
+    (luid.LowPart, luid.HighPart)
+}
+
+/// Whether the adapter shares system memory (UMA), from D3D12's architecture query
+/// None when no D3D12 device can be created on it
+fn adapter_is_uma(adapter: &IDXGIAdapter1) -> Option<bool> {
+    let mut device: Option<ID3D12Device> = None;
+    unsafe { D3D12CreateDevice(adapter, D3D_FEATURE_LEVEL_11_0, &mut device) }.ok()?;
+    let device = device?;
+
+    let mut architecture = D3D12_FEATURE_DATA_ARCHITECTURE::default();
+    unsafe {
+        device.CheckFeatureSupport(
+            D3D12_FEATURE_ARCHITECTURE,
+            &mut architecture as *mut _ as *mut std::ffi::c_void,
+            std::mem::size_of::<D3D12_FEATURE_DATA_ARCHITECTURE>() as u32,
+        )
+    }
+    .ok()?;
+    Some(architecture.UMA.as_bool())
+}
+
+/// Split a GPU Engine instance name into its adapter LUID and engine type
+fn parse_engine_instance(name: &str) -> Option<((u32, i32), &str)> {
+    let (_, rest) = name.split_once("_luid_")?;
+    let mut parts = rest.split('_');
+    let high = parts.next()?.strip_prefix("0x")?;
+    let low = parts.next()?.strip_prefix("0x")?;
+    let engine = rest.split_once("_engtype_")?.1;
+
+    let high = u32::from_str_radix(high, 16).ok()? as i32;
+    let low = u32::from_str_radix(low, 16).ok()?;
+    Some(((low, high), engine))
+}
+
+/// Per-adapter utilization like Task Manager's: each engine type sums its processes'
+/// shares, and the adapter shows its busiest engine type
+fn engine_utilization(samples: &[(String, f64)]) -> HashMap<(u32, i32), u32> {
+    let mut engines: HashMap<((u32, i32), &str), f64> = HashMap::new();
+    for (name, value) in samples {
+        if let Some(key) = parse_engine_instance(name) {
+            *engines.entry(key).or_default() += value;
+        }
+    }
+
+    let mut adapters: HashMap<(u32, i32), u32> = HashMap::new();
+    for ((luid, _), total) in engines {
+        let percent = total.clamp(0.0, 100.0).round() as u32;
+        let busiest = adapters.entry(luid).or_default();
+        *busiest = (*busiest).max(percent);
+    }
+    adapters
+}
+
+/// Format a packed UMD driver version (four 16-bit parts) as "a.b.c.d"
+fn format_driver_version(version: i64) -> String {
+    let version = version as u64;
+    format!(
+        "{}.{}.{}.{}",
+        (version >> 48) & 0xFFFF,
+        (version >> 32) & 0xFFFF,
+        (version >> 16) & 0xFFFF,
+        version & 0xFFFF
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_utilization_sums_processes_and_takes_the_busiest_engine() {
+        let sample = |name: &str, value: f64| (name.to_string(), value);
+        let samples = [
+            sample("pid_10_luid_0x00000000_0x0000C2A1_phys_0_eng_0_engtype_3D", 30.0),
+            sample("pid_20_luid_0x00000000_0x0000C2A1_phys_0_eng_0_engtype_3D", 25.0),
+            sample("pid_20_luid_0x00000000_0x0000C2A1_phys_0_eng_4_engtype_VideoDecode", 40.0),
+            sample("pid_30_luid_0x00000001_0x00001000_phys_0_eng_1_engtype_Compute_0", 80.0),
+            sample("pid_30_luid_0x00000001_0x00001000_phys_0_eng_2_engtype_Copy", 90.0),
+            sample("not an engine instance", 100.0),
+        ];
+
+        let utilization = engine_utilization(&samples);
+        assert_eq!(utilization.len(), 2);
+        assert_eq!(utilization[&(0xC2A1, 0)], 55);
+        assert_eq!(utilization[&(0x1000, 1)], 90);
+    }
+}
@@ -1,76 +1,408 @@
 // GPU Monitoring Module
 // Provides comprehensive GPU monitoring for NVIDIA, AMD, and Intel GPUs
 
+#[cfg(windows)]
+mod dxgi;
+
+use nvml_wrapper::enum_wrappers::device::{EccCounter, MemoryError};
+use nvml_wrapper::error::{nvml_try, NvmlError};
+use nvml_wrapper::enums::device::{SampleValue, UsedGpuMemory};
+use nvml_wrapper::struct_wrappers::device::{ProcessInfo, ProcessUtilizationSample};
+use nvml_wrapper::structs::device::FieldId;
+use nvml_wrapper::sys_exports::field_id::NVML_FI_DEV_MEMORY_TEMP;
 use nvml_wrapper::Nvml;
+use nvml_wrapper_sys::bindings::NvmlLib;
+use std::collections::{HashMap, VecDeque};
+#[cfg(target_os = "linux")]
+use std::collections::HashSet;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
 use std::fs;
+#[cfg(target_os = "linux")]
+use std::io::ErrorKind;
+#[cfg(target_os = "linux")]
 use std::path::Path;
 
+#[cfg(target_os = "linux")]
+use crate::modules::{CommandRunner, SystemCommandRunner};
+use crate::modules::{LockRecover, MonitorError, TemperatureUnit};
+
 /// GPU vendor type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GpuVendor {
     Nvidia,
     Amd,
     Intel,
+    #[default]
     Unknown,
 }
 
 /// Information about a single GPU
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GpuInfo {
     pub index: u32,
     pub name: String,
     pub vendor: GpuVendor,
+    pub is_integrated: bool, // iGPU sharing system memory
     pub uuid: String,
     pub utilization_gpu: u32,    // Percentage
     pub utilization_memory: u32, // Percentage
     pub memory_total: u64,       // Bytes
     pub memory_used: u64,        // Bytes
     pub memory_free: u64,        // Bytes
-    pub temperature: u32,        // Celsius
+    pub temperature: u32,        // Celsius, edge sensor
+    pub temperature_hotspot: Option<u32>, // Celsius, where exposed
+    pub temperature_memory: Option<u32>,  // Celsius, where exposed
     pub power_usage: u32,        // Milliwatts
     pub power_limit: u32,        // Milliwatts
+    pub power_limit_min: Option<u32>, // Milliwatts, lowest settable limit (NVIDIA only)
+    pub power_limit_max: Option<u32>, // Milliwatts, highest settable limit (NVIDIA only)
     pub fan_speed: Option<u32>,  // Percentage
     pub clock_graphics: u32,     // MHz
     pub clock_memory: u32,       // MHz
     pub encoder_utilization: Option<u32>,
     pub decoder_utilization: Option<u32>,
+    pub encoder_sessions: Option<u32>, // Active NVENC sessions, NVIDIA only
+    pub encoder_fps: Option<u32>,      // Average FPS across active sessions
+    pub throttle_reasons: Vec<String>, // NVIDIA only
+    pub ecc_errors_corrected: Option<u64>,   // Lifetime count, None without ECC
+    pub ecc_errors_uncorrected: Option<u64>, // Lifetime count, None without ECC
+    pub pcie_gen: Option<u32>,
+    pub pcie_width: Option<u32>,
 }
 
 /// Overall GPU information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GpusInfo {
     pub gpus: Vec<GpuInfo>,
     pub nvidia_available: bool,
     pub amd_available: bool,
     pub intel_available: bool,
+    pub nvidia_driver_loaded: bool,        // NVML initialized successfully
+    pub nvidia_init_error: Option<String>, // NVML init failure, e.g. driver/library mismatch
     pub driver_version: Option<String>,
+    pub temperature_unit: String, // Symbol of the unit the temperatures are in (TemperatureUnit)
+    pub errors: Vec<String>,
+}
+
+/// Which GPUs get_gpu_info returns; the *_available flags ignore it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GpuQuery {
+    pub vendors: Option<Vec<GpuVendor>>, // None keeps every vendor
+    pub include_integrated: bool,
+}
+
+impl Default for GpuQuery {
+    fn default() -> Self {
+        Self {
+            vendors: None,
+            include_integrated: true,
+        }
+    }
+}
+
+impl GpuInfo {
+    /// Hottest of the edge, hotspot and memory sensors
+    pub fn max_temperature(&self) -> u32 {
+        [self.temperature_hotspot, self.temperature_memory]
+            .into_iter()
+            .flatten()
+            .fold(self.temperature, u32::max)
+    }
+}
+
+impl GpusInfo {
+    /// Keep only the GPUs matching the query
+    pub fn filter(mut self, query: &GpuQuery) -> Self {
+        self.gpus.retain(|gpu| {
+            query
+                .vendors
+                .as_ref()
+                .is_none_or(|vendors| vendors.contains(&gpu.vendor))
+                && (query.include_integrated || !gpu.is_integrated)
+        });
+        self
+    }
+
+    /// Render GPU temperatures in the given unit
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        for gpu in &mut self.gpus {
+            gpu.temperature = unit.convert_whole(gpu.temperature);
+            gpu.temperature_hotspot = gpu.temperature_hotspot.map(|t| unit.convert_whole(t));
+            gpu.temperature_memory = gpu.temperature_memory.map(|t| unit.convert_whole(t));
+        }
+        self.temperature_unit = unit.symbol().to_string();
+        self
+    }
+}
+
+/// A process using an NVIDIA GPU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub gpu_index: u32,
+    pub used_memory: u64, // Bytes
+    pub sm_util: Option<u32>,  // Percentage
+    pub enc_util: Option<u32>, // Percentage
+    pub dec_util: Option<u32>, // Percentage
+}
+
+/// GPU process list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuProcessList {
+    pub processes: Vec<GpuProcess>,
     pub errors: Vec<String>,
 }
 
+impl GpuProcessList {
+    /// Whether `pid` has a context on any GPU
+    pub fn contains(&self, pid: u32) -> bool {
+        self.processes.iter().any(|p| p.pid == pid)
+    }
+
+    /// Guard for actions from the GPU pane: NotGpuProcess unless `pid` is in the list
+    pub fn ensure_contains(&self, pid: u32) -> Result<(), MonitorError> {
+        if self.contains(pid) {
+            Ok(())
+        } else {
+            Err(MonitorError::NotGpuProcess(pid))
+        }
+    }
+}
+
+/// One history sample for a GPU
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuSample {
+    pub timestamp: u64,      // Milliseconds
+    pub utilization_gpu: u32, // Percentage
+    pub temperature: u32,    // Celsius
+    pub power_usage: u32,    // Milliwatts
+    pub clock_graphics: u32, // MHz
+}
+
+impl GpuSample {
+    /// Render the temperature in the given unit
+    pub fn in_unit(self, unit: TemperatureUnit) -> Self {
+        Self {
+            temperature: unit.convert_whole(self.temperature),
+            ..self
+        }
+    }
+}
+
+/// Rolling history for one GPU
+struct GpuHistory {
+    samples: VecDeque<GpuSample>,
+    last_seen: u64, // Milliseconds
+}
+
 /// Internal GPU state for lazy initialization and history tracking
 struct GpuState {
     nvml: Option<Nvml>,
+    nvml_init_error: Option<String>, // Why Nvml::init failed, kept for the UI
     initialized: bool,
     // Store last RC6 reading and timestamp for Intel GPUs: (card_index) -> (residency_ms, timestamp_ms)
-    last_rc6_readings: std::collections::HashMap<u32, (u64, u64)>,
+    last_rc6_readings: HashMap<u32, (u64, u64)>,
+    // Last engine busy counters for Intel GPUs: (card_index, engine) -> (busy_ns, timestamp_ms)
+    last_engine_busy: HashMap<(u32, String), (u64, u64)>,
+    // Per-index NVIDIA metadata that doesn't change while the device is present
+    nvidia_static: Vec<Option<NvidiaStaticInfo>>,
+    nvidia_driver_version: Option<String>,
+    // Sample history keyed by uuid, so it follows a GPU across index changes
+    history: HashMap<String, GpuHistory>,
+    // Result of the last refresh, for readers that mustn't trigger a sample
+    last_info: Option<GpusInfo>,
+    // Last rocm-smi result and when it was taken, reused for ROCM_SMI_CACHE_SECS
+    #[cfg(target_os = "linux")]
+    rocm_smi: Option<(Instant, HashMap<String, RocmSmiCard>)>,
+    #[cfg(target_os = "linux")]
+    rocm_smi_missing: bool, // Set once rocm-smi fails to spawn; not retried this session
+    #[cfg(windows)]
+    dxgi: dxgi::DxgiState,
+}
+
+/// Static NVIDIA device properties, cached across refreshes
+struct NvidiaStaticInfo {
+    name: String,
+    uuid: String,
+    power_limit_range: Option<(u32, u32)>, // (min, max) milliwatts
+    memory_total: u64,                     // Bytes
+}
+
+/// Source of the static NVIDIA properties; implemented by Nvml and faked in tests
+trait NvidiaDevices {
+    fn driver_version(&self) -> Option<String>;
+    /// None when the device can't be opened
+    fn static_info(&self, index: u32) -> Option<NvidiaStaticInfo>;
+}
+
+impl NvidiaDevices for Nvml {
+    fn driver_version(&self) -> Option<String> {
+        self.sys_driver_version().ok()
+    }
+
+    fn static_info(&self, index: u32) -> Option<NvidiaStaticInfo> {
+        let device = self.device_by_index(index).ok()?;
+        Some(NvidiaStaticInfo {
+            name: device
+                .name()
+                .unwrap_or_else(|_| "Unknown NVIDIA GPU".to_string()),
+            uuid: device.uuid().unwrap_or_else(|_| format!("nvidia-{}", index)),
+            power_limit_range: device
+                .power_management_limit_constraints()
+                .ok()
+                .map(|c| (c.min_limit, c.max_limit)),
+            memory_total: device.memory_info().map(|m| m.total).unwrap_or(0),
+        })
+    }
+}
+
+/// Static metadata is read once per device set; a changed count (first refresh or eGPU
+/// hotplug) reloads it along with the driver version
+fn update_nvidia_static(
+    devices: &dyn NvidiaDevices,
+    cache: &mut Vec<Option<NvidiaStaticInfo>>,
+    driver_version: &mut Option<String>,
+    device_count: u32,
+) {
+    if cache.len() != device_count as usize {
+        *driver_version = devices.driver_version();
+        *cache = (0..device_count).map(|i| devices.static_info(i)).collect();
+    }
 }
 
 /// GPU Monitor state with lazy initialization
 pub struct GpuMonitor {
     state: std::sync::RwLock<GpuState>,
+    history_len: usize,
+    nvml_init: fn() -> Result<Nvml, NvmlError>, // Nvml::init, replaced in tests
+    #[cfg(target_os = "linux")]
+    runner: Box<dyn CommandRunner>,
 }
 
+// NVML shared library, the same one Nvml::init loads
+#[cfg(windows)]
+const NVML_LIBRARY: &str = "nvml.dll";
+#[cfg(not(windows))]
+const NVML_LIBRARY: &str = "libnvidia-ml.so";
+
+// Default number of samples kept per GPU
+const DEFAULT_HISTORY_LEN: usize = 60;
+
+// How long a vanished GPU keeps its history before being dropped (5 minutes)
+const HISTORY_GRACE_PERIOD_MS: u64 = 5 * 60 * 1000;
+
+#[cfg(target_os = "linux")]
+const DRM_PATH: &str = "/sys/class/drm";
+
+// How long rocm-smi output is reused; the tool takes a noticeable fraction of a second
+#[cfg(target_os = "linux")]
+const ROCM_SMI_CACHE_SECS: u64 = 5;
+
 impl GpuMonitor {
     pub fn new() -> Self {
+        Self::with_history_len(DEFAULT_HISTORY_LEN)
+    }
+
+    pub fn with_history_len(history_len: usize) -> Self {
         // Don't init NVML here - do it lazily on first use
         Self {
             state: std::sync::RwLock::new(GpuState {
                 nvml: None,
+                nvml_init_error: None,
                 initialized: false,
-                last_rc6_readings: std::collections::HashMap::new(),
+                last_rc6_readings: HashMap::new(),
+                last_engine_busy: HashMap::new(),
+                nvidia_static: Vec::new(),
+                nvidia_driver_version: None,
+                history: HashMap::new(),
+                last_info: None,
+                #[cfg(target_os = "linux")]
+                rocm_smi: None,
+                #[cfg(target_os = "linux")]
+                rocm_smi_missing: false,
+                #[cfg(windows)]
+                dxgi: dxgi::DxgiState::default(),
             }),
+            history_len: history_len.max(1),
+            nvml_init: Nvml::init,
+            #[cfg(target_os = "linux")]
+            runner: Box::new(SystemCommandRunner),
+        }
+    }
+
+    /// Create a monitor that runs rocm-smi through the given runner
+    #[cfg(target_os = "linux")]
+    pub fn with_runner(runner: Box<dyn CommandRunner>) -> Self {
+        Self {
+            runner,
+            ..Self::new()
+        }
+    }
+
+    /// Whether NVML loaded, initializing it on first use
+    pub fn nvml_available(&self) -> bool {
+        self.ensure_nvml();
+        self.state
+            .read_or_recover("GPU state")
+            .nvml
+            .is_some()
+    }
+
+    /// Result of the last refresh without sampling again (None before the first one)
+    pub fn cached(&self) -> Option<GpusInfo> {
+        self.state.read_or_recover("GPU state").last_info.clone()
+    }
+
+    /// Per-GPU sample history keyed by uuid, oldest first
+    pub fn history(&self) -> HashMap<String, Vec<GpuSample>> {
+        let state = self.state.read_or_recover("GPU state");
+        state
+            .history
+            .iter()
+            .map(|(uuid, h)| (uuid.clone(), h.samples.iter().copied().collect()))
+            .collect()
+    }
+
+    fn record_history(&self, gpus: &[GpuInfo]) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let mut state = self.state.write_or_recover("GPU state");
+
+        for gpu in gpus {
+            let entry = state.history.entry(gpu.uuid.clone()).or_insert_with(|| GpuHistory {
+                samples: VecDeque::with_capacity(self.history_len),
+                last_seen: timestamp,
+            });
+            if entry.samples.len() >= self.history_len {
+                entry.samples.pop_front();
+            }
+            entry.samples.push_back(GpuSample {
+                timestamp,
+                utilization_gpu: gpu.utilization_gpu,
+                temperature: gpu.temperature,
+                power_usage: gpu.power_usage,
+                clock_graphics: gpu.clock_graphics,
+            });
+            entry.last_seen = timestamp;
         }
+
+        // Drop GPUs that have been gone longer than the grace period (unplugged eGPU)
+        state
+            .history
+            .retain(|_, h| timestamp.saturating_sub(h.last_seen) <= HISTORY_GRACE_PERIOD_MS);
     }
 
     /// Get NVIDIA GPU information via NVML
@@ -81,91 +413,147 @@ impl GpuMonitor {
     ) -> Vec<GpuInfo> {
         let mut gpus = Vec::new();
 
-        // Use read lock initially
-        if let Ok(state) = self.state.read() {
-            if let Some(ref nvml) = state.nvml {
-                *driver_version = nvml.sys_driver_version().ok();
-
-                match nvml.device_count() {
-                    Ok(device_count) => {
-                        for i in 0..device_count {
-                            if let Ok(device) = nvml.device_by_index(i) {
-                                let name = device
-                                    .name()
-                                    .unwrap_or_else(|_| "Unknown NVIDIA GPU".to_string());
-                                let uuid =
-                                    device.uuid().unwrap_or_else(|_| format!("nvidia-{}", i));
-
-                                let (utilization_gpu, utilization_memory) = device
-                                    .utilization_rates()
-                                    .map(|u| (u.gpu, u.memory))
-                                    .unwrap_or((0, 0));
-
-                                let (memory_total, memory_used, memory_free) = device
-                                    .memory_info()
-                                    .map(|m| (m.total, m.used, m.free))
-                                    .unwrap_or((0, 0, 0));
-
-                                let temperature = device
-                                    .temperature(
-                                        nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu,
-                                    )
-                                    .unwrap_or(0);
-
-                                let power_usage = device.power_usage().unwrap_or(0);
-                                let power_limit = device.power_management_limit().unwrap_or(0);
-                                let fan_speed = device.fan_speed(0).ok();
-
-                                let clock_graphics = device
-                                    .clock_info(
-                                        nvml_wrapper::enum_wrappers::device::Clock::Graphics,
-                                    )
-                                    .unwrap_or(0);
-                                let clock_memory = device
-                                    .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Memory)
-                                    .unwrap_or(0);
-
-                                let encoder_utilization =
-                                    device.encoder_utilization().ok().map(|e| e.utilization);
-                                let decoder_utilization =
-                                    device.decoder_utilization().ok().map(|d| d.utilization);
-
-                                gpus.push(GpuInfo {
-                                    index: i,
-                                    name,
-                                    vendor: GpuVendor::Nvidia,
-                                    uuid,
-                                    utilization_gpu,
-                                    utilization_memory,
-                                    memory_total,
-                                    memory_used,
-                                    memory_free,
-                                    temperature,
-                                    power_usage,
-                                    power_limit,
-                                    fan_speed,
-                                    clock_graphics,
-                                    clock_memory,
-                                    encoder_utilization,
-                                    decoder_utilization,
-                                });
-                            }
-                        }
-                    }
-                    Err(e) => errors.push(format!("NVIDIA: Failed to get device count: {}", e)),
-                }
-            } else {
-                errors.push("NVIDIA: NVML not initialized".to_string());
+        let mut guard = self.state.write_or_recover("GPU state");
+        let GpuState {
+            nvml,
+            nvml_init_error,
+            nvidia_static,
+            nvidia_driver_version,
+            ..
+        } = &mut *guard;
+
+        let Some(nvml) = nvml.as_ref() else {
+            errors.push(format!(
+                "NVIDIA: NVML not initialized: {}",
+                nvml_init_error.as_deref().unwrap_or("unknown error")
+            ));
+            return gpus;
+        };
+
+        let device_count = match nvml.device_count() {
+            Ok(count) => count,
+            Err(e) => {
+                errors.push(format!("NVIDIA: Failed to get device count: {}", e));
+                return gpus;
             }
+        };
+
+        update_nvidia_static(nvml, nvidia_static, nvidia_driver_version, device_count);
+        *driver_version = nvidia_driver_version.clone();
+
+        for (i, info) in nvidia_static.iter().enumerate() {
+            let i = i as u32;
+            let (Some(info), Ok(device)) = (info, nvml.device_by_index(i)) else {
+                continue;
+            };
+
+            // Only volatile fields are queried each tick; the power limit can be changed
+            // through set_power_limit, so it counts as volatile
+            let power_limit = device.power_management_limit().unwrap_or(0);
+            let (utilization_gpu, utilization_memory) = device
+                .utilization_rates()
+                .map(|u| (u.gpu, u.memory))
+                .unwrap_or((0, 0));
+
+            let (memory_used, memory_free) = device
+                .memory_info()
+                .map(|m| (m.used, m.free))
+                .unwrap_or((0, 0));
+
+            let temperature = device
+                .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+                .unwrap_or(0);
+
+            // NVML has no hotspot sensor; memory temperature is only a field value on
+            // HBM/GDDR6X boards and errors elsewhere
+            let temperature_memory = device
+                .field_values_for(&[FieldId(NVML_FI_DEV_MEMORY_TEMP)])
+                .ok()
+                .and_then(|samples| samples.into_iter().next())
+                .and_then(|sample| sample.ok()?.value.ok())
+                .and_then(|value| match value {
+                    SampleValue::U32(v) => Some(v),
+                    SampleValue::U64(v) => Some(v as u32),
+                    SampleValue::I64(v) => u32::try_from(v).ok(),
+                    SampleValue::F64(v) => Some(v as u32),
+                })
+                .filter(|t| *t > 0);
+
+            let power_usage = device.power_usage().unwrap_or(0);
+            let fan_speed = device.fan_speed(0).ok();
+
+            let clock_graphics = device
+                .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Graphics)
+                .unwrap_or(0);
+            let clock_memory = device
+                .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Memory)
+                .unwrap_or(0);
+
+            let encoder_utilization = device.encoder_utilization().ok().map(|e| e.utilization);
+            let decoder_utilization = device.decoder_utilization().ok().map(|d| d.utilization);
+
+            // Average FPS is meaningless without an active session
+            let encoder_stats = device.encoder_stats().ok();
+            let encoder_sessions = encoder_stats.as_ref().map(|s| s.session_count);
+            let encoder_fps = encoder_stats
+                .filter(|s| s.session_count > 0)
+                .map(|s| s.average_fps);
+
+            let throttle_reasons = device
+                .current_throttle_reasons()
+                .map(|r| decode_throttle_reasons(r.bits()))
+                .unwrap_or_default();
+
+            // Each query is guarded on its own; devices without ECC report None
+            let ecc_errors_corrected = device
+                .total_ecc_errors(MemoryError::Corrected, EccCounter::Aggregate)
+                .ok();
+            let ecc_errors_uncorrected = device
+                .total_ecc_errors(MemoryError::Uncorrected, EccCounter::Aggregate)
+                .ok();
+            let pcie_gen = device.current_pcie_link_gen().ok();
+            let pcie_width = device.current_pcie_link_width().ok();
+
+            gpus.push(GpuInfo {
+                index: i,
+                name: info.name.clone(),
+                vendor: GpuVendor::Nvidia,
+                is_integrated: false,
+                uuid: info.uuid.clone(),
+                utilization_gpu,
+                utilization_memory,
+                memory_total: info.memory_total,
+                memory_used,
+                memory_free,
+                temperature,
+                temperature_hotspot: None,
+                temperature_memory,
+                power_usage,
+                power_limit,
+                power_limit_min: info.power_limit_range.map(|(min, _)| min),
+                power_limit_max: info.power_limit_range.map(|(_, max)| max),
+                fan_speed,
+                clock_graphics,
+                clock_memory,
+                encoder_utilization,
+                decoder_utilization,
+                encoder_sessions,
+                encoder_fps,
+                throttle_reasons,
+                ecc_errors_corrected,
+                ecc_errors_uncorrected,
+                pcie_gen,
+                pcie_width,
+            });
         }
 
         gpus
     }
 
+    #[cfg(target_os = "linux")]
     /// Get AMD GPU information via sysfs
-    fn get_amd_gpus(&self, _errors: &mut Vec<String>) -> Vec<GpuInfo> {
+    fn get_amd_gpus(&self, drm_path: &Path, errors: &mut Vec<String>) -> Vec<GpuInfo> {
         let mut gpus = Vec::new();
-        let drm_path = Path::new("/sys/class/drm");
 
         if !drm_path.exists() {
             return gpus;
@@ -173,6 +561,10 @@ impl GpuMonitor {
 
         if let Ok(entries) = fs::read_dir(drm_path) {
             let mut index = 0;
+            let mut seen_bus_ids = HashSet::new();
+            // rocm-smi output keyed by PCI bus id, looked up at most once per refresh
+            let mut rocm_cards: Option<HashMap<String, RocmSmiCard>> = None;
+
             for entry in entries.flatten() {
                 let path = entry.path();
                 let device_path = path.join("device");
@@ -188,11 +580,24 @@ impl GpuMonitor {
                     continue;
                 }
 
+                // cardN and renderDN nodes point at the same PCI device
+                let bus_id = Self::pci_bus_id(&device_path);
+                if let Some(ref id) = bus_id {
+                    if !seen_bus_ids.insert(id.clone()) {
+                        continue;
+                    }
+                }
+
                 // Get GPU name - better detection for iGPU
                 let device_id = fs::read_to_string(device_path.join("device"))
                     .ok()
                     .and_then(|s| s.trim().to_string().into());
 
+                let is_known_igpu = device_id
+                    .as_deref()
+                    .and_then(|id| u32::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+                    .is_some_and(is_amd_igpu);
+
                 let name = if let Some(ref id) = device_id {
                     match id.as_str() {
                         "0x1638" => "AMD Radeon Graphics (Ryzen 5000 Series iGPU)".to_string(),
@@ -204,21 +609,37 @@ impl GpuMonitor {
                 };
 
                 // Get utilization (gpu_busy_percent)
-                let utilization_gpu = fs::read_to_string(device_path.join("gpu_busy_percent"))
+                let busy_percent = fs::read_to_string(device_path.join("gpu_busy_percent"))
                     .ok()
-                    .and_then(|s| s.trim().parse::<u32>().ok())
-                    .unwrap_or(0);
+                    .and_then(|s| s.trim().parse::<u32>().ok());
+                let mut utilization_gpu = busy_percent.unwrap_or(0);
 
                 // Get VRAM usage from sysfs
-                let memory_total = fs::read_to_string(device_path.join("mem_info_vram_total"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<u64>().ok())
-                    .unwrap_or(0);
+                let (mut memory_total, mut memory_used) = read_vram_info(&device_path);
 
-                let memory_used = fs::read_to_string(device_path.join("mem_info_vram_used"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<u64>().ok())
-                    .unwrap_or(0);
+                let mut temperature = Self::find_amd_temperature(&device_path).unwrap_or(0);
+                let temperature_hotspot = Self::find_amd_labeled_temperature(&device_path, "junction");
+                let temperature_memory = Self::find_amd_labeled_temperature(&device_path, "mem");
+                let mut power_usage = Self::find_amd_power(&device_path).unwrap_or(0);
+
+                // Some cards lack gpu_busy_percent but rocm-smi still reports them; an idle
+                // card reading 0 is a real value and doesn't need the fallback
+                if busy_percent.is_none() {
+                    let cards = rocm_cards.get_or_insert_with(|| self.rocm_smi_cards(errors));
+                    if let Some(card) = bus_id.as_ref().and_then(|id| cards.get(id)) {
+                        utilization_gpu = card.utilization.unwrap_or(utilization_gpu);
+                        if memory_total == 0 {
+                            memory_total = card.memory_total.unwrap_or(0);
+                            memory_used = card.memory_used.unwrap_or(0);
+                        }
+                        if temperature == 0 {
+                            temperature = card.temperature.unwrap_or(0);
+                        }
+                        if power_usage == 0 {
+                            power_usage = card.power_usage.unwrap_or(0);
+                        }
+                    }
+                }
 
                 let memory_free = memory_total.saturating_sub(memory_used);
                 let utilization_memory = if memory_total > 0 {
@@ -227,8 +648,9 @@ impl GpuMonitor {
                     0
                 };
 
-                let temperature = Self::find_amd_temperature(&device_path).unwrap_or(0);
-                let power_usage = Self::find_amd_power(&device_path).unwrap_or(0);
+                // APUs without a table entry still stand out by having no dedicated VRAM
+                let is_integrated = is_known_igpu || memory_total == 0;
+
                 let clock_graphics = Self::find_amd_clock(&device_path, "pp_dpm_sclk").unwrap_or(0);
                 let clock_memory = Self::find_amd_clock(&device_path, "pp_dpm_mclk").unwrap_or(0);
 
@@ -236,20 +658,32 @@ impl GpuMonitor {
                     index,
                     name,
                     vendor: GpuVendor::Amd,
-                    uuid: format!("amd-{}", index),
+                    is_integrated,
+                    uuid: drm_gpu_uuid("amd", &device_path, bus_id.as_deref(), index),
                     utilization_gpu,
                     utilization_memory,
                     memory_total,
                     memory_used,
                     memory_free,
                     temperature,
+                    temperature_hotspot,
+                    temperature_memory,
                     power_usage,
                     power_limit: 0,
+                    power_limit_min: None,
+                    power_limit_max: None,
                     fan_speed: None,
                     clock_graphics,
                     clock_memory,
                     encoder_utilization: None,
                     decoder_utilization: None,
+                    encoder_sessions: None,
+                    encoder_fps: None,
+                    throttle_reasons: Vec::new(),
+                    ecc_errors_corrected: None,
+                    ecc_errors_uncorrected: None,
+                    pcie_gen: None,
+                    pcie_width: None,
                 });
 
                 index += 1;
@@ -259,6 +693,68 @@ impl GpuMonitor {
         gpus
     }
 
+    #[cfg(target_os = "linux")]
+    /// PCI bus id (e.g. 0000:03:00.0) of a DRM device
+    fn pci_bus_id(device_path: &Path) -> Option<String> {
+        fs::canonicalize(device_path)
+            .ok()?
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+    }
+
+    #[cfg(target_os = "linux")]
+    /// rocm-smi cards keyed by PCI bus id, rerunning the tool once the cached result is
+    /// older than ROCM_SMI_CACHE_SECS; empty when rocm-smi isn't installed
+    fn rocm_smi_cards(&self, errors: &mut Vec<String>) -> HashMap<String, RocmSmiCard> {
+        {
+            let state = self.state.read_or_recover("GPU state");
+            if state.rocm_smi_missing {
+                return HashMap::new();
+            }
+            if let Some((taken_at, cards)) = &state.rocm_smi {
+                if taken_at.elapsed().as_secs() < ROCM_SMI_CACHE_SECS {
+                    return cards.clone();
+                }
+            }
+        }
+
+        let output = self.runner.run(
+            "rocm-smi",
+            &[
+                "--showuse",
+                "--showmeminfo",
+                "vram",
+                "--showtemp",
+                "--showpower",
+                "--showbus",
+                "--json",
+            ],
+        );
+
+        let cards = match output {
+            Ok(output) if output.status.success() => {
+                parse_rocm_smi_json(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(output) => {
+                errors.push(format!("AMD: rocm-smi exited with {}", output.status));
+                HashMap::new()
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                self.state.write_or_recover("GPU state").rocm_smi_missing = true;
+                return HashMap::new();
+            }
+            Err(e) => {
+                errors.push(format!("AMD: Failed to run rocm-smi: {}", e));
+                HashMap::new()
+            }
+        };
+
+        // Failures are cached too, so a broken install isn't rerun on every refresh
+        self.state.write_or_recover("GPU state").rocm_smi = Some((Instant::now(), cards.clone()));
+        cards
+    }
+
+    #[cfg(target_os = "linux")]
     fn find_amd_temperature(device_path: &Path) -> Option<u32> {
         let hwmon_path = device_path.join("hwmon");
         if let Ok(entries) = fs::read_dir(&hwmon_path) {
@@ -273,6 +769,36 @@ impl GpuMonitor {
         None
     }
 
+    #[cfg(target_os = "linux")]
+    /// Find an amdgpu hwmon temperature by its tempN_label (edge, junction, mem)
+    fn find_amd_labeled_temperature(device_path: &Path, label: &str) -> Option<u32> {
+        let hwmon_dirs = fs::read_dir(device_path.join("hwmon")).ok()?;
+        for hwmon in hwmon_dirs.flatten() {
+            let dir = hwmon.path();
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let Some(sensor) = file_name.strip_suffix("_label") else {
+                    continue;
+                };
+                let matches = sensor.starts_with("temp")
+                    && fs::read_to_string(entry.path()).is_ok_and(|l| l.trim() == label);
+                if !matches {
+                    continue;
+                }
+
+                let input = fs::read_to_string(dir.join(format!("{}_input", sensor)));
+                if let Some(millidegrees) = input.ok().and_then(|v| v.trim().parse::<u32>().ok()) {
+                    return Some(millidegrees / 1000);
+                }
+            }
+        }
+        None
+    }
+
+    #[cfg(target_os = "linux")]
     fn find_amd_power(device_path: &Path) -> Option<u32> {
         let hwmon_path = device_path.join("hwmon");
         if let Ok(entries) = fs::read_dir(&hwmon_path) {
@@ -287,6 +813,7 @@ impl GpuMonitor {
         None
     }
 
+    #[cfg(target_os = "linux")]
     fn find_amd_clock(device_path: &Path, clock_file: &str) -> Option<u32> {
         if let Ok(clock_str) = fs::read_to_string(device_path.join(clock_file)) {
             for line in clock_str.lines() {
@@ -305,10 +832,10 @@ impl GpuMonitor {
         None
     }
 
+    #[cfg(target_os = "linux")]
     /// Get Intel GPU information via sysfs/RC6
-    fn get_intel_gpus(&self, _errors: &mut Vec<String>) -> Vec<GpuInfo> {
+    fn get_intel_gpus(&self, drm_path: &Path, _errors: &mut Vec<String>) -> Vec<GpuInfo> {
         let mut gpus = Vec::new();
-        let drm_path = Path::new("/sys/class/drm");
 
         if !drm_path.exists() {
             return gpus;
@@ -316,6 +843,7 @@ impl GpuMonitor {
 
         if let Ok(entries) = fs::read_dir(drm_path) {
             let mut index = 0;
+            let mut seen_bus_ids = HashSet::new();
             for entry in entries.flatten() {
                 let path = entry.path();
                 let device_path = path.join("device");
@@ -330,7 +858,28 @@ impl GpuMonitor {
                     continue;
                 }
 
-                let name = "Intel Integrated GPU".to_string();
+                // cardN and renderDN nodes point at the same PCI device
+                let bus_id = Self::pci_bus_id(&device_path);
+                if let Some(ref id) = bus_id {
+                    if !seen_bus_ids.insert(id.clone()) {
+                        continue;
+                    }
+                }
+
+                // Discrete Arc cards (i915/xe) expose dedicated VRAM; integrated GPUs report 0
+                let (memory_total, memory_used) = read_vram_info(&device_path);
+                let memory_free = memory_total.saturating_sub(memory_used);
+                let utilization_memory = if memory_total > 0 {
+                    ((memory_used as f64 / memory_total as f64) * 100.0) as u32
+                } else {
+                    0
+                };
+
+                let name = if memory_total > 0 {
+                    "Intel Discrete GPU".to_string()
+                } else {
+                    "Intel Integrated GPU".to_string()
+                };
 
                 // Intel GPU frequency (current)
                 let clock_graphics = fs::read_to_string(path.join("gt/gt0/rps_cur_freq_mhz"))
@@ -342,61 +891,74 @@ impl GpuMonitor {
                 // Path: /sys/class/drm/cardX/gt/gt0/rc6_residency_ms
                 let rc6_path = path.join("gt/gt0/rc6_residency_ms");
                 let mut utilization_gpu = 0;
+                let current_time = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64;
 
                 if rc6_path.exists() {
-                    let current_time = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_millis() as u64;
-
                     if let Ok(rc6_str) = fs::read_to_string(&rc6_path) {
                         if let Ok(rc6_ms) = rc6_str.trim().parse::<u64>() {
                             // Calculate delta
-                            // We need a write lock to update state, but we only have `&self`.
-                            // This means we need to upgrade or just use the lock.
-                            if let Ok(mut state) = self.state.write() {
-                                if let Some((last_rc6, last_time)) =
-                                    state.last_rc6_readings.get(&index)
-                                {
-                                    let delta_time = current_time.saturating_sub(*last_time);
-                                    let delta_rc6 = rc6_ms.saturating_sub(*last_rc6);
-
-                                    if delta_time > 0 {
-                                        // RC6 is "idle time". Utilization = 1.0 - (idle / total)
-                                        // Cap at 100% just in case
-                                        let idle_percent =
-                                            (delta_rc6 as f64 / delta_time as f64) * 100.0;
-                                        utilization_gpu =
-                                            (100.0 - idle_percent).clamp(0.0, 100.0) as u32;
-                                    }
+                            let mut state = self.state.write_or_recover("GPU state");
+                            if let Some((last_rc6, last_time)) =
+                                state.last_rc6_readings.get(&index)
+                            {
+                                let delta_time = current_time.saturating_sub(*last_time);
+                                let delta_rc6 = rc6_ms.saturating_sub(*last_rc6);
+
+                                if delta_time > 0 {
+                                    // RC6 is "idle time". Utilization = 1.0 - (idle / total)
+                                    // Cap at 100% just in case
+                                    let idle_percent =
+                                        (delta_rc6 as f64 / delta_time as f64) * 100.0;
+                                    utilization_gpu =
+                                        (100.0 - idle_percent).clamp(0.0, 100.0) as u32;
                                 }
-                                // Update state
-                                state
-                                    .last_rc6_readings
-                                    .insert(index, (rc6_ms, current_time));
                             }
+                            // Update state
+                            state
+                                .last_rc6_readings
+                                .insert(index, (rc6_ms, current_time));
                         }
                     }
                 }
 
+                // Per-engine busy counters are more accurate than RC6 when exposed
+                if let Some(busiest) = self.intel_engine_utilization(index, &path, current_time) {
+                    utilization_gpu = busiest;
+                }
+
                 gpus.push(GpuInfo {
                     index,
                     name,
                     vendor: GpuVendor::Intel,
-                    uuid: format!("intel-{}", index),
+                    is_integrated: memory_total == 0,
+                    uuid: drm_gpu_uuid("intel", &device_path, bus_id.as_deref(), index),
                     utilization_gpu,
-                    utilization_memory: 0,
-                    memory_total: 0,
-                    memory_used: 0,
-                    memory_free: 0,
+                    utilization_memory,
+                    memory_total,
+                    memory_used,
+                    memory_free,
                     temperature: 0,
+                    temperature_hotspot: None,
+                    temperature_memory: None,
                     power_usage: 0,
                     power_limit: 0,
+                    power_limit_min: None,
+                    power_limit_max: None,
                     fan_speed: None,
                     clock_graphics,
                     clock_memory: 0,
                     encoder_utilization: None,
                     decoder_utilization: None,
+                    encoder_sessions: None,
+                    encoder_fps: None,
+                    throttle_reasons: Vec::new(),
+                    ecc_errors_corrected: None,
+                    ecc_errors_uncorrected: None,
+                    pcie_gen: None,
+                    pcie_width: None,
                 });
 
                 index += 1;
@@ -406,23 +968,214 @@ impl GpuMonitor {
         gpus
     }
 
-    pub fn refresh(&self) -> GpusInfo {
-        // Lazy initialize NVML
-        {
-            let mut state = self.state.write().expect("GPU state RwLock poisoned");
-            if !state.initialized {
-                state.nvml = Nvml::init().ok();
-                state.initialized = true;
+    /// Lazy initialize NVML on first use
+    fn ensure_nvml(&self) {
+        let mut state = self.state.write_or_recover("GPU state");
+        if !state.initialized {
+            match (self.nvml_init)() {
+                Ok(nvml) => state.nvml = Some(nvml),
+                Err(e) => state.nvml_init_error = Some(e.to_string()),
+            }
+            state.initialized = true;
+        }
+    }
+
+    /// Set every fan of an NVIDIA GPU to a fixed speed, or give control back to the
+    /// driver's automatic curve with `None`
+    /// Requires root on Linux (administrator on Windows); cards without manual fan
+    /// control, and laptops in particular, report Unsupported
+    pub fn set_fan_speed(&self, gpu_index: u32, percent: Option<u32>) -> Result<(), MonitorError> {
+        if percent.is_some_and(|p| p > 100) {
+            return Err(MonitorError::InvalidArgument(
+                "Fan speed must be between 0 and 100".to_string(),
+            ));
+        }
+
+        self.ensure_nvml();
+        let state = self.state.read_or_recover("GPU state");
+        let Some(ref nvml) = state.nvml else {
+            return Err(MonitorError::GpuNotAvailable(
+                state
+                    .nvml_init_error
+                    .clone()
+                    .unwrap_or_else(|| "NVML not initialized".to_string()),
+            ));
+        };
+
+        let device = nvml.device_by_index(gpu_index).map_err(|e| {
+            MonitorError::GpuNotAvailable(format!("NVIDIA GPU {}: {}", gpu_index, e))
+        })?;
+        let fan_count = device.num_fans().map_err(fan_control_error)?;
+        if fan_count == 0 {
+            return Err(MonitorError::Unsupported(format!(
+                "NVIDIA GPU {} has no controllable fans",
+                gpu_index
+            )));
+        }
+
+        // nvml-wrapper doesn't cover fan control, so the setters come from the raw bindings
+        let lib = unsafe { NvmlLib::new(NVML_LIBRARY) }
+            .map_err(|e| MonitorError::GpuNotAvailable(e.to_string()))?;
+        let supported = match percent {
+            Some(_) => lib.nvmlDeviceSetFanSpeed_v2.is_ok(),
+            None => lib.nvmlDeviceSetDefaultFanSpeed_v2.is_ok(),
+        };
+        if !supported {
+            return Err(MonitorError::Unsupported(
+                "NVIDIA driver is too old for fan control".to_string(),
+            ));
+        }
+
+        let handle = unsafe { device.handle() };
+        for fan in 0..fan_count {
+            let code = unsafe {
+                match percent {
+                    Some(percent) => lib.nvmlDeviceSetFanSpeed_v2(handle, fan, percent),
+                    None => lib.nvmlDeviceSetDefaultFanSpeed_v2(handle, fan),
+                }
+            };
+            nvml_try(code).map_err(fan_control_error)?;
+        }
+        Ok(())
+    }
+
+    /// Set the power management limit of an NVIDIA GPU in milliwatts, or restore the
+    /// default limit with `None`; the value must lie within the device's constraints
+    /// Requires root on Linux (administrator on Windows); the limit lasts until reboot
+    /// or driver reload
+    pub fn set_power_limit(
+        &self,
+        gpu_index: u32,
+        milliwatts: Option<u32>,
+    ) -> Result<(), MonitorError> {
+        self.ensure_nvml();
+        let state = self.state.read_or_recover("GPU state");
+        let Some(ref nvml) = state.nvml else {
+            return Err(MonitorError::GpuNotAvailable(
+                state
+                    .nvml_init_error
+                    .clone()
+                    .unwrap_or_else(|| "NVML not initialized".to_string()),
+            ));
+        };
+
+        let mut device = nvml.device_by_index(gpu_index).map_err(|e| {
+            MonitorError::GpuNotAvailable(format!("NVIDIA GPU {}: {}", gpu_index, e))
+        })?;
+        let limit = match milliwatts {
+            Some(milliwatts) => {
+                let constraints = device
+                    .power_management_limit_constraints()
+                    .map_err(power_limit_error)?;
+                validate_power_limit(milliwatts, constraints.min_limit, constraints.max_limit)?;
+                milliwatts
+            }
+            None => device
+                .power_management_limit_default()
+                .map_err(power_limit_error)?,
+        };
+        device
+            .set_power_management_limit(limit)
+            .map_err(power_limit_error)
+    }
+
+    /// List processes using NVIDIA GPUs with their memory and utilization
+    pub fn processes(&self) -> GpuProcessList {
+        self.ensure_nvml();
+
+        let mut processes = Vec::new();
+        let mut errors = Vec::new();
+
+        let state = self.state.read_or_recover("GPU state");
+        let Some(ref nvml) = state.nvml else {
+            errors.push("NVIDIA: NVML not initialized".to_string());
+            return GpuProcessList { processes, errors };
+        };
+
+        match nvml.device_count() {
+            Ok(device_count) => {
+                for i in 0..device_count {
+                    let Ok(device) = nvml.device_by_index(i) else {
+                        continue;
+                    };
+
+                    let compute = device.running_compute_processes().unwrap_or_default();
+                    let graphics = device.running_graphics_processes().unwrap_or_default();
+                    // Not supported before Maxwell; memory figures are still useful without it
+                    let samples = device.process_utilization_stats(None).unwrap_or_default();
+
+                    processes.extend(merge_gpu_processes(i, &compute, &graphics, &samples));
+                }
+            }
+            Err(e) => errors.push(format!("NVIDIA: Failed to get device count: {}", e)),
+        }
+
+        GpuProcessList { processes, errors }
+    }
+
+    #[cfg(target_os = "linux")]
+    /// Utilization of the busiest engine since the last refresh, from gt/gt0/engines/*/busy
+    /// The busy files hold cumulative busy time in nanoseconds
+    fn intel_engine_utilization(&self, index: u32, card_path: &Path, now_ms: u64) -> Option<u32> {
+        let entries = fs::read_dir(card_path.join("gt/gt0/engines")).ok()?;
+        let mut state = self.state.write_or_recover("GPU state");
+        let mut busiest: Option<u32> = None;
+
+        for entry in entries.flatten() {
+            let Some(busy_ns) = fs::read_to_string(entry.path().join("busy"))
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+            else {
+                continue;
+            };
+
+            let key = (index, entry.file_name().to_string_lossy().to_string());
+            if let Some(&(last_busy, last_time)) = state.last_engine_busy.get(&key) {
+                let delta_time_ns = now_ms.saturating_sub(last_time) * 1_000_000;
+                if delta_time_ns > 0 {
+                    let percent = (busy_ns.saturating_sub(last_busy) as f64 / delta_time_ns as f64
+                        * 100.0)
+                        .clamp(0.0, 100.0) as u32;
+                    busiest = Some(busiest.map_or(percent, |b| b.max(percent)));
+                }
             }
+            state.last_engine_busy.insert(key, (busy_ns, now_ms));
         }
 
+        busiest
+    }
+
+    pub fn refresh(&self) -> GpusInfo {
+        self.ensure_nvml();
+
         let mut all_gpus = Vec::new();
         let mut driver_version: Option<String> = None;
         let mut errors = Vec::new();
 
         all_gpus.extend(self.get_nvidia_gpus(&mut driver_version, &mut errors));
-        all_gpus.extend(self.get_amd_gpus(&mut errors));
-        all_gpus.extend(self.get_intel_gpus(&mut errors));
+
+        #[cfg(target_os = "linux")]
+        {
+            all_gpus.extend(self.get_amd_gpus(Path::new(DRM_PATH), &mut errors));
+            all_gpus.extend(self.get_intel_gpus(Path::new(DRM_PATH), &mut errors));
+        }
+
+        #[cfg(windows)]
+        all_gpus.extend(dxgi::get_dxgi_gpus(
+            &mut self.state.write_or_recover("GPU state").dxgi,
+            &mut driver_version,
+            &mut errors,
+        ));
+
+        #[cfg(not(any(target_os = "linux", windows)))]
+        errors.push("AMD/Intel: GPU monitoring is not supported on this platform".to_string());
+
+        self.record_history(&all_gpus);
+
+        let (nvidia_driver_loaded, nvidia_init_error) = {
+            let state = self.state.read_or_recover("GPU state");
+            (state.nvml.is_some(), state.nvml_init_error.clone())
+        };
 
         let nvidia_available = !all_gpus
             .iter()
@@ -432,15 +1185,239 @@ impl GpuMonitor {
             .iter()
             .any(|g| matches!(g.vendor, GpuVendor::Intel));
 
-        GpusInfo {
+        let info = GpusInfo {
             gpus: all_gpus,
             nvidia_available,
             amd_available,
             intel_available,
+            nvidia_driver_loaded,
+            nvidia_init_error,
             driver_version,
+            temperature_unit: TemperatureUnit::Celsius.symbol().to_string(),
             errors,
+        };
+        self.state.write_or_recover("GPU state").last_info = Some(info.clone());
+        info
+    }
+
+    /// Stand in for a refresh in tests, without any hardware
+    #[cfg(test)]
+    pub(crate) fn set_cached(&self, info: GpusInfo) {
+        self.state.write_or_recover("GPU state").last_info = Some(info);
+    }
+}
+
+// PCI device ids of AMD APU graphics (Raven, Picasso, Renoir, Cezanne, Rembrandt,
+// Mendocino, Raphael, Phoenix, Strix Point)
+#[cfg(any(target_os = "linux", windows))]
+const AMD_IGPU_DEVICE_IDS: [u32; 11] = [
+    0x15dd, 0x15d8, 0x1636, 0x164c, 0x1638, 0x1681, 0x1506, 0x164e, 0x15bf, 0x15c8, 0x150e,
+];
+
+/// Whether an AMD PCI device id belongs to an APU's integrated graphics
+#[cfg(any(target_os = "linux", windows))]
+fn is_amd_igpu(device_id: u32) -> bool {
+    AMD_IGPU_DEVICE_IDS.contains(&device_id)
+}
+
+/// Map an NVML fan control failure to the matching MonitorError
+fn fan_control_error(err: NvmlError) -> MonitorError {
+    match err {
+        NvmlError::NoPermission => MonitorError::PermissionDenied(
+            "Fan control requires root/administrator privileges".to_string(),
+        ),
+        NvmlError::NotSupported => {
+            MonitorError::Unsupported("This GPU doesn't support manual fan control".to_string())
         }
+        other => MonitorError::SystemAccess(format!("NVML fan control failed: {}", other)),
+    }
+}
+
+/// Map NVML errors from power limit calls to the matching MonitorError
+fn power_limit_error(err: NvmlError) -> MonitorError {
+    match err {
+        NvmlError::NoPermission => MonitorError::PermissionDenied(
+            "Changing the power limit requires root/administrator privileges".to_string(),
+        ),
+        NvmlError::NotSupported => {
+            MonitorError::Unsupported("This GPU doesn't support power limit changes".to_string())
+        }
+        other => MonitorError::SystemAccess(format!("NVML power limit call failed: {}", other)),
+    }
+}
+
+/// Check a requested power limit against the device's (min, max) constraints
+fn validate_power_limit(milliwatts: u32, min: u32, max: u32) -> Result<(), MonitorError> {
+    if (min..=max).contains(&milliwatts) {
+        Ok(())
+    } else {
+        Err(MonitorError::InvalidArgument(format!(
+            "Power limit must be between {} and {} mW",
+            min, max
+        )))
+    }
+}
+
+/// Read dedicated VRAM (total, used) in bytes from mem_info_vram_* (amdgpu, i915/xe)
+/// Devices without dedicated VRAM report (0, 0)
+#[cfg(target_os = "linux")]
+fn read_vram_info(device_path: &Path) -> (u64, u64) {
+    let read = |file: &str| {
+        fs::read_to_string(device_path.join(file))
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    (read("mem_info_vram_total"), read("mem_info_vram_used"))
+}
+
+/// Identifier that survives reboots and card renumbering, for keying history: the
+/// device's unique_id (amdgpu serial) when exposed, else its PCI slot, else the index
+#[cfg(target_os = "linux")]
+fn drm_gpu_uuid(vendor: &str, device_path: &Path, bus_id: Option<&str>, index: u32) -> String {
+    let unique_id = fs::read_to_string(device_path.join("unique_id"))
+        .ok()
+        .map(|id| id.trim().to_lowercase())
+        .filter(|id| !id.is_empty());
+    match (unique_id, bus_id) {
+        (Some(id), _) => format!("{}-{}", vendor, id),
+        (None, Some(bus_id)) => format!("{}-{}", vendor, bus_id),
+        (None, None) => format!("{}-{}", vendor, index),
+    }
+}
+
+/// Per-card values reported by rocm-smi
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Default)]
+struct RocmSmiCard {
+    utilization: Option<u32>,  // Percentage
+    memory_total: Option<u64>, // Bytes
+    memory_used: Option<u64>,  // Bytes
+    temperature: Option<u32>,  // Celsius
+    power_usage: Option<u32>,  // Milliwatts
+}
+
+/// Parse `rocm-smi --json` output into cards keyed by lowercase PCI bus id
+/// Key names vary between rocm-smi releases, so fields are matched by prefix
+#[cfg(target_os = "linux")]
+fn parse_rocm_smi_json(json: &str) -> HashMap<String, RocmSmiCard> {
+    let mut cards = HashMap::new();
+    let Ok(serde_json::Value::Object(root)) = serde_json::from_str::<serde_json::Value>(json) else {
+        return cards;
+    };
+
+    for (key, value) in root {
+        let Some(fields) = value.as_object().filter(|_| key.starts_with("card")) else {
+            continue;
+        };
+
+        let number = |prefix: &str| rocm_smi_number(fields, |k| k.starts_with(prefix));
+
+        let Some(bus_id) = fields.get("PCI Bus").and_then(|v| v.as_str()) else {
+            continue;
+        };
+
+        cards.insert(
+            bus_id.trim().to_lowercase(),
+            RocmSmiCard {
+                utilization: number("GPU use (%)").map(|v| v as u32),
+                memory_total: number("VRAM Total Memory (B)").map(|v| v as u64),
+                memory_used: number("VRAM Total Used Memory (B)").map(|v| v as u64),
+                temperature: number("Temperature (Sensor edge)")
+                    .or_else(|| number("Temperature"))
+                    .map(|v| v as u32),
+                power_usage: rocm_smi_number(fields, |k| k.contains("Graphics Package Power (W)"))
+                    .map(|watts| (watts * 1000.0) as u32),
+            },
+        );
+    }
+
+    cards
+}
+
+/// First numeric value whose key matches; rocm-smi emits most numbers as strings
+#[cfg(target_os = "linux")]
+fn rocm_smi_number(
+    fields: &serde_json::Map<String, serde_json::Value>,
+    matches: impl Fn(&str) -> bool,
+) -> Option<f64> {
+    fields
+        .iter()
+        .find(|(k, _)| matches(k))
+        .and_then(|(_, v)| match v {
+            serde_json::Value::String(s) => s.trim().parse::<f64>().ok(),
+            other => other.as_f64(),
+        })
+}
+
+// NVML clock throttle reason bits (nvmlClocksThrottleReason*)
+const THROTTLE_REASONS: [(u64, &str); 9] = [
+    (0x0000_0001, "GpuIdle"),
+    (0x0000_0002, "ApplicationsClocksSetting"),
+    (0x0000_0004, "SwPowerCap"),
+    (0x0000_0008, "HwSlowdown"),
+    (0x0000_0010, "SyncBoost"),
+    (0x0000_0020, "SwThermalSlowdown"),
+    (0x0000_0040, "HwThermalSlowdown"),
+    (0x0000_0080, "HwPowerBrakeSlowdown"),
+    (0x0000_0100, "DisplayClockSetting"),
+];
+
+/// Translate an NVML throttle-reason bitmask into readable names
+fn decode_throttle_reasons(mask: u64) -> Vec<String> {
+    THROTTLE_REASONS
+        .iter()
+        .filter(|(bit, _)| mask & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Merge compute and graphics process lists for one device, deduplicated by PID
+/// A PID in both lists keeps the larger memory figure; utilization comes from the newest sample
+fn merge_gpu_processes(
+    gpu_index: u32,
+    compute: &[ProcessInfo],
+    graphics: &[ProcessInfo],
+    samples: &[ProcessUtilizationSample],
+) -> Vec<GpuProcess> {
+    let mut latest: HashMap<u32, &ProcessUtilizationSample> = HashMap::new();
+    for sample in samples {
+        latest
+            .entry(sample.pid)
+            .and_modify(|e| {
+                if sample.timestamp > e.timestamp {
+                    *e = sample;
+                }
+            })
+            .or_insert(sample);
     }
+
+    let mut merged: HashMap<u32, GpuProcess> = HashMap::new();
+    for proc in compute.iter().chain(graphics) {
+        let used_memory = match proc.used_gpu_memory {
+            UsedGpuMemory::Used(bytes) => bytes,
+            UsedGpuMemory::Unavailable => 0,
+        };
+
+        merged
+            .entry(proc.pid)
+            .and_modify(|e| e.used_memory = e.used_memory.max(used_memory))
+            .or_insert_with(|| {
+                let sample = latest.get(&proc.pid);
+                GpuProcess {
+                    pid: proc.pid,
+                    gpu_index,
+                    used_memory,
+                    sm_util: sample.map(|s| s.sm_util),
+                    enc_util: sample.map(|s| s.enc_util),
+                    dec_util: sample.map(|s| s.dec_util),
+                }
+            });
+    }
+
+    let mut processes: Vec<GpuProcess> = merged.into_values().collect();
+    processes.sort_by_key(|p| std::cmp::Reverse(p.used_memory));
+    processes
 }
 
 impl Default for GpuMonitor {
@@ -448,3 +1425,362 @@ impl Default for GpuMonitor {
         Self::new()
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::modules::{FakeCommandRunner, ScratchDir};
+
+    // Trimmed `rocm-smi --showuse --showmeminfo vram --showtemp --showpower --showbus --json`
+    const ROCM_SMI_JSON: &str = r#"{
+        "card0": {
+            "Temperature (Sensor edge) (C)": "48.0",
+            "Temperature (Sensor junction) (C)": "51.0",
+            "Average Graphics Package Power (W)": "41.0",
+            "GPU use (%)": "37",
+            "VRAM Total Memory (B)": "17163091968",
+            "VRAM Total Used Memory (B)": "2147483648",
+            "PCI Bus": "0000:0A:00.0"
+        },
+        "card1": {
+            "Temperature (Sensor edge) (C)": "40.0",
+            "GPU use (%)": "3",
+            "PCI Bus": "0000:0B:00.0"
+        },
+        "system": {"Driver version": "6.8.0"}
+    }"#;
+
+    /// A drm class directory whose card0 and renderD128 nodes share one AMD PCI device
+    fn fake_drm() -> ScratchDir {
+        let dir = ScratchDir::new("gpu-drm");
+        let device = dir.write("devices/0000:0a:00.0/vendor", "0x1002\n");
+        let device = device.parent().unwrap();
+        for node in ["card0", "renderD128"] {
+            fs::create_dir_all(dir.join("drm").join(node)).unwrap();
+            std::os::unix::fs::symlink(device, dir.join("drm").join(node).join("device")).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn drm_uuid_prefers_unique_id_then_pci_slot() {
+        let device = ScratchDir::new("gpu-uuid");
+
+        assert_eq!(drm_gpu_uuid("intel", &device, Some("0000:00:02.0"), 0), "intel-0000:00:02.0");
+        assert_eq!(drm_gpu_uuid("intel", &device, None, 1), "intel-1");
+
+        fs::write(device.join("unique_id"), "3A2F1B0C5D6E7F80\n").unwrap();
+        assert_eq!(drm_gpu_uuid("amd", &device, Some("0000:03:00.0"), 0), "amd-3a2f1b0c5d6e7f80");
+    }
+
+    #[test]
+    fn merges_graphics_and_compute_entries_per_pid() {
+        let process = |pid: u32, used: Option<u64>| ProcessInfo {
+            pid,
+            used_gpu_memory: used.map_or(UsedGpuMemory::Unavailable, UsedGpuMemory::Used),
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        };
+        let sample = |pid: u32, timestamp: u64, sm_util: u32| ProcessUtilizationSample {
+            pid,
+            timestamp,
+            sm_util,
+            mem_util: 0,
+            enc_util: 0,
+            dec_util: 0,
+        };
+
+        // PID 100 runs CUDA inside a GUI app, so NVML lists it in both tables
+        let compute = [process(100, Some(512 << 20)), process(200, Some(64 << 20))];
+        let graphics = [process(100, Some(256 << 20)), process(300, None)];
+        let samples = [sample(100, 1, 10), sample(100, 2, 40), sample(200, 1, 5)];
+
+        let merged = merge_gpu_processes(1, &compute, &graphics, &samples);
+        let pids: Vec<u32> = merged.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [100, 200, 300]);
+
+        // Memory isn't added up across the two lists
+        assert_eq!(merged[0].used_memory, 512 << 20);
+        assert_eq!(merged[0].sm_util, Some(40));
+        assert_eq!(merged[0].gpu_index, 1);
+        assert_eq!(merged[2].used_memory, 0);
+        assert_eq!(merged[2].sm_util, None);
+    }
+
+    #[test]
+    fn decodes_throttle_reason_bits_and_ignores_unknown_ones() {
+        assert!(decode_throttle_reasons(0).is_empty());
+        assert_eq!(decode_throttle_reasons(0x1), ["GpuIdle"]);
+        assert_eq!(
+            decode_throttle_reasons(0x4 | 0x40 | 0x100),
+            ["SwPowerCap", "HwThermalSlowdown", "DisplayClockSetting"]
+        );
+        // Bits newer drivers may add have no name yet and are dropped
+        assert_eq!(decode_throttle_reasons(0x8 | 0x200 | 1 << 40), ["HwSlowdown"]);
+    }
+
+    #[test]
+    fn parses_rocm_smi_cards_by_lowercase_bus_id() {
+        let cards = parse_rocm_smi_json(ROCM_SMI_JSON);
+        assert_eq!(cards.len(), 2);
+
+        let card = &cards["0000:0a:00.0"];
+        assert_eq!(card.utilization, Some(37));
+        assert_eq!(card.memory_total, Some(17_163_091_968));
+        assert_eq!(card.memory_used, Some(2_147_483_648));
+        assert_eq!(card.temperature, Some(48));
+        assert_eq!(card.power_usage, Some(41_000));
+        assert_eq!(cards["0000:0b:00.0"].memory_total, None);
+
+        assert!(parse_rocm_smi_json("rocm-smi: command failed").is_empty());
+    }
+
+    #[test]
+    fn reads_dedicated_vram_from_sysfs() {
+        let device = ScratchDir::new("gpu-vram");
+        assert_eq!(read_vram_info(&device), (0, 0));
+
+        device.write("mem_info_vram_total", "8573157376\n");
+        device.write("mem_info_vram_used", "1234567168\n");
+        assert_eq!(read_vram_info(&device), (8_573_157_376, 1_234_567_168));
+
+        // An unreadable counter only zeroes that half
+        device.write("mem_info_vram_used", "n/a\n");
+        assert_eq!(read_vram_info(&device), (8_573_157_376, 0));
+    }
+
+    /// Device set whose static reads are counted
+    struct FakeNvidia {
+        names: Vec<&'static str>,
+        static_reads: std::cell::Cell<usize>,
+    }
+
+    impl NvidiaDevices for FakeNvidia {
+        fn driver_version(&self) -> Option<String> {
+            Some("550.54.14".to_string())
+        }
+
+        fn static_info(&self, index: u32) -> Option<NvidiaStaticInfo> {
+            self.static_reads.set(self.static_reads.get() + 1);
+            let name = self.names.get(index as usize)?;
+            Some(NvidiaStaticInfo {
+                name: name.to_string(),
+                uuid: format!("GPU-{}", index),
+                power_limit_range: Some((100_000, 450_000)),
+                memory_total: 24 << 30,
+            })
+        }
+    }
+
+    #[test]
+    fn static_nvidia_info_is_read_once_per_device_set() {
+        let mut devices = FakeNvidia {
+            names: vec!["NVIDIA GeForce RTX 4090"],
+            static_reads: std::cell::Cell::new(0),
+        };
+        let mut cache = Vec::new();
+        let mut driver_version = None;
+
+        for _ in 0..3 {
+            update_nvidia_static(&devices, &mut cache, &mut driver_version, 1);
+        }
+        assert_eq!(devices.static_reads.get(), 1);
+        assert_eq!(driver_version.as_deref(), Some("550.54.14"));
+        assert_eq!(cache[0].as_ref().unwrap().name, "NVIDIA GeForce RTX 4090");
+
+        // An eGPU shows up: both devices are read again, once
+        devices.names.push("NVIDIA RTX A2000");
+        update_nvidia_static(&devices, &mut cache, &mut driver_version, 2);
+        update_nvidia_static(&devices, &mut cache, &mut driver_version, 2);
+        assert_eq!(devices.static_reads.get(), 3);
+        assert_eq!(cache[1].as_ref().unwrap().uuid, "GPU-1");
+
+        // A device that can't be opened stays a hole rather than shifting the others
+        update_nvidia_static(&devices, &mut cache, &mut driver_version, 3);
+        assert_eq!(cache.len(), 3);
+        assert!(cache[2].is_none());
+    }
+
+    #[test]
+    fn rocm_smi_fills_sysfs_cards_without_busy_percent() {
+        let drm = fake_drm();
+        let runner = FakeCommandRunner::default();
+        runner.respond("rocm-smi", 0, ROCM_SMI_JSON);
+        let monitor = GpuMonitor::with_runner(Box::new(runner.clone()));
+
+        let mut errors = Vec::new();
+        let gpus = monitor.get_amd_gpus(&drm.join("drm"), &mut errors);
+        // card0 and renderD128 are one GPU, matched to rocm-smi's card by PCI bus id;
+        // card1 has no sysfs node and isn't added
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].utilization_gpu, 37);
+        assert_eq!(gpus[0].memory_total, 17_163_091_968);
+        assert_eq!(gpus[0].temperature, 48);
+        assert!(errors.is_empty());
+
+        // The next refresh reuses the cached result
+        monitor.get_amd_gpus(&drm.join("drm"), &mut errors);
+        assert_eq!(runner.calls().len(), 1);
+
+        // A card that reports 0% busy is idle, not missing the file
+        drm.write("devices/0000:0a:00.0/gpu_busy_percent", "0\n");
+        let gpus = monitor.get_amd_gpus(&drm.join("drm"), &mut errors);
+        assert_eq!(gpus[0].utilization_gpu, 0);
+    }
+
+    #[test]
+    fn missing_rocm_smi_is_not_retried() {
+        let drm = fake_drm();
+        let runner = FakeCommandRunner::default();
+        let monitor = GpuMonitor::with_runner(Box::new(runner.clone()));
+
+        let mut errors = Vec::new();
+        for _ in 0..3 {
+            let gpus = monitor.get_amd_gpus(&drm.join("drm"), &mut errors);
+            assert_eq!(gpus[0].utilization_gpu, 0);
+        }
+        assert_eq!(runner.calls().len(), 1);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn reads_labeled_amd_temperatures() {
+        let device = ScratchDir::new("gpu-amd-temps");
+        device.write("hwmon/hwmon4/name", "amdgpu\n");
+        device.write("hwmon/hwmon4/temp1_label", "edge\n");
+        device.write("hwmon/hwmon4/temp1_input", "52000\n");
+        device.write("hwmon/hwmon4/temp2_label", "junction\n");
+        device.write("hwmon/hwmon4/temp2_input", "67000\n");
+        device.write("hwmon/hwmon4/temp3_label", "mem\n");
+        device.write("hwmon/hwmon4/temp3_input", "74000\n");
+        // A fan label mustn't be mistaken for a temperature
+        device.write("hwmon/hwmon4/fan1_label", "junction\n");
+
+        let labeled = |label| GpuMonitor::find_amd_labeled_temperature(&device, label);
+        assert_eq!(labeled("edge"), Some(52));
+        assert_eq!(labeled("junction"), Some(67));
+        assert_eq!(labeled("mem"), Some(74));
+        assert_eq!(labeled("vddgfx"), None);
+        assert_eq!(GpuMonitor::find_amd_temperature(&device), Some(52));
+
+        // APUs only expose edge
+        let apu = ScratchDir::new("gpu-apu-temps");
+        apu.write("hwmon/hwmon1/temp1_label", "edge\n");
+        apu.write("hwmon/hwmon1/temp1_input", "48000\n");
+        assert_eq!(GpuMonitor::find_amd_labeled_temperature(&apu, "junction"), None);
+    }
+
+    #[test]
+    fn filtering_keeps_availability_flags_and_errors() {
+        let gpu = |vendor, is_integrated| GpuInfo {
+            vendor,
+            is_integrated,
+            ..Default::default()
+        };
+        let info = GpusInfo {
+            gpus: vec![
+                gpu(GpuVendor::Nvidia, false),
+                gpu(GpuVendor::Amd, true),
+                gpu(GpuVendor::Intel, true),
+            ],
+            nvidia_available: true,
+            amd_available: true,
+            intel_available: true,
+            nvidia_driver_loaded: true,
+            driver_version: Some("550.54.14".to_string()),
+            errors: vec!["AMD: rocm-smi exited with exit status: 1".to_string()],
+            ..Default::default()
+        };
+
+        let discrete = info.clone().filter(&GpuQuery {
+            vendors: None,
+            include_integrated: false,
+        });
+        assert_eq!(discrete.gpus.len(), 1);
+        assert_eq!(discrete.gpus[0].vendor, GpuVendor::Nvidia);
+
+        let intel = info.clone().filter(&GpuQuery {
+            vendors: Some(vec![GpuVendor::Intel]),
+            ..Default::default()
+        });
+        assert_eq!(intel.gpus.len(), 1);
+        assert!(intel.nvidia_available && intel.amd_available && intel.intel_available);
+        assert!(intel.nvidia_driver_loaded);
+        assert_eq!(intel.driver_version, info.driver_version);
+        assert_eq!(intel.errors, info.errors);
+
+        assert_eq!(info.filter(&GpuQuery::default()).gpus.len(), 3);
+    }
+
+    #[test]
+    fn nvml_init_failure_is_reported() {
+        let monitor = GpuMonitor {
+            nvml_init: || Err(NvmlError::DriverNotLoaded),
+            ..GpuMonitor::new()
+        };
+
+        assert!(!monitor.nvml_available());
+        let info = monitor.refresh();
+        let message = NvmlError::DriverNotLoaded.to_string();
+        assert!(!info.nvidia_driver_loaded);
+        assert_eq!(info.nvidia_init_error.as_deref(), Some(message.as_str()));
+        assert!(info
+            .errors
+            .contains(&format!("NVIDIA: NVML not initialized: {}", message)));
+        assert!(monitor.processes().errors[0].starts_with("NVIDIA: NVML not initialized"));
+    }
+
+    #[test]
+    fn out_of_range_fan_speed_is_rejected_before_loading_nvml() {
+        let monitor = GpuMonitor {
+            nvml_init: || panic!("NVML must not be loaded for an invalid fan speed"),
+            ..GpuMonitor::new()
+        };
+        assert!(matches!(
+            monitor.set_fan_speed(0, Some(101)),
+            Err(MonitorError::InvalidArgument(_))
+        ));
+        assert!(!monitor.state.read_or_recover("GPU state").initialized);
+    }
+
+    #[test]
+    fn only_listed_pids_pass_the_gpu_process_guard() {
+        let list = GpuProcessList {
+            processes: vec![GpuProcess {
+                pid: 4242,
+                gpu_index: 0,
+                used_memory: 512 << 20,
+                sm_util: Some(30),
+                enc_util: None,
+                dec_util: None,
+            }],
+            errors: Vec::new(),
+        };
+        assert!(list.ensure_contains(4242).is_ok());
+        assert!(matches!(list.ensure_contains(1), Err(MonitorError::NotGpuProcess(1))));
+        assert_eq!(
+            String::from(list.ensure_contains(1).unwrap_err()),
+            "Process 1 is not using a GPU"
+        );
+
+        // Without NVML the list is empty, so nothing can be killed from the GPU pane
+        let unavailable = GpuProcessList {
+            processes: Vec::new(),
+            errors: vec!["NVIDIA: NVML not initialized".to_string()],
+        };
+        assert!(unavailable.ensure_contains(4242).is_err());
+    }
+
+    #[test]
+    fn power_limit_bounds_are_inclusive() {
+        let (min, max) = (100_000, 450_000);
+        assert!(validate_power_limit(min, min, max).is_ok());
+        assert!(validate_power_limit(max, min, max).is_ok());
+        for milliwatts in [min - 1, max + 1] {
+            assert!(matches!(
+                validate_power_limit(milliwatts, min, max),
+                Err(MonitorError::InvalidArgument(_))
+            ));
+        }
+    }
+}
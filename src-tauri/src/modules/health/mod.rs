@@ -0,0 +1,286 @@
+// Health Module
+// Rolls a snapshot up into an at-a-glance Ok/Warning/Critical status
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::modules::disk::{parent_disk_name, SmartHealth};
+use crate::modules::snapshot::Snapshot;
+
+/// Severity of a health issue, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// A single problem found in the snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub component: String, // "cpu", "memory", "disk:/home", "network:eth0", ...
+    pub level: HealthLevel,
+    pub message: String,
+}
+
+/// Overall status plus the issues behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSummary {
+    pub overall: HealthLevel, // Worst level among the issues, Ok when there are none
+    pub issues: Vec<HealthIssue>,
+}
+
+// CPU temperature thresholds (85 / 95 °C)
+const CPU_TEMP_WARNING: f32 = 85.0;
+const CPU_TEMP_CRITICAL: f32 = 95.0;
+
+// Filesystem usage thresholds (90 / 97 %)
+const DISK_USAGE_WARNING: f32 = 90.0;
+const DISK_USAGE_CRITICAL: f32 = 97.0;
+
+// Share of the last 10 s tasks stalled on memory (PSI): some task 10 %, all tasks 10 %
+const PRESSURE_SOME_WARNING: f32 = 10.0;
+const PRESSURE_FULL_CRITICAL: f32 = 10.0;
+
+// Without PSI, memory and swap usage that together indicate thrashing
+// (95 % RAM, 50 / 90 % swap)
+const THRASH_MEMORY_PERCENT: f32 = 95.0;
+const THRASH_SWAP_WARNING: f32 = 50.0;
+const THRASH_SWAP_CRITICAL: f32 = 90.0;
+
+// Receive errors per second on one interface (1 / 100)
+const NETWORK_ERRORS_WARNING: f64 = 1.0;
+const NETWORK_ERRORS_CRITICAL: f64 = 100.0;
+
+/// Derive health issues from a snapshot; temperatures must be in Celsius
+pub fn summarize(snapshot: &Snapshot) -> HealthSummary {
+    let mut issues = Vec::new();
+    let mut report = |component: String, level: Option<HealthLevel>, message: String| {
+        if let Some(level) = level {
+            issues.push(HealthIssue {
+                component,
+                level,
+                message,
+            });
+        }
+    };
+
+    if let Some(temp) = snapshot.sensors.cpu_temp {
+        report(
+            "cpu".to_string(),
+            level_above(temp, CPU_TEMP_WARNING, CPU_TEMP_CRITICAL),
+            format!("CPU temperature is {:.0} °C", temp),
+        );
+    }
+
+    // PSI measures stalls directly; without it thrashing is inferred from RAM being nearly
+    // exhausted while a large share of swap is in use
+    let memory = &snapshot.memory;
+    if let Some(pressure) = memory.pressure {
+        let level = if pressure.full_avg10 >= PRESSURE_FULL_CRITICAL {
+            Some(HealthLevel::Critical)
+        } else if pressure.some_avg10 >= PRESSURE_SOME_WARNING {
+            Some(HealthLevel::Warning)
+        } else {
+            None
+        };
+        report(
+            "memory".to_string(),
+            level,
+            format!(
+                "Tasks stalled on memory {:.0}% of the last 10 s ({:.0}% fully stalled)",
+                pressure.some_avg10, pressure.full_avg10
+            ),
+        );
+    } else if memory.memory_usage_percent >= THRASH_MEMORY_PERCENT {
+        report(
+            "memory".to_string(),
+            level_above(
+                memory.swap_usage_percent,
+                THRASH_SWAP_WARNING,
+                THRASH_SWAP_CRITICAL,
+            ),
+            format!(
+                "Memory is {:.0}% used and swap {:.0}% used; the system is likely thrashing",
+                memory.memory_usage_percent, memory.swap_usage_percent
+            ),
+        );
+    }
+
+    // Read-only mounts (squashfs snaps, ISO images) are always full by design
+    for disk in snapshot
+        .disk
+        .disks
+        .iter()
+        .filter(|d| !d.read_only && d.total_space > 0)
+    {
+        report(
+            format!("disk:{}", disk.mount_point),
+            level_above(disk.usage_percent, DISK_USAGE_WARNING, DISK_USAGE_CRITICAL),
+            format!("{} is {:.0}% full", disk.mount_point, disk.usage_percent),
+        );
+    }
+
+    // Every partition carries its disk's SMART data, so report each disk once
+    let mut failed_disks = HashSet::new();
+    for disk in &snapshot.disk.disks {
+        let failed = disk
+            .smart
+            .as_ref()
+            .is_some_and(|smart| matches!(smart.health, SmartHealth::Failed));
+        let parent = parent_disk_name(&disk.name);
+        if failed && failed_disks.insert(parent.clone()) {
+            report(
+                format!("disk:{}", parent),
+                Some(HealthLevel::Critical),
+                format!("SMART health check failed on {}", parent),
+            );
+        }
+    }
+
+    for iface in &snapshot.network.interfaces {
+        report(
+            format!("network:{}", iface.name),
+            level_above(
+                iface.errors_in_per_sec,
+                NETWORK_ERRORS_WARNING,
+                NETWORK_ERRORS_CRITICAL,
+            ),
+            format!(
+                "{} is seeing {:.1} receive errors per second",
+                iface.name, iface.errors_in_per_sec
+            ),
+        );
+    }
+
+    HealthSummary {
+        overall: issues
+            .iter()
+            .map(|issue| issue.level)
+            .max()
+            .unwrap_or(HealthLevel::Ok),
+        issues,
+    }
+}
+
+/// Warning at or above `warning`, Critical at or above `critical`, None below both
+fn level_above<T: PartialOrd>(value: T, warning: T, critical: T) -> Option<HealthLevel> {
+    if value >= critical {
+        Some(HealthLevel::Critical)
+    } else if value >= warning {
+        Some(HealthLevel::Warning)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::disk::{DiskInfo, SmartInfo};
+    use crate::modules::memory::MemoryPressure;
+    use crate::modules::network::NetworkInterface;
+
+    fn levels(summary: &HealthSummary) -> Vec<(&str, HealthLevel)> {
+        summary
+            .issues
+            .iter()
+            .map(|issue| (issue.component.as_str(), issue.level))
+            .collect()
+    }
+
+    #[test]
+    fn quiet_snapshot_is_ok() {
+        let mut snapshot = Snapshot::default();
+        snapshot.sensors.cpu_temp = Some(50.0);
+        snapshot.memory.pressure = Some(MemoryPressure { some_avg10: 2.0, full_avg10: 0.0 });
+        let summary = summarize(&snapshot);
+        assert_eq!(summary.overall, HealthLevel::Ok);
+        assert!(summary.issues.is_empty());
+    }
+
+    #[test]
+    fn warning_levels() {
+        let mut snapshot = Snapshot::default();
+        snapshot.sensors.cpu_temp = Some(CPU_TEMP_WARNING);
+        snapshot.memory.pressure = Some(MemoryPressure { some_avg10: 25.0, full_avg10: 1.0 });
+        snapshot.network.interfaces = vec![NetworkInterface {
+            name: "eth0".into(),
+            errors_in_per_sec: 5.0,
+            ..Default::default()
+        }];
+        let summary = summarize(&snapshot);
+        assert_eq!(summary.overall, HealthLevel::Warning);
+        assert_eq!(
+            levels(&summary),
+            [
+                ("cpu", HealthLevel::Warning),
+                ("memory", HealthLevel::Warning),
+                ("network:eth0", HealthLevel::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn critical_levels() {
+        let mut snapshot = Snapshot::default();
+        snapshot.memory.pressure = Some(MemoryPressure { some_avg10: 60.0, full_avg10: 30.0 });
+        let full_disk = |name: &str, mount_point: &str| DiskInfo {
+            name: name.into(),
+            mount_point: mount_point.into(),
+            total_space: 100,
+            usage_percent: 99.0,
+            smart: Some(SmartInfo {
+                health: SmartHealth::Failed,
+                temperature: None,
+                power_on_hours: None,
+                power_cycle_count: None,
+            }),
+            ..Default::default()
+        };
+        snapshot.disk.disks = vec![full_disk("/dev/sdz1", "/"), full_disk("/dev/sdz2", "/home")];
+        let summary = summarize(&snapshot);
+        assert_eq!(summary.overall, HealthLevel::Critical);
+        // Both partitions are full, but the failed disk is reported once
+        assert_eq!(
+            levels(&summary),
+            [
+                ("memory", HealthLevel::Critical),
+                ("disk:/", HealthLevel::Critical),
+                ("disk:/home", HealthLevel::Critical),
+                ("disk:sdz", HealthLevel::Critical),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_only_mounts_are_never_full() {
+        let mut snapshot = Snapshot::default();
+        snapshot.disk.disks = vec![DiskInfo {
+            name: "/dev/loop0".into(),
+            mount_point: "/snap/core/1".into(),
+            total_space: 100,
+            usage_percent: 100.0,
+            read_only: true,
+            ..Default::default()
+        }];
+        assert_eq!(summarize(&snapshot).overall, HealthLevel::Ok);
+    }
+
+    #[test]
+    fn thrashing_is_inferred_without_psi() {
+        let mut snapshot = Snapshot::default();
+        snapshot.memory.memory_usage_percent = 97.0;
+        snapshot.memory.swap_usage_percent = 60.0;
+        assert_eq!(levels(&summarize(&snapshot)), [("memory", HealthLevel::Warning)]);
+
+        snapshot.memory.swap_usage_percent = 95.0;
+        assert_eq!(levels(&summarize(&snapshot)), [("memory", HealthLevel::Critical)]);
+
+        // PSI wins over the inference when present
+        snapshot.memory.pressure = Some(MemoryPressure::default());
+        assert!(summarize(&snapshot).issues.is_empty());
+    }
+}
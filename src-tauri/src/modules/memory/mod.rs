@@ -2,11 +2,17 @@
 // Provides RAM and SWAP usage statistics
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use sysinfo::System;
+use std::fs;
+use std::path::Path;
 use std::sync::RwLock;
 
+use crate::modules::LockRecover;
+
 /// Memory statistics in bytes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct MemoryInfo {
     pub total_memory: u64,
     pub used_memory: u64,
@@ -15,8 +21,46 @@ pub struct MemoryInfo {
     pub used_swap: u64,
     pub memory_usage_percent: f32,
     pub swap_usage_percent: f32,
+    pub swap_devices: Vec<SwapDevice>,
+    pub committed_as: u64, // Memory promised to processes (Committed_AS)
+    pub commit_limit: u64, // CommitLimit; strict overcommit refuses allocations past it
+    pub overcommit_ratio: f32, // committed_as / commit_limit, above 1.0 risks OOM
+    pub top_consumers: Vec<(String, u64)>, // (process name, RSS bytes), largest first
+    pub pressure: Option<MemoryPressure>, // None without PSI (pre-4.20 kernels, psi=0)
 }
 
+/// Memory pressure stall information from /proc/pressure/memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryPressure {
+    pub some_avg10: f32, // % of the last 10 s in which at least one task stalled on memory
+    pub full_avg10: f32, // % of the last 10 s in which every runnable task stalled at once
+}
+
+/// A single swap area from /proc/swaps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapDevice {
+    pub name: String,
+    pub kind: String, // partition, file or zram
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    pub priority: i32,
+}
+
+/// A NUMA node with its CPUs and local memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<u32>, // Logical CPU numbers
+    pub total_memory: u64, // Bytes
+    pub free_memory: u64, // Bytes
+}
+
+// Number of top memory consumers reported by default (5)
+pub const DEFAULT_TOP_CONSUMERS: usize = 5;
+
 /// Memory Monitor state
 pub struct MemoryMonitor {
     system: RwLock<System>,
@@ -31,16 +75,28 @@ impl MemoryMonitor {
         }
     }
 
-    pub fn refresh(&self) -> MemoryInfo {
-        let mut sys = self.system.write()
-            .expect("Memory monitor RwLock poisoned - this is a fatal error");
+    /// Refresh memory stats, including the `top_count` processes with the largest RSS
+    pub fn refresh(&self, top_count: usize) -> MemoryInfo {
+        let mut sys = self.system.write_or_recover("Memory monitor");
         sys.refresh_memory();
         
         let total_memory = sys.total_memory();
         let used_memory = sys.used_memory();
         let available_memory = sys.available_memory();
-        let total_swap = sys.total_swap();
-        let used_swap = sys.used_swap();
+        let swap_devices = read_swap_devices();
+        let meminfo = read_meminfo();
+        let committed_as = meminfo.get("Committed_AS").copied().unwrap_or(0);
+        let commit_limit = meminfo.get("CommitLimit").copied().unwrap_or(0);
+
+        // Keep the aggregate consistent with the per-device breakdown when it's available
+        let (total_swap, used_swap) = if swap_devices.is_empty() {
+            (sys.total_swap(), sys.used_swap())
+        } else {
+            (
+                swap_devices.iter().map(|d| d.size_bytes).sum(),
+                swap_devices.iter().map(|d| d.used_bytes).sum(),
+            )
+        };
 
         let memory_usage_percent = if total_memory > 0 {
             (used_memory as f32 / total_memory as f32) * 100.0
@@ -62,12 +118,308 @@ impl MemoryMonitor {
             used_swap,
             memory_usage_percent,
             swap_usage_percent,
+            swap_devices,
+            committed_as,
+            commit_limit,
+            overcommit_ratio: overcommit_ratio(committed_as, commit_limit),
+            top_consumers: top_consumers(top_count),
+            pressure: read_pressure(),
         }
     }
 }
 
+/// NUMA nodes from /sys/devices/system/node, sorted by id
+/// Kernels without NUMA support have no node directory; they get a single node 0 covering
+/// all online CPUs and system memory
+pub fn get_numa_info() -> Vec<NumaNode> {
+    let node_root = Path::new("/sys/devices/system/node");
+    let mut nodes: Vec<NumaNode> = fs::read_dir(node_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let id = name.strip_prefix("node")?.parse().ok()?;
+                    let dir = entry.path();
+                    let meminfo = fs::read_to_string(dir.join("meminfo"))
+                        .map(|contents| parse_node_meminfo(&contents))
+                        .unwrap_or_default();
+                    Some(NumaNode {
+                        id,
+                        cpus: fs::read_to_string(dir.join("cpulist"))
+                            .map(|list| parse_cpu_list(&list))
+                            .unwrap_or_default(),
+                        total_memory: meminfo.get("MemTotal").copied().unwrap_or(0),
+                        free_memory: meminfo.get("MemFree").copied().unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if nodes.is_empty() {
+        let meminfo = read_meminfo();
+        nodes.push(NumaNode {
+            id: 0,
+            cpus: fs::read_to_string("/sys/devices/system/cpu/online")
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_default(),
+            total_memory: meminfo.get("MemTotal").copied().unwrap_or(0),
+            free_memory: meminfo.get("MemFree").copied().unwrap_or(0),
+        });
+    }
+
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+/// Expand a kernel cpulist ("0-3,8-11", "5", or empty for memory-only nodes) into CPU numbers
+fn parse_cpu_list(list: &str) -> Vec<u32> {
+    list.trim()
+        .split(',')
+        .filter(|range| !range.is_empty())
+        .filter_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            Some(start.parse::<u32>().ok()?..=end.parse().ok()?)
+        })
+        .flatten()
+        .collect()
+}
+
+/// Parse a node's meminfo, whose lines carry a "Node N " prefix ("Node 0 MemFree: 812 kB")
+fn parse_node_meminfo(contents: &str) -> HashMap<String, u64> {
+    let stripped: Vec<&str> = contents
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("Node ")?.split_once(' '))
+        .map(|(_, rest)| rest)
+        .collect();
+    parse_meminfo(&stripped.join("\n"))
+}
+
+/// Largest processes by RSS from a minimal /proc/<pid>/statm scan
+/// Kept separate from the process module, which refreshes far more per process
+fn top_consumers(count: usize) -> Vec<(String, u64)> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(0) as u64;
+
+    let mut consumers: Vec<(String, u64)> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()))
+        .filter_map(|e| {
+            let dir = e.path();
+            // Processes can exit mid-scan; those just drop out
+            let rss_pages = parse_statm_rss(&fs::read_to_string(dir.join("statm")).ok()?)?;
+            let name = fs::read_to_string(dir.join("comm")).ok()?.trim().to_string();
+            Some((name, rss_pages * page_size))
+        })
+        .filter(|(_, rss)| *rss > 0)
+        .collect();
+
+    consumers.sort_by_key(|(_, rss)| std::cmp::Reverse(*rss));
+    consumers.truncate(count);
+    consumers
+}
+
+/// Resident pages from statm: "size resident shared text lib data dt"
+fn parse_statm_rss(statm: &str) -> Option<u64> {
+    statm.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// committed / limit, or 0 when the limit is unknown
+fn overcommit_ratio(committed: u64, limit: u64) -> f32 {
+    if limit == 0 {
+        return 0.0;
+    }
+    (committed as f64 / limit as f64) as f32
+}
+
+/// All /proc/meminfo fields in bytes, read in one pass; empty if unavailable
+fn read_meminfo() -> HashMap<String, u64> {
+    fs::read_to_string("/proc/meminfo")
+        .map(|contents| parse_meminfo(&contents))
+        .unwrap_or_default()
+}
+
+/// Parse "Key:   value kB" lines; values without a unit (HugePages_*) are counts
+fn parse_meminfo(contents: &str) -> HashMap<String, u64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// Memory PSI; None when the kernel doesn't provide it
+fn read_pressure() -> Option<MemoryPressure> {
+    parse_pressure(&fs::read_to_string("/proc/pressure/memory").ok()?)
+}
+
+/// Parse the avg10 columns of "some avg10=1.52 avg60=0.80 avg300=0.21 total=12345" and
+/// the matching "full ..." line
+fn parse_pressure(contents: &str) -> Option<MemoryPressure> {
+    let avg10 = |kind: &str| -> Option<f32> {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(kind)?.strip_prefix(' '))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    };
+    Some(MemoryPressure {
+        some_avg10: avg10("some")?,
+        full_avg10: avg10("full")?,
+    })
+}
+
+/// Swap areas from /proc/swaps; empty if it can't be read (non-Linux or no swap)
+fn read_swap_devices() -> Vec<SwapDevice> {
+    fs::read_to_string("/proc/swaps")
+        .map(|contents| parse_swaps(&contents))
+        .unwrap_or_default()
+}
+
+/// Parse /proc/swaps: a header line, then "Filename Type Size Used Priority" rows
+/// Size and Used are in KiB; zram devices report as partitions, so they're told apart by name
+fn parse_swaps(contents: &str) -> Vec<SwapDevice> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, kind, size, used, priority] = fields[..] else {
+                return None;
+            };
+            let kind = if name.starts_with("/dev/zram") { "zram" } else { kind };
+            Some(SwapDevice {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                size_bytes: size.parse::<u64>().ok()? * 1024,
+                used_bytes: used.parse::<u64>().ok()? * 1024,
+                priority: priority.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
 impl Default for MemoryMonitor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_swaps_with_zram_and_files() {
+        let swaps = parse_swaps(
+            "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n\
+             /dev/nvme0n1p3                          partition\t8388604\t\t1024\t\t-2\n\
+             /swapfile                               file\t\t2097148\t\t0\t\t-3\n\
+             /dev/zram0                              partition\t4194300\t\t524288\t\t100\n\
+             garbage line\n",
+        );
+        assert_eq!(swaps.len(), 3);
+        assert_eq!(swaps[0].name, "/dev/nvme0n1p3");
+        assert_eq!(swaps[0].kind, "partition");
+        assert_eq!(swaps[0].size_bytes, 8388604 * 1024);
+        assert_eq!(swaps[0].used_bytes, 1024 * 1024);
+        assert_eq!(swaps[0].priority, -2);
+        assert_eq!(swaps[1].kind, "file");
+        assert_eq!(swaps[1].used_bytes, 0);
+        assert_eq!(swaps[2].kind, "zram");
+        assert_eq!(swaps[2].priority, 100);
+
+        // Header only: swap is off
+        assert!(parse_swaps("Filename Type Size Used Priority\n").is_empty());
+    }
+
+    #[test]
+    fn computes_overcommit_from_meminfo() {
+        let meminfo = parse_meminfo(
+            "MemTotal:       16303540 kB\n\
+             CommitLimit:    10000000 kB\n\
+             Committed_AS:   15000000 kB\n\
+             HugePages_Total:       4\n",
+        );
+        let committed = meminfo["Committed_AS"];
+        let limit = meminfo["CommitLimit"];
+        assert_eq!(committed, 15000000 * 1024);
+        assert_eq!(limit, 10000000 * 1024);
+        assert_eq!(meminfo["HugePages_Total"], 4);
+        assert_eq!(overcommit_ratio(committed, limit), 1.5);
+
+        // An unknown limit reports no ratio rather than dividing by zero
+        assert_eq!(overcommit_ratio(committed, 0), 0.0);
+        assert_eq!(overcommit_ratio(0, limit), 0.0);
+    }
+
+    #[test]
+    fn parses_statm_resident_pages() {
+        assert_eq!(parse_statm_rss("1234 567 89 10 0 300 0\n"), Some(567));
+        assert_eq!(parse_statm_rss("1234"), None);
+        assert_eq!(parse_statm_rss(""), None);
+    }
+
+    #[test]
+    fn top_consumers_are_limited_and_sorted() {
+        assert!(top_consumers(0).is_empty());
+
+        let consumers = top_consumers(3);
+        assert!(consumers.len() <= 3);
+        assert!(consumers.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(consumers.iter().all(|(_, rss)| *rss > 0));
+    }
+
+    #[test]
+    fn expands_cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8-11\n"), [0, 1, 2, 3, 8, 9, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), [5]);
+        assert_eq!(parse_cpu_list("0,2,4-5"), [0, 2, 4, 5]);
+        // Memory-only (CXL, HBM) nodes have no CPUs
+        assert!(parse_cpu_list("\n").is_empty());
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn parses_node_meminfo() {
+        let meminfo = parse_node_meminfo(
+            "Node 0 MemTotal:       32768000 kB\n\
+             Node 0 MemFree:          812000 kB\n\
+             Node 0 HugePages_Total:      0\n",
+        );
+        assert_eq!(meminfo["MemTotal"], 32768000 * 1024);
+        assert_eq!(meminfo["MemFree"], 812000 * 1024);
+        assert_eq!(meminfo["HugePages_Total"], 0);
+
+        let meminfo = parse_node_meminfo("Node 12 MemFree: 4 kB\n");
+        assert_eq!(meminfo["MemFree"], 4096);
+    }
+
+    #[test]
+    fn parses_pressure_avg10() {
+        let psi = "some avg10=12.50 avg60=3.10 avg300=0.80 total=123456\n\
+                   full avg10=4.25 avg60=1.00 avg300=0.20 total=65432\n";
+        assert_eq!(
+            parse_pressure(psi),
+            Some(MemoryPressure { some_avg10: 12.5, full_avg10: 4.25 })
+        );
+        assert_eq!(parse_pressure("some avg10=1.00 avg60=0.00 avg300=0.00 total=1\n"), None);
+        assert_eq!(parse_pressure(""), None);
+    }
+}
@@ -1,16 +1,26 @@
 // Nova System Monitor - Modules
 // Core system monitoring functionality
 
+pub mod alerts;
+pub mod battery;
 pub mod cpu;
 pub mod disk;
+pub mod export;
+pub mod format;
 pub mod gpu;
+pub mod health;
 pub mod memory;
 pub mod network;
 pub mod process;
 pub mod sensors;
+pub mod snapshot;
 pub mod system;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::process::{Command, Output};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use thiserror::Error;
 
 #[derive(Error, Debug, Serialize)]
@@ -26,6 +36,21 @@ pub enum MonitorError {
 
     #[error("Process not found: {0}")]
     ProcessNotFound(u32),
+
+    #[error("Process {0} is not using a GPU")]
+    NotGpuProcess(u32),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Device is busy: {0}")]
+    DeviceBusy(String),
+
+    #[error("Not supported on this system: {0}")]
+    Unsupported(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
 }
 
 impl From<MonitorError> for String {
@@ -34,4 +59,354 @@ impl From<MonitorError> for String {
     }
 }
 
+impl From<io::Error> for MonitorError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => MonitorError::PermissionDenied(err.to_string()),
+            io::ErrorKind::Unsupported => MonitorError::Unsupported(err.to_string()),
+            io::ErrorKind::ResourceBusy => MonitorError::DeviceBusy(err.to_string()),
+            _ => MonitorError::Io(err.to_string()),
+        }
+    }
+}
+
+/// Temperature unit for emitted values; monitors always measure in Celsius
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Convert a Celsius reading into this unit
+    pub fn convert(self, celsius: f32) -> f32 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// Convert a whole-degree Celsius reading, rounding to the nearest degree
+    pub fn convert_whole(self, celsius: u32) -> u32 {
+        self.convert(celsius as f32).round().max(0.0) as u32
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "\u{00B0}C",
+            TemperatureUnit::Fahrenheit => "\u{00B0}F",
+        }
+    }
+}
+
+/// Lock access that recovers from poisoning instead of panicking
+/// A panic while a lock is held (e.g. a parsing bug in one refresh) would otherwise poison
+/// it and make every later call panic too. The locked data is caches and samples that the
+/// next refresh overwrites, so carrying on with it is safe; the poison flag is cleared so
+/// the recovery is only reported once.
+pub trait LockRecover<T> {
+    fn read_or_recover(&self, name: &str) -> RwLockReadGuard<'_, T>;
+    fn write_or_recover(&self, name: &str) -> RwLockWriteGuard<'_, T>;
+}
+
+impl<T> LockRecover<T> for RwLock<T> {
+    fn read_or_recover(&self, name: &str) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(|poisoned| {
+            report_poisoned(name);
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    fn write_or_recover(&self, name: &str) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(|poisoned| {
+            report_poisoned(name);
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+/// Mutex counterpart of LockRecover
+pub trait MutexRecover<T> {
+    fn lock_or_recover(&self, name: &str) -> MutexGuard<'_, T>;
+}
+
+impl<T> MutexRecover<T> for Mutex<T> {
+    fn lock_or_recover(&self, name: &str) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            report_poisoned(name);
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+fn report_poisoned(name: &str) {
+    eprintln!("{} lock was poisoned by a panic; recovering with its last state", name);
+}
+
+/// Counter pair (e.g. rx/tx bytes) accumulated since the first refresh of `key`
+/// The first call records the baseline; a counter that went backwards (driver reload,
+/// device re-plug) resets it so the result never underflows
+pub fn since_start(
+    baselines: &mut HashMap<String, (u64, u64)>,
+    key: &str,
+    current: (u64, u64),
+) -> (u64, u64) {
+    let baseline = baselines.entry(key.to_string()).or_insert(current);
+    if current.0 < baseline.0 || current.1 < baseline.1 {
+        *baseline = current;
+    }
+    (current.0 - baseline.0, current.1 - baseline.1)
+}
+
+/// Check whether an external program is available on PATH
+/// Used to avoid spawning optional tools (smartctl, rocm-smi, ...) that aren't installed
+pub fn command_exists(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Runs external programs; lets monitors that parse tool output (smartctl, ...)
+/// be driven by canned output instead of the real binaries
+pub trait CommandRunner: Send + Sync {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output>;
+}
+
+/// CommandRunner that spawns the real program
+pub struct SystemCommandRunner;
+
+// Don't flash a console window when spawning tools from the GUI process
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        let mut command = Command::new(program);
+        command.args(args);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        command.output()
+    }
+}
+
+/// CommandRunner for tests: answers with canned output and records every command line
+/// Clones share their responses and calls, so a test can keep one after boxing another
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct FakeCommandRunner {
+    responses: std::sync::Arc<Mutex<Vec<FakeResponse>>>,
+    calls: std::sync::Arc<Mutex<Vec<String>>>,
+}
+
+#[cfg(test)]
+struct FakeResponse {
+    prefix: String,
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+#[cfg(test)]
+impl FakeCommandRunner {
+    /// Answer command lines starting with `prefix` (e.g. "smartctl -H"); later responses
+    /// win over earlier ones, and unmatched commands fail as if not installed
+    pub fn respond(&self, prefix: &str, exit_code: i32, stdout: &str) {
+        self.respond_with_stderr(prefix, exit_code, stdout, "");
+    }
+
+    /// Like respond, for tools that report errors on stderr
+    pub fn respond_with_stderr(&self, prefix: &str, exit_code: i32, stdout: &str, stderr: &str) {
+        self.responses.lock().unwrap().push(FakeResponse {
+            prefix: prefix.to_string(),
+            exit_code,
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+        });
+    }
+
+    /// Command lines run so far, oldest first
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for FakeCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        use std::os::unix::process::ExitStatusExt;
+
+        let command = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.calls.lock().unwrap().push(command.clone());
+
+        let responses = self.responses.lock().unwrap();
+        let response = responses
+            .iter()
+            .rev()
+            .find(|response| command.starts_with(response.prefix.as_str()))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, program.to_string()))?;
+        Ok(Output {
+            status: std::process::ExitStatus::from_raw(response.exit_code << 8),
+            stdout: response.stdout.clone().into_bytes(),
+            stderr: response.stderr.clone().into_bytes(),
+        })
+    }
+}
+
+/// Scratch directory for filesystem fixtures (fake sysfs, /proc, ...)
+/// Each one is unique, so parallel tests never share a directory, and it is removed on drop,
+/// including when the test panics
+#[cfg(test)]
+pub struct ScratchDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl ScratchDir {
+    pub fn new(name: &str) -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "nova-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Write `contents` to a path relative to the directory, creating parent directories
+    pub fn write(&self, relative: &str, contents: &str) -> std::path::PathBuf {
+        let path = self.0.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for ScratchDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Poison `lock` the way a refresh bug would: panic on another thread while holding it
+#[cfg(test)]
+pub fn poison_lock<T: Send + Sync>(lock: &RwLock<T>) {
+    std::thread::scope(|s| {
+        let panicked = s
+            .spawn(|| {
+                let _guard = lock.write().unwrap();
+                panic!("simulated parsing bug");
+            })
+            .join();
+        assert!(panicked.is_err());
+    });
+    assert!(lock.is_poisoned());
+}
+
 // Common traits or structs can go here if needed across modules
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_display_and_convert_from_io() {
+        let io_error = |kind, message| MonitorError::from(io::Error::new(kind, message));
+
+        let err = io_error(io::ErrorKind::InvalidData, "bad sysfs value");
+        assert!(matches!(err, MonitorError::Io(_)));
+        assert_eq!(String::from(err), "I/O error: bad sysfs value");
+
+        let err = io_error(io::ErrorKind::Unsupported, "no battery");
+        assert!(matches!(err, MonitorError::Unsupported(_)));
+        assert_eq!(err.to_string(), "Not supported on this system: no battery");
+
+        let err = io_error(io::ErrorKind::PermissionDenied, "/dev/nvme0");
+        assert!(matches!(err, MonitorError::PermissionDenied(_)));
+
+        let err = io_error(io::ErrorKind::ResourceBusy, "/media/usb");
+        assert!(matches!(err, MonitorError::DeviceBusy(_)));
+        assert_eq!(err.to_string(), "Device is busy: /media/usb");
+    }
+
+    #[test]
+    fn converts_celsius_readings() {
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(0.0), 32.0);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(100.0), 212.0);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-40.0), -40.0);
+        assert_eq!(TemperatureUnit::Celsius.convert(21.5), 21.5);
+
+        // 37 C is 98.6 F and 38 C is 100.4 F: whole degrees round to the nearest
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_whole(0), 32);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_whole(100), 212);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_whole(37), 99);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_whole(38), 100);
+        assert_eq!(TemperatureUnit::Celsius.convert_whole(65), 65);
+
+        assert_eq!(TemperatureUnit::Celsius.symbol(), "\u{00B0}C");
+        assert_eq!(TemperatureUnit::Fahrenheit.symbol(), "\u{00B0}F");
+    }
+
+    #[test]
+    fn since_start_counts_from_the_first_sample_and_survives_resets() {
+        let mut baselines = HashMap::new();
+        assert_eq!(since_start(&mut baselines, "eth0", (5_000, 2_000)), (0, 0));
+        assert_eq!(since_start(&mut baselines, "eth0", (5_600, 2_100)), (600, 100));
+        // Keys keep separate baselines
+        assert_eq!(since_start(&mut baselines, "sda", (10, 20)), (0, 0));
+        assert_eq!(since_start(&mut baselines, "eth0", (6_000, 2_500)), (1_000, 500));
+
+        // A driver reload zeroes the counters: start over instead of underflowing
+        assert_eq!(since_start(&mut baselines, "eth0", (300, 2_600)), (0, 0));
+        assert_eq!(since_start(&mut baselines, "eth0", (450, 2_700)), (150, 100));
+        assert_eq!(baselines["eth0"], (300, 2_600));
+    }
+
+    #[test]
+    fn poisoned_locks_recover_their_last_state() {
+        let samples = RwLock::new(vec![1, 2, 3]);
+        poison_lock(&samples);
+        assert_eq!(*samples.read_or_recover("Samples"), [1, 2, 3]);
+        // The flag is cleared, so plain access works again too
+        assert!(!samples.is_poisoned());
+        samples.write_or_recover("Samples").push(4);
+        assert_eq!(*samples.read().unwrap(), [1, 2, 3, 4]);
+
+        let unit = Mutex::new(TemperatureUnit::Fahrenheit);
+        std::thread::scope(|s| {
+            let _ = s
+                .spawn(|| {
+                    let _guard = unit.lock().unwrap();
+                    panic!("simulated parsing bug");
+                })
+                .join();
+        });
+        assert!(unit.is_poisoned());
+        assert_eq!(*unit.lock_or_recover("Unit"), TemperatureUnit::Fahrenheit);
+        assert!(!unit.is_poisoned());
+    }
+}
@@ -3,9 +3,15 @@
 
 use serde::{Deserialize, Serialize};
 use sysinfo::Networks;
+use std::fs;
+use std::ffi::CStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
 use std::sync::RwLock;
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::modules::{since_start, LockRecover};
 
 /// Network rate sample for calculating speed
 #[derive(Debug, Clone)]
@@ -13,46 +19,264 @@ struct NetworkSample {
     timestamp: u64,  // Milliseconds
     received: u64,
     transmitted: u64,
+    errors_in: u64,
+    dropped_in: u64,
+}
+
+/// Kind of network interface, used by the UI to pick an icon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterfaceType {
+    Ethernet,
+    Wireless,
+    Loopback,
+    Virtual,
+    Bridge,
+    Tun,
+    #[default]
+    Unknown,
+}
+
+/// Rolling bandwidth history for one interface
+#[derive(Debug, Clone)]
+struct InterfaceHistory {
+    samples: VecDeque<(u64, f64, f64)>, // (timestamp ms, download bps, upload bps)
+    last_seen: u64,                     // Milliseconds
 }
 
 /// Information about a single network interface
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NetworkInterface {
     pub name: String,
+    pub interface_type: InterfaceType,
     pub mac_address: String,
     pub received_bytes: u64,
     pub transmitted_bytes: u64,
+    pub received_since_start: u64,    // Bytes since the app started watching the interface
+    pub transmitted_since_start: u64, // Bytes since the app started watching the interface
     pub received_packets: u64,
     pub transmitted_packets: u64,
     pub errors_in: u64,
     pub errors_out: u64,
+    pub dropped_in: u64,
+    pub dropped_out: u64,
+    pub errors_in_per_sec: f64,  // Receive errors per second since the last refresh
+    pub dropped_in_per_sec: f64, // Dropped inbound packets per second since the last refresh
+    pub ipv4_addresses: Vec<String>,
+    pub ipv6_addresses: Vec<String>,
+    pub link_speed_mbps: Option<u64>, // None when the link is down
+    pub is_up: bool,
+    pub mtu: u32,
+    pub duplex: Option<String>, // "full" or "half", wired links with carrier only
+    pub carrier: bool,
     // Real-time rates
     pub download_rate_bps: f64,  // Bytes per second
     pub upload_rate_bps: f64,    // Bytes per second
 }
 
 /// Overall network statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NetworkInfo {
     pub interfaces: Vec<NetworkInterface>,
     pub total_received: u64,
     pub total_transmitted: u64,
+    pub total_received_since_start: u64,
+    pub total_transmitted_since_start: u64,
     pub total_download_rate: f64,  // Bytes per second
     pub total_upload_rate: f64,    // Bytes per second
+    pub hidden: Vec<String>,       // Interfaces hidden by the user, sorted
+}
+
+/// Resolver and default route configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConfig {
+    pub dns_servers: Vec<String>,
+    pub default_gateway_v4: Option<String>,
+    pub default_gateway_v6: Option<String>,
+}
+
+/// Socket counts per TCP state plus TCP/UDP totals, IPv4 and IPv6 combined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketSummary {
+    pub tcp_states: BTreeMap<String, u32>, // e.g. "ESTABLISHED" -> 12
+    pub tcp_total: u32,
+    pub udp_total: u32,
+}
+
+/// Cumulative traffic of one protocol, system-wide, with rates since the previous call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolCounter {
+    pub protocol: String, // IPv4, IPv6, TCP, UDP, UDPv6, ICMP, ICMPv6
+    pub in_packets: u64, // Segments for TCP, datagrams for UDP, messages for ICMP
+    pub out_packets: u64,
+    pub in_bytes: Option<u64>, // Only tracked at the IP level
+    pub out_bytes: Option<u64>,
+    pub in_packets_per_sec: f64,
+    pub out_packets_per_sec: f64,
+}
+
+/// An open TCP/UDP socket (like netstat/ss)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection {
+    pub protocol: String,
+    pub local_addr: String,
+    pub local_port: u16,
+    pub remote_addr: String,
+    pub remote_port: u16,
+    pub state: String,
+    pub inode: u64,
+    pub pid: Option<u32>,
+}
+
+/// Which interfaces to include in the list and totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkQuery {
+    pub include_loopback: bool,
+    pub only_up: bool,
+}
+
+impl Default for NetworkQuery {
+    fn default() -> Self {
+        Self {
+            include_loopback: false,
+            only_up: true,
+        }
+    }
+}
+
+impl NetworkQuery {
+    /// Whether an interface of this type and link state is listed and counted in totals
+    fn includes(&self, interface_type: InterfaceType, is_up: bool) -> bool {
+        (self.include_loopback || interface_type != InterfaceType::Loopback)
+            && (is_up || !self.only_up)
+    }
+}
+
+impl NetworkInfo {
+    /// Wrap the listed interfaces, summing their counters and rates into the totals
+    fn from_interfaces(interfaces: Vec<NetworkInterface>, hidden: Vec<String>) -> Self {
+        Self {
+            total_received: interfaces.iter().map(|i| i.received_bytes).sum(),
+            total_transmitted: interfaces.iter().map(|i| i.transmitted_bytes).sum(),
+            total_received_since_start: interfaces.iter().map(|i| i.received_since_start).sum(),
+            total_transmitted_since_start: interfaces
+                .iter()
+                .map(|i| i.transmitted_since_start)
+                .sum(),
+            total_download_rate: interfaces.iter().map(|i| i.download_rate_bps).sum(),
+            total_upload_rate: interfaces.iter().map(|i| i.upload_rate_bps).sum(),
+            interfaces,
+            hidden,
+        }
+    }
 }
 
 /// Network Monitor state
 pub struct NetworkMonitor {
     networks: RwLock<Networks>,
     last_samples: RwLock<HashMap<String, NetworkSample>>,
+    baselines: RwLock<HashMap<String, (u64, u64)>>, // (received, transmitted) at first refresh
+    history: RwLock<HashMap<String, InterfaceHistory>>,
+    hidden: RwLock<HashSet<String>>, // Interface names the user chose to hide
+    protocol_sample: RwLock<Option<(u64, HashMap<String, u64>)>>, // (timestamp ms, SNMP counters)
+    history_len: usize,
+}
+
+/// SNMP counter keys making up one ProtocolCounter
+struct ProtocolKeys {
+    protocol: &'static str,
+    packets: (&'static str, &'static str),        // (in, out)
+    bytes: Option<(&'static str, &'static str)>, // (in, out)
 }
 
+// TCP is counted once for IPv4 and IPv6; UDP and ICMP have separate v6 counters
+const PROTOCOL_KEYS: [ProtocolKeys; 7] = [
+    ProtocolKeys {
+        protocol: "IPv4",
+        packets: ("Ip.InReceives", "Ip.OutRequests"),
+        bytes: Some(("IpExt.InOctets", "IpExt.OutOctets")),
+    },
+    ProtocolKeys {
+        protocol: "IPv6",
+        packets: ("Ip6InReceives", "Ip6OutRequests"),
+        bytes: Some(("Ip6InOctets", "Ip6OutOctets")),
+    },
+    ProtocolKeys { protocol: "TCP", packets: ("Tcp.InSegs", "Tcp.OutSegs"), bytes: None },
+    ProtocolKeys {
+        protocol: "UDP",
+        packets: ("Udp.InDatagrams", "Udp.OutDatagrams"),
+        bytes: None,
+    },
+    ProtocolKeys {
+        protocol: "UDPv6",
+        packets: ("Udp6InDatagrams", "Udp6OutDatagrams"),
+        bytes: None,
+    },
+    ProtocolKeys { protocol: "ICMP", packets: ("Icmp.InMsgs", "Icmp.OutMsgs"), bytes: None },
+    ProtocolKeys {
+        protocol: "ICMPv6",
+        packets: ("Icmp6InMsgs", "Icmp6OutMsgs"),
+        bytes: None,
+    },
+];
+
+// Default number of samples kept per interface
+const DEFAULT_HISTORY_LEN: usize = 60;
+
+// How long a vanished interface keeps its history before being dropped (5 minutes)
+const HISTORY_GRACE_PERIOD_MS: u64 = 5 * 60 * 1000;
+
 impl NetworkMonitor {
     pub fn new() -> Self {
+        Self::with_history_len(DEFAULT_HISTORY_LEN)
+    }
+
+    pub fn with_history_len(history_len: usize) -> Self {
         Self {
             networks: RwLock::new(Networks::new_with_refreshed_list()),
             last_samples: RwLock::new(HashMap::new()),
+            baselines: RwLock::new(HashMap::new()),
+            history: RwLock::new(HashMap::new()),
+            hidden: RwLock::new(HashSet::new()),
+            protocol_sample: RwLock::new(None),
+            history_len: history_len.max(1),
+        }
+    }
+
+    /// Bandwidth history per interface as (timestamp ms, download bps, upload bps)
+    pub fn history(&self) -> HashMap<String, Vec<(u64, f64, f64)>> {
+        let history = self.history.read_or_recover("Network history");
+        history
+            .iter()
+            .map(|(name, h)| (name.clone(), h.samples.iter().copied().collect()))
+            .collect()
+    }
+
+    /// Append one sample per interface and prune interfaces unseen past the grace period
+    fn record_history(&self, interfaces: &[NetworkInterface], timestamp: u64) {
+        let mut history = self.history.write_or_recover("Network history");
+
+        for iface in interfaces {
+            let entry = history
+                .entry(iface.name.clone())
+                .or_insert_with(|| InterfaceHistory {
+                    samples: VecDeque::with_capacity(self.history_len),
+                    last_seen: timestamp,
+                });
+            if entry.samples.len() >= self.history_len {
+                entry.samples.pop_front();
+            }
+            entry.samples.push_back((timestamp, iface.download_rate_bps, iface.upload_rate_bps));
+            entry.last_seen = timestamp;
         }
+
+        history.retain(|_, h| timestamp.saturating_sub(h.last_seen) <= HISTORY_GRACE_PERIOD_MS);
     }
 
     fn current_timestamp_ms() -> u64 {
@@ -62,80 +286,1108 @@ impl NetworkMonitor {
             .as_millis() as u64
     }
 
-    pub fn refresh(&self) -> NetworkInfo {
-        let mut networks_handle = self.networks.write()
-            .expect("Network monitor RwLock poisoned - fatal error");
+    /// Restart the since-start counters and rate sampling from the next refresh
+    /// Locks are taken in refresh's order, so a concurrent refresh sees all or nothing
+    pub fn reset_counters(&self) {
+        let mut last_samples = self.last_samples.write_or_recover("Network samples");
+        let mut baselines = self.baselines.write_or_recover("Network baselines");
+        last_samples.clear();
+        baselines.clear();
+    }
+
+    /// Replace the set of hidden interfaces; they're left out of the list and totals
+    pub fn set_hidden(&self, names: Vec<String>) {
+        *self.hidden.write_or_recover("Network hidden") = names.into_iter().collect();
+    }
+
+    pub fn refresh(&self, query: &NetworkQuery) -> NetworkInfo {
+        let mut networks_handle = self.networks.write_or_recover("Network monitor");
         networks_handle.refresh();
         
-        let mut last_samples = self.last_samples.write()
-            .expect("Network samples RwLock poisoned - fatal error");
+        let mut last_samples = self.last_samples.write_or_recover("Network samples");
         
+        let mut baselines = self.baselines.write_or_recover("Network baselines");
+
+        let hidden = self.hidden.read_or_recover("Network hidden");
+
         let current_time = Self::current_timestamp_ms();
         
         let mut interfaces: Vec<NetworkInterface> = Vec::new();
-        let mut total_received: u64 = 0;
-        let mut total_transmitted: u64 = 0;
-        let mut total_download_rate: f64 = 0.0;
-        let mut total_upload_rate: f64 = 0.0;
+
+        let mut addresses = get_interface_addresses();
+        let sys_class_net = Path::new(SYS_CLASS_NET);
 
         for (name, network) in networks_handle.iter() {
+            let interface_type = classify_interface_in(sys_class_net, name);
+            let (is_up, link_speed_mbps) = read_link_state_in(sys_class_net, name);
+
+            // Excluded interfaces are left out of the list, the totals, and the sample map
+            if !query.includes(interface_type, is_up) || hidden.contains(name) {
+                last_samples.remove(name);
+                continue;
+            }
+
             let received = network.total_received();
             let transmitted = network.total_transmitted();
+            let (received_since_start, transmitted_since_start) =
+                since_start(&mut baselines, name, (received, transmitted));
             
+            let errors_in = network.total_errors_on_received();
+            let link = read_link_details_in(sys_class_net, name, interface_type);
+
             // Calculate rates
-            let (download_rate, upload_rate) = if let Some(last_sample) = last_samples.get(name) {
-                let time_delta = (current_time - last_sample.timestamp) as f64 / 1000.0; // Convert to seconds
-                
-                if time_delta > 0.0 {
-                    let rx_delta = received.saturating_sub(last_sample.received) as f64;
-                    let tx_delta = transmitted.saturating_sub(last_sample.transmitted) as f64;
-                    
-                    (rx_delta / time_delta, tx_delta / time_delta)
+            let (download_rate, upload_rate, errors_in_per_sec, dropped_in_per_sec) =
+                if let Some(last_sample) = last_samples.get(name) {
+                    let time_delta = (current_time - last_sample.timestamp) as f64 / 1000.0; // Convert to seconds
+                    (
+                        per_second(last_sample.received, received, time_delta),
+                        per_second(last_sample.transmitted, transmitted, time_delta),
+                        per_second(last_sample.errors_in, errors_in, time_delta),
+                        per_second(last_sample.dropped_in, link.dropped_in, time_delta),
+                    )
                 } else {
-                    (0.0, 0.0)
-                }
-            } else {
-                (0.0, 0.0)
-            };
+                    (0.0, 0.0, 0.0, 0.0)
+                };
             
             // Update sample
             last_samples.insert(name.clone(), NetworkSample {
                 timestamp: current_time,
                 received,
                 transmitted,
+                errors_in,
+                dropped_in: link.dropped_in,
             });
             
+            let (ipv4_addresses, ipv6_addresses) = addresses.remove(name).unwrap_or_default();
+
             interfaces.push(NetworkInterface {
                 name: name.clone(),
+                interface_type,
                 mac_address: network.mac_address().to_string(),
                 received_bytes: received,
                 transmitted_bytes: transmitted,
+                received_since_start,
+                transmitted_since_start,
                 received_packets: network.total_packets_received(),
                 transmitted_packets: network.total_packets_transmitted(),
-                errors_in: network.total_errors_on_received(),
+                errors_in,
                 errors_out: network.total_errors_on_transmitted(),
+                dropped_in: link.dropped_in,
+                dropped_out: link.dropped_out,
+                errors_in_per_sec,
+                dropped_in_per_sec,
+                ipv4_addresses,
+                ipv6_addresses,
+                link_speed_mbps,
+                is_up,
+                mtu: link.mtu,
+                duplex: link.duplex,
+                carrier: link.carrier,
                 download_rate_bps: download_rate,
                 upload_rate_bps: upload_rate,
             });
+        }
+
+        let mut hidden: Vec<String> = hidden.iter().cloned().collect();
+        hidden.sort();
+
+        drop(last_samples);
+        drop(baselines);
+        drop(networks_handle);
+        self.record_history(&interfaces, current_time);
+
+        NetworkInfo::from_interfaces(interfaces, hidden)
+    }
+}
 
-            total_received += received;
-            total_transmitted += transmitted;
-            total_download_rate += download_rate;
-            total_upload_rate += upload_rate;
+impl NetworkMonitor {
+    /// List open TCP/UDP sockets from /proc/net/{tcp,tcp6,udp,udp6}
+    pub fn connections(&self) -> Vec<Connection> {
+        let inode_to_pid = socket_inode_owners();
+        let mut connections = Vec::new();
+
+        for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+            let Ok(content) = fs::read_to_string(format!("/proc/net/{}", protocol)) else {
+                continue;
+            };
+
+            // First line is the column header
+            for line in content.lines().skip(1) {
+                if let Some(mut conn) = parse_proc_net_line(protocol, line) {
+                    conn.pid = inode_to_pid.get(&conn.inode).copied();
+                    connections.push(conn);
+                }
+            }
         }
 
-        NetworkInfo {
-            interfaces,
-            total_received,
-            total_transmitted,
-            total_download_rate,
-            total_upload_rate,
+        connections
+    }
+
+    /// Packet counts per protocol from /proc/net/{snmp,snmp6,netstat}, with per-second
+    /// rates against the previous call (0 on the first one)
+    pub fn protocol_stats(&self) -> Vec<ProtocolCounter> {
+        let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+        let mut counters = parse_snmp(&read("/proc/net/snmp"));
+        counters.extend(parse_snmp(&read("/proc/net/netstat")));
+        counters.extend(parse_snmp6(&read("/proc/net/snmp6")));
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut sample = self
+            .protocol_sample
+            .write_or_recover("Protocol sample");
+        let previous = sample.take();
+
+        let stats = PROTOCOL_KEYS
+            .iter()
+            .filter_map(|keys| {
+                let (packets_in, packets_out) = keys.packets;
+                let in_packets = *counters.get(packets_in)?;
+                let out_packets = counters.get(packets_out).copied().unwrap_or(0);
+                let rate = |key: &str, current: u64| match &previous {
+                    Some((timestamp, last)) => per_second(
+                        last.get(key).copied().unwrap_or(current),
+                        current,
+                        now.saturating_sub(*timestamp) as f64 / 1000.0,
+                    ),
+                    None => 0.0,
+                };
+                Some(ProtocolCounter {
+                    protocol: keys.protocol.to_string(),
+                    in_packets,
+                    out_packets,
+                    in_bytes: keys.bytes.and_then(|(key, _)| counters.get(key).copied()),
+                    out_bytes: keys.bytes.and_then(|(_, key)| counters.get(key).copied()),
+                    in_packets_per_sec: rate(packets_in, in_packets),
+                    out_packets_per_sec: rate(packets_out, out_packets),
+                })
+            })
+            .collect();
+
+        *sample = Some((now, counters));
+        stats
+    }
+
+    /// Count sockets by TCP state without resolving owners; cheap enough to poll
+    pub fn socket_summary(&self) -> SocketSummary {
+        let mut summary = SocketSummary {
+            tcp_states: BTreeMap::new(),
+            tcp_total: 0,
+            udp_total: 0,
+        };
+
+        for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+            if let Ok(content) = fs::read_to_string(format!("/proc/net/{}", protocol)) {
+                summary.add_socket_table(protocol, &content);
+            }
+        }
+
+        summary
+    }
+}
+
+impl SocketSummary {
+    /// Count the sockets of one /proc/net/{tcp,tcp6,udp,udp6} table
+    fn add_socket_table(&mut self, protocol: &str, content: &str) {
+        // First line is the column header; field 4 is the hex state
+        for line in content.lines().skip(1) {
+            let Some(state) = line
+                .split_whitespace()
+                .nth(3)
+                .and_then(|s| u8::from_str_radix(s, 16).ok())
+            else {
+                continue;
+            };
+
+            if protocol.starts_with("tcp") {
+                self.tcp_total += 1;
+                *self
+                    .tcp_states
+                    .entry(tcp_state_name(state).to_string())
+                    .or_insert(0) += 1;
+            } else {
+                self.udp_total += 1;
+            }
+        }
+    }
+}
+
+/// Collect IPv4 and IPv6 addresses for every interface via getifaddrs
+/// Returns a map of interface name -> (ipv4 addresses, ipv6 addresses)
+pub fn get_interface_addresses() -> HashMap<String, (Vec<String>, Vec<String>)> {
+    let mut addresses: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+
+    unsafe {
+        let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return addresses;
+        }
+
+        let mut cursor = ifap;
+        while !cursor.is_null() {
+            let ifa = &*cursor;
+            cursor = ifa.ifa_next;
+
+            if ifa.ifa_addr.is_null() || ifa.ifa_name.is_null() {
+                continue;
+            }
+
+            let name = CStr::from_ptr(ifa.ifa_name).to_string_lossy().to_string();
+            match sockaddr_ip(ifa.ifa_addr) {
+                Some(IpAddr::V4(addr)) => {
+                    addresses.entry(name).or_default().0.push(addr.to_string())
+                }
+                Some(IpAddr::V6(addr)) => {
+                    addresses.entry(name).or_default().1.push(addr.to_string())
+                }
+                None => {}
+            }
+        }
+
+        libc::freeifaddrs(ifap);
+    }
+
+    addresses
+}
+
+/// IP address held by a sockaddr; None for other families (AF_PACKET link addresses)
+///
+/// # Safety
+/// `addr` must point to a valid sockaddr whose size matches its sa_family
+unsafe fn sockaddr_ip(addr: *const libc::sockaddr) -> Option<IpAddr> {
+    match (*addr).sa_family as i32 {
+        libc::AF_INET => {
+            let sin = &*(addr as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr))))
         }
+        libc::AF_INET6 => {
+            let sin6 = &*(addr as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+
+// ARPHRD_* values from /sys/class/net/<if>/type
+const ARPHRD_ETHER: u32 = 1;
+const ARPHRD_LOOPBACK: u32 = 772;
+const ARPHRD_NONE: u32 = 65534;
+
+/// Classify an interface from its name and sysfs attributes
+fn classify_interface_in(sys_class_net: &Path, name: &str) -> InterfaceType {
+    let sys_path = sys_class_net.join(name);
+    let arp_type = fs::read_to_string(sys_path.join("type"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+
+    classify_interface_attrs(
+        name,
+        arp_type,
+        sys_path.join("wireless").exists(),
+        sys_path.join("bridge").exists(),
+        sys_path.join("device").exists(),
+    )
+}
+
+/// Pure classification over the collected attributes
+/// `has_device` is true for interfaces backed by real hardware
+fn classify_interface_attrs(
+    name: &str,
+    arp_type: Option<u32>,
+    is_wireless: bool,
+    is_bridge: bool,
+    has_device: bool,
+) -> InterfaceType {
+    if arp_type == Some(ARPHRD_LOOPBACK) || name == "lo" {
+        return InterfaceType::Loopback;
+    }
+    if is_wireless || name.starts_with("wl") {
+        return InterfaceType::Wireless;
+    }
+    if is_bridge || name.starts_with("br-") || name.starts_with("virbr") {
+        return InterfaceType::Bridge;
+    }
+    if arp_type == Some(ARPHRD_NONE)
+        || ["tun", "tap", "wg"].iter().any(|p| name.starts_with(p))
+    {
+        return InterfaceType::Tun;
+    }
+    if ["docker", "veth", "vnet", "virbr", "cni", "flannel"]
+        .iter()
+        .any(|p| name.starts_with(p))
+    {
+        return InterfaceType::Virtual;
+    }
+    match arp_type {
+        Some(ARPHRD_ETHER) if has_device => InterfaceType::Ethernet,
+        Some(ARPHRD_ETHER) => InterfaceType::Virtual,
+        _ if name.starts_with("en") || name.starts_with("eth") => InterfaceType::Ethernet,
+        _ => InterfaceType::Unknown,
     }
 }
 
+/// Read operstate and link speed from /sys/class/net/<if>
+/// The sysfs speed value is meaningless while the link is down, so it's only reported when up
+fn read_link_state_in(sys_class_net: &Path, name: &str) -> (bool, Option<u64>) {
+    let sys_path = sys_class_net.join(name);
+
+    // Drivers without operstate support (tun, wireguard) report "unknown", so fall back to IFF_UP
+    let is_up = match fs::read_to_string(sys_path.join("operstate")) {
+        Ok(state) if state.trim() == "up" => true,
+        Ok(state) if state.trim() == "unknown" => fs::read_to_string(sys_path.join("flags"))
+            .ok()
+            .and_then(|f| u32::from_str_radix(f.trim().trim_start_matches("0x"), 16).ok())
+            .is_some_and(|flags| flags & libc::IFF_UP as u32 != 0),
+        _ => false,
+    };
+
+    let link_speed_mbps = if is_up {
+        fs::read_to_string(sys_path.join("speed"))
+            .ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .map(|speed| speed as u64)
+    } else {
+        None
+    };
+
+    (is_up, link_speed_mbps)
+}
+
+/// MTU, carrier, duplex and drop counters of an interface
+struct LinkDetails {
+    mtu: u32,
+    duplex: Option<String>,
+    carrier: bool,
+    dropped_in: u64,
+    dropped_out: u64,
+}
+
+/// Rate of a cumulative counter between two samples; 0 for a zero interval or a counter reset
+fn per_second(last: u64, current: u64, elapsed_secs: f64) -> f64 {
+    if elapsed_secs > 0.0 {
+        current.saturating_sub(last) as f64 / elapsed_secs
+    } else {
+        0.0
+    }
+}
+
+/// Parse /proc/net/snmp or /proc/net/netstat, where each protocol has a header line of
+/// field names followed by a value line ("Tcp: RtoAlgorithm ... InSegs" / "Tcp: 1 ... 42")
+/// Keys are "Proto.Field"; negative values (Tcp MaxConn = -1) are skipped
+fn parse_snmp(contents: &str) -> HashMap<String, u64> {
+    let mut counters = HashMap::new();
+    let lines: Vec<&str> = contents.lines().collect();
+    for pair in lines.chunks(2) {
+        let [header, values] = pair else {
+            continue;
+        };
+        let (Some((proto, names)), Some((value_proto, values))) =
+            (header.split_once(':'), values.split_once(':'))
+        else {
+            continue;
+        };
+        if proto != value_proto {
+            continue;
+        }
+        for (name, value) in names.split_whitespace().zip(values.split_whitespace()) {
+            if let Ok(value) = value.parse::<u64>() {
+                counters.insert(format!("{}.{}", proto, name), value);
+            }
+        }
+    }
+    counters
+}
+
+/// Parse /proc/net/snmp6, one "Ip6InReceives   1234" pair per line
+fn parse_snmp6(contents: &str) -> HashMap<String, u64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let value = parts.next()?.parse().ok()?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// Read mtu, carrier, duplex and drop counters from /sys/class/net/<if>
+/// carrier can't be read while the interface is down and duplex is only meaningful
+/// on a wired link with carrier, so both fall back to false/None
+fn read_link_details_in(
+    sys_class_net: &Path,
+    name: &str,
+    interface_type: InterfaceType,
+) -> LinkDetails {
+    let sys_path = sys_class_net.join(name);
+    let read = |file: &str| {
+        fs::read_to_string(sys_path.join(file))
+            .ok()
+            .map(|v| v.trim().to_string())
+    };
+
+    let mtu = read("mtu").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let carrier = read("carrier").is_some_and(|v| v == "1");
+    let duplex = if carrier && interface_type == InterfaceType::Ethernet {
+        read("duplex").filter(|d| d == "full" || d == "half")
+    } else {
+        None
+    };
+
+    let counter = |file: &str| read(file).and_then(|v| v.parse().ok()).unwrap_or(0);
+
+    LinkDetails {
+        mtu,
+        duplex,
+        carrier,
+        dropped_in: counter("statistics/rx_dropped"),
+        dropped_out: counter("statistics/tx_dropped"),
+    }
+}
+
+/// Parse one socket line from /proc/net/{tcp,tcp6,udp,udp6}
+fn parse_proc_net_line(protocol: &str, line: &str) -> Option<Connection> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 10 {
+        return None;
+    }
+
+    // Field 2: local address, Field 3: remote address, Field 4: state, Field 10: inode
+    let (local_addr, local_port) = decode_socket_addr(parts[1])?;
+    let (remote_addr, remote_port) = decode_socket_addr(parts[2])?;
+    let state = u8::from_str_radix(parts[3], 16).ok()?;
+    let inode = parts[9].parse::<u64>().ok()?;
+
+    Some(Connection {
+        protocol: protocol.to_string(),
+        local_addr,
+        local_port,
+        remote_addr,
+        remote_port,
+        state: tcp_state_name(state).to_string(),
+        inode,
+        pid: None,
+    })
+}
+
+/// Decode a hex "ADDR:PORT" pair as written by the kernel
+/// Addresses are printed as 32-bit words in host byte order, the port as a plain number
+fn decode_socket_addr(field: &str) -> Option<(String, u16)> {
+    let (addr_hex, port_hex) = field.split_once(':')?;
+    let port = u16::from_str_radix(port_hex, 16).ok()?;
+    // One word for IPv4, four for IPv6; anything else is a truncated line
+    if addr_hex.len() != 8 && addr_hex.len() != 32 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(16);
+    for chunk in addr_hex.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let addr = match bytes.len() {
+        4 => Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            Ipv6Addr::from(octets).to_string()
+        }
+        _ => return None,
+    };
+
+    Some((addr, port))
+}
+
+// systemd-resolved's local stub; the real upstream servers live in its own resolv.conf
+const RESOLVED_STUB: &str = "127.0.0.53";
+const RESOLVED_UPSTREAM_CONF: &str = "/run/systemd/resolve/resolv.conf";
+
+/// DNS servers and default gateways, for connectivity troubleshooting
+pub fn get_network_config() -> NetworkConfig {
+    let read_dns = |path: &str| {
+        fs::read_to_string(path)
+            .map(|contents| parse_resolv_conf(&contents))
+            .unwrap_or_default()
+    };
+    let mut dns_servers = read_dns("/etc/resolv.conf");
+    if dns_servers.iter().all(|s| s == RESOLVED_STUB) {
+        let upstream = read_dns(RESOLVED_UPSTREAM_CONF);
+        if !upstream.is_empty() {
+            dns_servers = upstream;
+        }
+    }
+
+    NetworkConfig {
+        dns_servers,
+        default_gateway_v4: fs::read_to_string("/proc/net/route")
+            .ok()
+            .and_then(|contents| parse_default_gateway_v4(&contents)),
+        default_gateway_v6: fs::read_to_string("/proc/net/ipv6_route")
+            .ok()
+            .and_then(|contents| parse_default_gateway_v6(&contents)),
+    }
+}
+
+/// "nameserver <addr>" entries from resolv.conf, in order; # and ; start comments
+fn parse_resolv_conf(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.split(['#', ';']).next().unwrap_or("").trim())
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            if fields.next()? != "nameserver" {
+                return None;
+            }
+            fields.next().map(str::to_string)
+        })
+        .collect()
+}
+
+/// Default IPv4 gateway from /proc/net/route, preferring the lowest metric
+/// Columns: Iface Destination Gateway Flags RefCnt Use Metric Mask ...; addresses are
+/// 32-bit hex words in host byte order, like the socket tables
+fn parse_default_gateway_v4(contents: &str) -> Option<String> {
+    // RTF_UP | RTF_GATEWAY
+    const FLAGS_UP_GATEWAY: u32 = 0x0003;
+
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let hex = |i: usize| u32::from_str_radix(fields.get(i)?, 16).ok();
+            let (destination, flags, metric, mask) = (hex(1)?, hex(3)?, fields.get(6)?, hex(7)?);
+            if destination != 0 || mask != 0 || flags & FLAGS_UP_GATEWAY != FLAGS_UP_GATEWAY {
+                return None;
+            }
+            let (gateway, _) = decode_socket_addr(&format!("{}:0", fields[2]))?;
+            Some((metric.parse::<u32>().unwrap_or(u32::MAX), gateway))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, gateway)| gateway)
+}
+
+/// Default IPv6 gateway from /proc/net/ipv6_route, preferring the lowest metric
+/// Columns: dest dest_len src src_len next_hop metric refcnt use flags iface; unlike the
+/// socket tables, addresses here are plain 32-digit hex in network byte order
+fn parse_default_gateway_v6(contents: &str) -> Option<String> {
+    let decode = |hex: &str| -> Option<Ipv6Addr> {
+        if hex.len() != 32 {
+            return None;
+        }
+        let mut octets = [0u8; 16];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+        }
+        Some(Ipv6Addr::from(octets))
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (destination, prefix_len) = (decode(fields.first()?)?, fields.get(1)?);
+            let next_hop = decode(fields.get(4)?)?;
+            let metric = u32::from_str_radix(fields.get(5)?, 16).unwrap_or(u32::MAX);
+            // Reject/unreachable default routes have no next hop
+            let is_default = destination.is_unspecified() && *prefix_len == "00";
+            (is_default && !next_hop.is_unspecified()).then(|| (metric, next_hop.to_string()))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, gateway)| gateway)
+}
+
+/// Canonical name for a kernel TCP state number (include/net/tcp_states.h)
+pub fn tcp_state_name(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW_SYN_RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Map socket inodes to their owning PID by scanning /proc/<pid>/fd (best-effort)
+/// Processes we can't inspect (other users without privilege) are skipped
+fn socket_inode_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return owners;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        for fd in fds.flatten() {
+            if let Ok(target) = fs::read_link(fd.path()) {
+                if let Some(inode) = target
+                    .to_str()
+                    .and_then(|t| t.strip_prefix("socket:["))
+                    .and_then(|t| t.strip_suffix(']'))
+                    .and_then(|t| t.parse::<u64>().ok())
+                {
+                    owners.entry(inode).or_insert(pid);
+                }
+            }
+        }
+    }
+
+    owners
+}
+
 impl Default for NetworkMonitor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::{poison_lock, ScratchDir};
+
+    // Captured on x86_64; the kernel prints addresses in host byte order
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_proc_net_tcp_lines() {
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when \
+                      retrnsmt   uid  timeout inode";
+        assert!(parse_proc_net_line("tcp", header).is_none());
+
+        let listen = "   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 \
+                      00000000     0        0 23456 1 0000000000000000 100 0 0 10 0";
+        let c = parse_proc_net_line("tcp", listen).unwrap();
+        assert_eq!((c.local_addr.as_str(), c.local_port), ("127.0.0.1", 631));
+        assert_eq!((c.remote_addr.as_str(), c.remote_port), ("0.0.0.0", 0));
+        assert_eq!((c.state.as_str(), c.inode), ("LISTEN", 23456));
+
+        let established = "   1: 0F02000A:A2C4 5DB8D822:01BB 01 00000000:00000000 02:000001F4 \
+                           00000000  1000        0 98765 2 0000000000000000 20 4 30 10 -1";
+        let c = parse_proc_net_line("tcp", established).unwrap();
+        assert_eq!((c.local_addr.as_str(), c.local_port), ("10.0.2.15", 41668));
+        assert_eq!((c.remote_addr.as_str(), c.remote_port), ("34.216.184.93", 443));
+        assert_eq!(c.state, "ESTABLISHED");
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_proc_net_tcp6_lines() {
+        let any = "   0: 00000000000000000000000000000000:0016 \
+                   00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 \
+                   00000000     0        0 34567 1 0000000000000000 100 0 0 10 0";
+        let c = parse_proc_net_line("tcp6", any).unwrap();
+        assert_eq!((c.local_addr.as_str(), c.local_port, c.inode), ("::", 22, 34567));
+
+        let loopback = "   1: 00000000000000000000000001000000:1F90 \
+                        0000000000000000FFFF00000100007F:0050 06 00000000:00000000 03:00000F3C \
+                        00000000  1000        0 0 3 0000000000000000";
+        let c = parse_proc_net_line("tcp6", loopback).unwrap();
+        assert_eq!((c.local_addr.as_str(), c.local_port), ("::1", 8080));
+        assert_eq!((c.remote_addr.as_str(), c.remote_port), ("::ffff:127.0.0.1", 80));
+        assert_eq!(c.state, "TIME_WAIT");
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        assert!(decode_socket_addr("0100007F").is_none());
+        assert!(decode_socket_addr("0100007F:XYZ").is_none());
+        assert!(decode_socket_addr("01007F:0016").is_none());
+        assert_eq!(tcp_state_name(0x0A), "LISTEN");
+        assert_eq!(tcp_state_name(0xFF), "UNKNOWN");
+    }
+
+    #[test]
+    fn formats_interface_addresses() {
+        let mut sin: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+        sin.sin_family = libc::AF_INET as libc::sa_family_t;
+        sin.sin_addr.s_addr = u32::from_be_bytes([192, 168, 1, 20]).to_be();
+        let addr = unsafe { sockaddr_ip(&sin as *const _ as *const libc::sockaddr) };
+        assert_eq!(addr.unwrap().to_string(), "192.168.1.20");
+
+        let mut sin6: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+        sin6.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+        sin6.sin6_addr.s6_addr = "fe80::1ff:fe23:4567:890a".parse::<Ipv6Addr>().unwrap().octets();
+        let addr = unsafe { sockaddr_ip(&sin6 as *const _ as *const libc::sockaddr) };
+        assert_eq!(addr.unwrap().to_string(), "fe80::1ff:fe23:4567:890a");
+
+        // Link-layer entries carry the MAC, not an IP address
+        let mut ll: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        ll.sll_family = libc::AF_PACKET as u16;
+        assert!(unsafe { sockaddr_ip(&ll as *const _ as *const libc::sockaddr) }.is_none());
+    }
+
+    #[test]
+    fn classifies_interfaces() {
+        use InterfaceType::*;
+
+        // (name, ARPHRD type, wireless/, bridge/, device link, expected)
+        let cases = [
+            ("lo", Some(ARPHRD_LOOPBACK), false, false, false, Loopback),
+            ("enp3s0", Some(ARPHRD_ETHER), false, false, true, Ethernet),
+            ("eth0", None, false, false, false, Ethernet),
+            ("wlp2s0", Some(ARPHRD_ETHER), true, false, true, Wireless),
+            ("wlan0", None, false, false, false, Wireless),
+            ("br0", Some(ARPHRD_ETHER), false, true, false, Bridge),
+            ("br-4f2a9c", Some(ARPHRD_ETHER), false, false, false, Bridge),
+            ("virbr0", Some(ARPHRD_ETHER), false, false, false, Bridge),
+            ("tun0", Some(ARPHRD_NONE), false, false, false, Tun),
+            ("wg0", Some(ARPHRD_NONE), false, false, false, Tun),
+            ("docker0", Some(ARPHRD_ETHER), false, false, false, Virtual),
+            ("veth1a2b3c", Some(ARPHRD_ETHER), false, false, false, Virtual),
+            // Software Ethernet without a backing device (dummy, macvlan)
+            ("dummy0", Some(ARPHRD_ETHER), false, false, false, Virtual),
+            ("can0", Some(280), false, false, true, Unknown),
+        ];
+
+        for (name, arp_type, wireless, bridge, device, expected) in cases {
+            assert_eq!(
+                classify_interface_attrs(name, arp_type, wireless, bridge, device),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+
+    fn interface(name: &str, interface_type: InterfaceType, is_up: bool) -> NetworkInterface {
+        NetworkInterface {
+            name: name.to_string(),
+            interface_type,
+            is_up,
+            received_bytes: 1000,
+            transmitted_bytes: 500,
+            download_rate_bps: 100.0,
+            upload_rate_bps: 50.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn totals_leave_out_loopback_and_down_interfaces() {
+        let all = [
+            interface("lo", InterfaceType::Loopback, true),
+            interface("enp3s0", InterfaceType::Ethernet, true),
+            interface("wlp2s0", InterfaceType::Wireless, false),
+        ];
+        let totals = |query: NetworkQuery| {
+            let listed = all
+                .iter()
+                .filter(|i| query.includes(i.interface_type, i.is_up))
+                .cloned()
+                .collect();
+            let info = NetworkInfo::from_interfaces(listed, Vec::new());
+            (info.interfaces.len(), info.total_received, info.total_upload_rate)
+        };
+
+        assert_eq!(totals(NetworkQuery::default()), (1, 1000, 50.0));
+        let with_loopback = NetworkQuery {
+            include_loopback: true,
+            ..Default::default()
+        };
+        assert_eq!(totals(with_loopback), (2, 2000, 100.0));
+        let everything = NetworkQuery {
+            include_loopback: true,
+            only_up: false,
+        };
+        assert_eq!(totals(everything), (3, 3000, 150.0));
+    }
+
+    #[test]
+    fn history_wraps_and_prunes_vanished_interfaces() {
+        let monitor = NetworkMonitor::with_history_len(3);
+        let eth = interface("enp3s0", InterfaceType::Ethernet, true);
+        let wifi = interface("wlp2s0", InterfaceType::Wireless, true);
+
+        for t in 1..=5 {
+            let eth = NetworkInterface {
+                download_rate_bps: t as f64,
+                ..eth.clone()
+            };
+            monitor.record_history(&[eth, wifi.clone()], t * 1000);
+        }
+        let history = monitor.history();
+        assert_eq!(
+            history["enp3s0"],
+            [(3000, 3.0, 50.0), (4000, 4.0, 50.0), (5000, 5.0, 50.0)]
+        );
+
+        // The Wi-Fi link drops out; its history survives the grace period, then goes
+        monitor.record_history(std::slice::from_ref(&eth), 5000 + HISTORY_GRACE_PERIOD_MS);
+        assert_eq!(monitor.history()["wlp2s0"].len(), 3);
+        monitor.record_history(&[eth], 5001 + HISTORY_GRACE_PERIOD_MS);
+        assert!(!monitor.history().contains_key("wlp2s0"));
+        assert_eq!(monitor.history()["enp3s0"].len(), 3);
+    }
+
+    #[test]
+    fn reads_link_attributes_from_sysfs() {
+        use InterfaceType::*;
+
+        let net = ScratchDir::new("sys-class-net");
+        let interface = |name: &str, files: &[(&str, &str)]| {
+            for (file, contents) in files {
+                net.write(&format!("{}/{}", name, file), contents);
+            }
+        };
+        interface("lo", &[("type", "772\n"), ("operstate", "unknown\n"), ("flags", "0x9\n")]);
+        interface(
+            "enp3s0",
+            &[
+                ("type", "1\n"),
+                ("device/uevent", ""),
+                ("operstate", "up\n"),
+                ("speed", "1000\n"),
+                ("mtu", "1500\n"),
+                ("carrier", "1\n"),
+                ("duplex", "full\n"),
+                ("statistics/rx_dropped", "7\n"),
+                ("statistics/tx_dropped", "2\n"),
+            ],
+        );
+        // The driver keeps its last speed and duplex around after the cable is pulled
+        interface(
+            "wlp2s0",
+            &[
+                ("type", "1\n"),
+                ("wireless/.keep", ""),
+                ("device/uevent", ""),
+                ("operstate", "down\n"),
+                ("speed", "866\n"),
+                ("mtu", "1500\n"),
+                ("carrier", "0\n"),
+                ("duplex", "full\n"),
+            ],
+        );
+        // wireguard reports "unknown" and no speed; IFF_UP decides
+        interface("wg0", &[("type", "65534\n"), ("operstate", "unknown\n"), ("flags", "0x91\n")]);
+        interface("tun1", &[("type", "65534\n"), ("operstate", "unknown\n"), ("flags", "0x90\n")]);
+        interface("docker0", &[("type", "1\n"), ("bridge/.keep", ""), ("operstate", "up\n")]);
+
+        let types: Vec<_> = ["lo", "enp3s0", "wlp2s0", "wg0", "tun1", "docker0", "gone0"]
+            .iter()
+            .map(|name| classify_interface_in(&net, name))
+            .collect();
+        assert_eq!(types, [Loopback, Ethernet, Wireless, Tun, Tun, Bridge, Unknown]);
+
+        assert_eq!(read_link_state_in(&net, "lo"), (true, None));
+        assert_eq!(read_link_state_in(&net, "enp3s0"), (true, Some(1000)));
+        assert_eq!(read_link_state_in(&net, "wlp2s0"), (false, None));
+        assert_eq!(read_link_state_in(&net, "wg0"), (true, None));
+        assert_eq!(read_link_state_in(&net, "tun1"), (false, None));
+        assert_eq!(read_link_state_in(&net, "gone0"), (false, None));
+
+        let wired = read_link_details_in(&net, "enp3s0", Ethernet);
+        assert_eq!((wired.mtu, wired.carrier, wired.duplex.as_deref()), (1500, true, Some("full")));
+        assert_eq!((wired.dropped_in, wired.dropped_out), (7, 2));
+
+        let wireless = read_link_details_in(&net, "wlp2s0", Wireless);
+        assert_eq!((wireless.mtu, wireless.carrier, wireless.duplex), (1500, false, None));
+        assert_eq!((wireless.dropped_in, wireless.dropped_out), (0, 0));
+
+        let gone = read_link_details_in(&net, "gone0", Unknown);
+        assert_eq!((gone.mtu, gone.carrier), (0, false));
+    }
+
+    #[test]
+    fn drop_rate_between_two_samples() {
+        // 30 more drops over a 2.5 s refresh interval
+        assert_eq!(per_second(120, 150, 2.5), 12.0);
+        assert_eq!(per_second(150, 150, 1.0), 0.0);
+        // The driver reset its counters, or two refreshes landed in the same millisecond
+        assert_eq!(per_second(150, 4, 1.0), 0.0);
+        assert_eq!(per_second(120, 150, 0.0), 0.0);
+    }
+
+    #[test]
+    fn hidden_interfaces_leave_the_list_and_totals() {
+        let monitor = NetworkMonitor::new();
+        let everything = NetworkQuery {
+            include_loopback: true,
+            only_up: false,
+        };
+        let mut names: Vec<String> =
+            monitor.refresh(&everything).interfaces.into_iter().map(|i| i.name).collect();
+        names.push("aa-not-present".to_string());
+        names.reverse();
+        monitor.set_hidden(names.clone());
+
+        let info = monitor.refresh(&everything);
+        assert!(info.interfaces.is_empty());
+        assert_eq!((info.total_received, info.total_transmitted), (0, 0));
+        assert_eq!((info.total_download_rate, info.total_upload_rate), (0.0, 0.0));
+        names.sort();
+        assert_eq!(info.hidden, names);
+    }
+
+    #[test]
+    fn decodes_hex_tcp_states() {
+        let names: Vec<_> = (0x01..=0x0C).map(tcp_state_name).collect();
+        assert_eq!(
+            names,
+            [
+                "ESTABLISHED", "SYN_SENT", "SYN_RECV", "FIN_WAIT1", "FIN_WAIT2", "TIME_WAIT",
+                "CLOSE", "CLOSE_WAIT", "LAST_ACK", "LISTEN", "CLOSING", "NEW_SYN_RECV",
+            ]
+        );
+        assert_eq!(tcp_state_name(0x00), "UNKNOWN");
+
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when \
+                      retrnsmt   uid  timeout inode\n";
+        let row = |state: &str| {
+            format!(
+                "   0: 0100007F:0277 00000000:0000 {} 00000000:00000000 00:00000000 \
+                 00000000     0        0 23456 1 0000000000000000 100 0 0 10 0\n",
+                state
+            )
+        };
+        let tcp = [header.to_string(), row("0A"), row("01"), row("01"), row("06"), row("zz")];
+        let mut summary = SocketSummary {
+            tcp_states: BTreeMap::new(),
+            tcp_total: 0,
+            udp_total: 0,
+        };
+        summary.add_socket_table("tcp", &tcp.concat());
+        summary.add_socket_table("tcp6", &[header.to_string(), row("0a")].concat());
+        summary.add_socket_table("udp", &[header.to_string(), row("07")].concat());
+
+        let states: Vec<_> = summary.tcp_states.iter().map(|(s, n)| (s.as_str(), *n)).collect();
+        assert_eq!(states, [("ESTABLISHED", 2), ("LISTEN", 2), ("TIME_WAIT", 1)]);
+        assert_eq!((summary.tcp_total, summary.udp_total), (5, 1));
+    }
+
+    #[test]
+    fn parses_resolv_conf_nameservers() {
+        let conf = "# Generated by NetworkManager\n\
+                    search lan\n\
+                    nameserver 192.168.1.1 # router\n\
+                    ; nameserver 10.0.0.1\n\
+                    nameserver\tfd00::1\n\
+                    nameserver\n\
+                    options edns0\n";
+        assert_eq!(parse_resolv_conf(conf), ["192.168.1.1", "fd00::1"]);
+        assert!(parse_resolv_conf("").is_empty());
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn decodes_default_gateways_from_route_tables() {
+        let route = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\n\
+                     wlp2s0\t00000000\tFE01A8C0\t0003\t0\t0\t600\t00000000\t0\n\
+                     enp3s0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\n\
+                     enp3s0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\n";
+        // Little-endian hex: 0102A8C0 is 192.168.2.1; the lower metric wins
+        assert_eq!(parse_default_gateway_v4(route).as_deref(), Some("192.168.2.1"));
+        // A default route without RTF_GATEWAY (point-to-point link) has no gateway
+        let ppp = "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                   ppp0\t00000000\t00000000\t0001\t0\t0\t0\t00000000\n";
+        assert_eq!(parse_default_gateway_v4(ppp), None);
+
+        let zero = "0".repeat(32);
+        // dest dest_len src src_len next_hop metric refcnt use flags iface
+        let default_via = |next_hop: &str, metric: &str, iface: &str| {
+            format!("{z} 00 {z} 00 {} {} 1 0 3 {}", next_hop, metric, iface, z = zero)
+        };
+        let ipv6_route = [
+            default_via("fe800000000000000000000000000001", "00000400", "wlp2s0"),
+            default_via("fe800000000000000000000000000002", "00000100", "enp3s0"),
+            // Unreachable default route on lo: no next hop
+            default_via(&zero, "ffffffff", "lo"),
+            format!("fe80{} 40 {z} 00 {z} 00000100 1 0 1 enp3s0", &zero[4..], z = zero),
+        ]
+        .join("\n");
+        assert_eq!(parse_default_gateway_v6(&ipv6_route).as_deref(), Some("fe80::2"));
+        assert_eq!(parse_default_gateway_v6(&default_via(&zero, "ffffffff", "lo")), None);
+    }
+
+    #[test]
+    fn parses_snmp_header_value_pairs() {
+        let snmp = "Ip: Forwarding DefaultTTL InReceives InDelivers OutRequests\n\
+                    Ip: 1 64 1234567 1230000 987654\n\
+                    Icmp: InMsgs OutMsgs\n\
+                    Icmp: 12 15\n\
+                    Tcp: RtoAlgorithm MaxConn InSegs OutSegs\n\
+                    Tcp: 1 -1 55555 44444\n\
+                    Udp: InDatagrams OutDatagrams\n\
+                    Udp: 3210 3100\n";
+        let counters = parse_snmp(snmp);
+        assert_eq!(counters["Ip.InReceives"], 1_234_567);
+        assert_eq!(counters["Icmp.OutMsgs"], 15);
+        assert_eq!((counters["Tcp.InSegs"], counters["Tcp.OutSegs"]), (55_555, 44_444));
+        assert_eq!(counters["Udp.InDatagrams"], 3_210);
+        // Tcp MaxConn is -1 (no limit)
+        assert!(!counters.contains_key("Tcp.MaxConn"));
+
+        // A header whose value line belongs to another protocol is skipped, not misread
+        let counters = parse_snmp("Tcp: InSegs\nUdp: 5\nUdp: InDatagrams\nUdp: 7\n");
+        assert_eq!(counters.get("Tcp.InSegs"), None);
+        assert_eq!(counters["Udp.InDatagrams"], 7);
+
+        let snmp6 = "Ip6InReceives                   \t84213\n\
+                     Ip6OutRequests                  \t80011\n\
+                     Udp6InDatagrams                 \t912\n\
+                     Icmp6OutMsgs\n";
+        let counters = parse_snmp6(snmp6);
+        assert_eq!(counters["Ip6InReceives"], 84_213);
+        assert_eq!(counters["Udp6InDatagrams"], 912);
+        assert_eq!(counters.len(), 3);
+    }
+
+    #[test]
+    fn reset_counters_restarts_since_start_and_rates() {
+        let monitor = NetworkMonitor::new();
+        let everything = NetworkQuery {
+            include_loopback: true,
+            only_up: false,
+        };
+        monitor.refresh(&everything);
+        monitor.reset_counters();
+
+        let info = monitor.refresh(&everything);
+        for iface in &info.interfaces {
+            assert_eq!(
+                (iface.received_since_start, iface.transmitted_since_start),
+                (0, 0),
+                "{}",
+                iface.name
+            );
+            assert_eq!((iface.download_rate_bps, iface.upload_rate_bps), (0.0, 0.0));
+            assert_eq!((iface.errors_in_per_sec, iface.dropped_in_per_sec), (0.0, 0.0));
+        }
+        assert_eq!(info.total_received_since_start, 0);
+        assert_eq!(info.total_transmitted_since_start, 0);
+    }
+
+    #[test]
+    fn refresh_recovers_from_poisoned_locks() {
+        let monitor = NetworkMonitor::new();
+        let everything = NetworkQuery {
+            include_loopback: true,
+            only_up: false,
+        };
+        let before = monitor.refresh(&everything).interfaces.len();
+
+        poison_lock(&monitor.networks);
+        poison_lock(&monitor.last_samples);
+        assert_eq!(monitor.refresh(&everything).interfaces.len(), before);
+        assert!(!monitor.networks.is_poisoned());
+    }
+}
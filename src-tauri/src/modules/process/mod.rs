@@ -1,11 +1,14 @@
 // Process Monitoring Module
 // Provides process listing, details, and management with priority control
 
-use crate::modules::MonitorError;
+use crate::modules::{LockRecover, MonitorError};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 use std::sync::RwLock;
-use sysinfo::{Pid, ProcessStatus, Signal, System};
+use std::time::{Duration, Instant};
+use sysinfo::{CpuRefreshKind, Pid, Process, ProcessStatus, ProcessesToUpdate, Signal, System, MINIMUM_CPU_UPDATE_INTERVAL};
 
 /// Status of a process
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +36,7 @@ impl From<ProcessStatus> for ProcStatus {
 
 /// Information about a single process
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ProcessInfo {
     pub pid: u32,
     pub parent_pid: Option<u32>,
@@ -44,22 +48,158 @@ pub struct ProcessInfo {
     pub memory_bytes: u64,
     pub memory_percent: f32,
     pub start_time: u64,
+    pub start_timestamp: u64, // Unix seconds, always absolute
     pub run_time: u64,
     pub user_id: Option<String>,
     pub nice: i32,
+    pub open_fds: u32,          // 0 unless requested via ProcessQuery::include_fds
+    pub socket_count: u32,      // 0 unless requested via ProcessQuery::include_fds
+    pub cgroup: Option<String>, // cgroup path, e.g. /system.slice/nginx.service
+    pub container_id: Option<String>, // Docker/containerd/podman id, None on the host
     pub instance_count: Option<u32>, // Number of instances when grouped
+    pub pinned: bool, // Pinned by the user (any instance, when grouped); listed first
 }
 
 /// Process list result
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ProcessList {
     pub processes: Vec<ProcessInfo>,
     pub total_count: usize,
 }
 
+/// Command line and environment of a single process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDetails {
+    pub pid: u32,
+    pub name: String,
+    pub command: Vec<String>,
+    pub environment: Option<Vec<(String, String)>>, // None when unreadable (other users' processes)
+    pub fd_count: Option<u32>,      // Open file descriptors; None when unreadable
+    pub fd_soft_limit: Option<u64>, // RLIMIT_NOFILE, u64::MAX for unlimited
+    pub fd_hard_limit: Option<u64>,
+}
+
+/// What get_top_processes ranks by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum SortKey {
+    Cpu,
+    Memory,
+}
+
+/// Processes that appeared or went away between two streaming refreshes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessChanges {
+    pub started: Vec<ProcessInfo>, // Ungrouped, per-core CPU usage
+    pub exited: Vec<u32>,          // PIDs
+}
+
+/// Outcome of kill_by_name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillByNameResult {
+    pub matched: u32,
+    pub killed: u32,
+    pub failures: Vec<(u32, String)>, // (pid, reason)
+}
+
+/// Options for a process list refresh
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProcessQuery {
+    pub include_fds: bool,       // Count open fds/sockets (one readdir per process)
+    pub filter: Option<String>,  // Case-insensitive match on name or command line
+    pub min_cpu: Option<f32>,    // Percent, compared after normalization
+    // false: percent of one core, so a multi-threaded process can exceed 100 (up to 100 * cores)
+    // true: percent of total CPU capacity (divided by logical cores), so it never exceeds 100
+    pub normalize_cpu: bool,
+    pub min_memory: Option<u64>, // Bytes
+    pub group_by: GroupKey,
+    pub scope: ProcessScope,
+}
+
+/// Which PID namespace a process list covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcessScope {
+    #[default]
+    Namespace, // Processes visible to Nova (the container's own, when containerized)
+    // Every process on the host; inside a container this needs the host's /proc mounted
+    // at HOST_PROC_PATH (e.g. -v /proc:/host/proc:ro), otherwise it sees the namespace only
+    Host,
+}
+
+impl ProcessScope {
+    /// /proc root the scope reads; outside a container /proc already is the host's view
+    fn proc_root(self) -> &'static str {
+        self.proc_root_with(HOST_PROC_PATH)
+    }
+
+    fn proc_root_with(self, host_proc: &str) -> &str {
+        match self {
+            ProcessScope::Namespace => "/proc",
+            ProcessScope::Host if Path::new(host_proc).is_dir() => host_proc,
+            ProcessScope::Host => "/proc",
+        }
+    }
+}
+
+/// What processes are merged into one row by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupKey {
+    #[default]
+    Name,
+    ExePath, // Keeps same-named binaries from different installs apart
+    User,
+    None, // Every process gets its own row
+}
+
+impl GroupKey {
+    /// Grouping key of a process; the pid for GroupKey::None, so no two processes share one
+    /// Processes without a readable exe (kernel threads, other users) fall back to their name
+    fn key_for(self, process: &ProcessInfo) -> String {
+        match self {
+            GroupKey::Name => process.name.clone(),
+            GroupKey::ExePath if process.exe_path.is_empty() => process.name.clone(),
+            GroupKey::ExePath => process.exe_path.clone(),
+            GroupKey::User => process.user_id.clone().unwrap_or_default(),
+            GroupKey::None => process.pid.to_string(),
+        }
+    }
+}
+
+// Processes kill_by_name refuses to target, compared case-insensitively
+const CRITICAL_PROCESS_NAMES: [&str; 10] = [
+    "init", "systemd", "kthreadd", "launchd", "kernel_task",
+    "csrss.exe", "wininit.exe", "winlogon.exe", "services.exe", "lsass.exe",
+];
+
+// Marks an env var or command-line flag as secret anywhere in the name, compared
+// case-insensitively (PGPASSWORD, GITHUBTOKEN, accessToken, --db-password)
+const SECRET_NAME_PARTS: [&str; 6] =
+    ["password", "passwd", "passphrase", "secret", "token", "credential"];
+
+// Too short to match inside other words, so these must be a whole "_"/"-" separated word
+// of the name (API_KEY, --api-key, APIKEY, MYSQL_PWD) and leave KEYBOARD or --monkey alone
+const SECRET_NAME_WORDS: [&str; 3] = ["key", "apikey", "pwd"];
+
+// Replacement for redacted values
+const REDACTED: &str = "***";
+
+// Where a container is expected to have the host's /proc bind-mounted
+const HOST_PROC_PATH: &str = "/host/proc";
+
+// Minimum time between process CPU samples; usage over shorter spans is 0 or noisy
+pub const MIN_INTERVAL: Duration = MINIMUM_CPU_UPDATE_INTERVAL;
+
 /// Process Monitor state with lazy initialization
 pub struct ProcessMonitor {
     system: RwLock<Option<System>>,
+    last_sample: RwLock<Option<Instant>>, // When process CPU times were last sampled
+    known_pids: RwLock<Option<HashSet<u32>>>, // PIDs seen by the last take_changes
+    pinned: RwLock<HashSet<u32>>, // PIDs always listed, regardless of filters
+    host_sample: RwLock<Option<(Instant, HashMap<u32, u64>)>>, // CPU ticks per host PID
 }
 
 impl ProcessMonitor {
@@ -67,121 +207,297 @@ impl ProcessMonitor {
         // Don't initialize System here - do it lazily
         Self {
             system: RwLock::new(None),
+            last_sample: RwLock::new(None),
+            known_pids: RwLock::new(None),
+            pinned: RwLock::new(HashSet::new()),
+            host_sample: RwLock::new(None),
         }
     }
 
-    pub fn refresh(&self) -> ProcessList {
+    /// Replace the set of pinned PIDs
+    pub fn set_pinned(&self, pids: Vec<u32>) {
+        *self
+            .pinned
+            .write_or_recover("Process pinned") = pids.into_iter().collect();
+    }
+
+    pub fn refresh(&self, query: &ProcessQuery) -> ProcessList {
         let mut sys_guard = self
             .system
-            .write()
-            .expect("Process monitor RwLock poisoned - fatal error");
+            .write_or_recover("Process monitor");
 
-        // Lazy initialization
-        if sys_guard.is_none() {
-            let mut system = System::new_all();
-            system.refresh_all();
-            *sys_guard = Some(system);
-        }
+        let mut last_sample = self
+            .last_sample
+            .write_or_recover("Process sample");
 
-        let sys = sys_guard.as_mut().unwrap();
-        sys.refresh_all();
+        let sys = Self::sample_processes(&mut sys_guard, &mut last_sample);
 
         let total_memory = sys.total_memory();
+        let logical_cores = sys.cpus().len().max(1);
+        let boot_time = System::boot_time();
         let mut processes: Vec<ProcessInfo> = Vec::new();
 
-        for (pid, process) in sys.processes() {
-            let memory = process.memory();
+        let filter = query
+            .filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::to_lowercase);
+
+        let proc_root = query.scope.proc_root();
+        let candidates: Vec<ProcessInfo> = match query.scope {
+            ProcessScope::Namespace => sys
+                .processes()
+                .iter()
+                .map(|(pid, process)| process_info(pid.as_u32(), process, total_memory, boot_time))
+                .collect(),
+            ProcessScope::Host => self.host_processes(proc_root, total_memory, boot_time),
+        };
+
+        // Pins of processes that have exited are dropped, so a recycled PID isn't pinned
+        // Pins are namespace PIDs, so a host listing leaves them alone
+        let mut pinned = self
+            .pinned
+            .write_or_recover("Process pinned");
+        if query.scope == ProcessScope::Namespace {
+            pinned.retain(|pid| sys.process(Pid::from_u32(*pid)).is_some());
+        }
+
+        for mut info in candidates {
+            if query.normalize_cpu {
+                info.cpu_usage = normalize_cpu_usage(info.cpu_usage, logical_cores);
+            }
+            info.pinned = query.scope == ProcessScope::Namespace && pinned.contains(&info.pid);
+
+            // Filters apply per process, before grouping, so skipped ones never reach the totals;
+            // pinned processes are exempt
+            if !info.pinned && !passes_filters(&info, filter.as_deref(), query) {
+                continue;
+            }
+
+            if query.include_fds {
+                (info.open_fds, info.socket_count) = count_fds(proc_root, info.pid);
+            }
+            processes.push(info);
+        }
+
+        drop(pinned);
+        let mut grouped_processes = group_processes(processes, query.group_by);
+
+        // cgroup is read once per group, for the representative (lowest) pid
+        for p in &mut grouped_processes {
+            if let Some(path) = read_cgroup_path(proc_root, p.pid) {
+                p.container_id = container_id_from_cgroup(&path);
+                p.cgroup = Some(path);
+            }
+        }
+
+        // Pinned processes first, then by CPU usage descending
+        grouped_processes.sort_by(|a, b| {
+            b.pinned.cmp(&a.pinned).then_with(|| {
+                b.cpu_usage
+                    .partial_cmp(&a.cpu_usage)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+
+        let total_count = grouped_processes.len();
+        ProcessList {
+            processes: grouped_processes,
+            total_count,
+        }
+    }
+
+    /// The `n` busiest processes by `by`, ungrouped and unfiltered
+    /// Only the winners get a full ProcessInfo, which keeps this cheap on busy systems
+    pub fn top(&self, n: usize, by: SortKey) -> Vec<ProcessInfo> {
+        let mut sys_guard = self
+            .system
+            .write_or_recover("Process monitor");
+        let mut last_sample = self
+            .last_sample
+            .write_or_recover("Process sample");
+        let sys = Self::sample_processes(&mut sys_guard, &mut last_sample);
+
+        let rank = |process: &Process| match by {
+            SortKey::Cpu => process.cpu_usage() as f64,
+            SortKey::Memory => process.memory() as f64,
+        };
+        let mut ranked: Vec<(&Pid, &Process)> = sys.processes().iter().collect();
+        let by_rank = |a: &(&Pid, &Process), b: &(&Pid, &Process)| rank(b.1).total_cmp(&rank(a.1));
+        if n < ranked.len() {
+            ranked.select_nth_unstable_by(n, by_rank);
+            ranked.truncate(n);
+        }
+        ranked.sort_by(by_rank);
+
+        let total_memory = sys.total_memory();
+        let boot_time = System::boot_time();
+        ranked
+            .into_iter()
+            .map(|(pid, process)| process_info(pid.as_u32(), process, total_memory, boot_time))
+            .collect()
+    }
+
+    /// Processes read straight from `proc_root`, bypassing sysinfo's fixed /proc
+    /// CPU usage is the tick delta since the previous host listing, 0 on the first one
+    fn host_processes(
+        &self,
+        proc_root: &str,
+        total_memory: u64,
+        boot_time: u64,
+    ) -> Vec<ProcessInfo> {
+        let Ok(entries) = fs::read_dir(proc_root) else {
+            return Vec::new();
+        };
+        // SAFETY: sysconf has no preconditions
+        let (ticks_per_sec, page_size) =
+            unsafe { (libc::sysconf(libc::_SC_CLK_TCK), libc::sysconf(libc::_SC_PAGESIZE)) };
+        let ticks_per_sec = ticks_per_sec.max(1) as u64;
+        let page_size = page_size.max(0) as u64;
+
+        let mut host_sample = self
+            .host_sample
+            .write_or_recover("Process host sample");
+        let now = Instant::now();
+        let (previous_at, previous_ticks) = host_sample.take().unwrap_or((now, HashMap::new()));
+        let elapsed_ticks = now.duration_since(previous_at).as_secs_f64() * ticks_per_sec as f64;
+        let now_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut ticks = HashMap::new();
+        let mut processes = Vec::new();
+        for entry in entries.flatten() {
+            let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            let dir = entry.path();
+            let Some(stat) = fs::read_to_string(dir.join("stat"))
+                .ok()
+                .and_then(|contents| parse_proc_stat(&contents))
+            else {
+                continue;
+            };
+
+            ticks.insert(pid, stat.cpu_ticks);
+            let cpu_usage = match previous_ticks.get(&pid) {
+                Some(&prev) if elapsed_ticks > 0.0 => {
+                    (stat.cpu_ticks.saturating_sub(prev) as f64 / elapsed_ticks * 100.0) as f32
+                }
+                _ => 0.0,
+            };
+            let memory = stat.rss_pages * page_size;
             let memory_percent = if total_memory > 0 {
                 (memory as f32 / total_memory as f32) * 100.0
             } else {
                 0.0
             };
+            let start_time = boot_time + stat.start_ticks / ticks_per_sec;
 
             processes.push(ProcessInfo {
-                pid: pid.as_u32(),
-                parent_pid: process.parent().map(|p| p.as_u32()),
-                name: process.name().to_string_lossy().to_string(),
-                exe_path: process
-                    .exe()
+                pid,
+                parent_pid: Some(stat.parent_pid).filter(|&ppid| ppid != 0),
+                name: stat.name,
+                exe_path: fs::read_link(dir.join("exe"))
                     .map(|p| p.to_string_lossy().to_string())
                     .unwrap_or_default(),
-                command: process
-                    .cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .collect(),
-                status: process.status().into(),
-                cpu_usage: process.cpu_usage(),
+                command: read_nul_separated(&format!("{}/{}/cmdline", proc_root, pid))
+                    .unwrap_or_default(),
+                status: ProcessStatus::from(stat.state).into(),
+                cpu_usage,
                 memory_bytes: memory,
                 memory_percent,
-                start_time: process.start_time(),
-                run_time: process.run_time(),
-                user_id: process.user_id().map(|u| u.to_string()),
-                nice: unsafe {
-                    // Clear errno
-                    *libc::__errno_location() = 0;
-                    let val = libc::getpriority(0, pid.as_u32());
-                    if val == -1 && *libc::__errno_location() != 0 {
-                        0
-                    } else {
-                        val
-                    }
-                },
-                instance_count: None, // Will be set if grouped
+                start_time,
+                start_timestamp: start_time,
+                run_time: now_secs.saturating_sub(start_time),
+                user_id: fs::read_to_string(dir.join("status"))
+                    .ok()
+                    .and_then(|contents| parse_status_uid(&contents)),
+                nice: stat.nice,
+                open_fds: 0,
+                socket_count: 0,
+                cgroup: None,
+                container_id: None,
+                instance_count: None,
+                pinned: false,
             });
         }
 
-        // Group processes by name
-        let mut groups: std::collections::HashMap<String, ProcessInfo> =
-            std::collections::HashMap::new();
-
-        for p in processes {
-            groups
-                .entry(p.name.clone())
-                .and_modify(|e| {
-                    // CPU: sum usage
-                    e.cpu_usage += p.cpu_usage;
-                    // Memory: keep existing (assuming main process/shared memory)
-                    // Instance count: increment
-                    e.instance_count = Some(e.instance_count.unwrap_or(1) + 1);
-
-                    // Keep lowest PID as representative (usually main thread/process)
-                    if p.pid < e.pid {
-                        e.pid = p.pid;
-                        e.memory_bytes = p.memory_bytes;
-                        e.memory_percent = p.memory_percent;
-                        e.start_time = p.start_time;
-                        e.user_id = p.user_id.clone();
-                    }
-                })
-                .or_insert_with(|| {
-                    let mut new_p = p.clone();
-                    new_p.instance_count = Some(1);
-                    new_p
-                });
+        *host_sample = Some((now, ticks));
+        processes
+    }
+
+    /// Create the System on first use and re-scan processes once MIN_INTERVAL has passed
+    /// Per-process CPU usage is the CPU time delta between two process refreshes,
+    /// divided by the elapsed time. The first refresh only records a baseline (usage
+    /// is 0), and samples closer together than MIN_INTERVAL give noisy
+    /// values, so the initial baseline is taken one interval before the first reading
+    /// and refreshes arriving too soon reuse the previous sample.
+    fn sample_processes<'a>(
+        sys_guard: &'a mut Option<System>,
+        last_sample: &mut Option<Instant>,
+    ) -> &'a mut System {
+        if sys_guard.is_none() {
+            let mut system = System::new();
+            // Total memory is only needed as the memory_percent denominator
+            system.refresh_memory();
+            // Only the CPU list, for the logical core count used by normalize_cpu
+            system.refresh_cpu_list(CpuRefreshKind::new());
+            system.refresh_processes(ProcessesToUpdate::All, true);
+            std::thread::sleep(MIN_INTERVAL);
+            *sys_guard = Some(system);
+            *last_sample = None;
         }
 
-        let mut grouped_processes: Vec<ProcessInfo> = groups.into_values().collect();
+        let sys = sys_guard.as_mut().unwrap();
+        if last_sample.is_none_or(|t| t.elapsed() >= MIN_INTERVAL) {
+            // Only processes are re-scanned; CPU, disks and components belong to other monitors
+            sys.refresh_processes(ProcessesToUpdate::All, true);
+            *last_sample = Some(Instant::now());
+        }
+        sys
+    }
 
-        // Sort by CPU usage descending by default
-        grouped_processes.sort_by(|a, b| {
-            b.cpu_usage
-                .partial_cmp(&a.cpu_usage)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+    /// Processes started and exited since the previous call, as of the last refresh
+    /// The first call only records the current PIDs, so it reports no changes
+    pub fn take_changes(&self) -> ProcessChanges {
+        let sys_guard = self
+            .system
+            .read_or_recover("Process monitor");
+        let Some(sys) = sys_guard.as_ref() else {
+            return ProcessChanges::default();
+        };
 
-        let total_count = grouped_processes.len();
-        ProcessList {
-            processes: grouped_processes,
-            total_count,
+        let current: HashSet<u32> = sys.processes().keys().map(|pid| pid.as_u32()).collect();
+        let mut known_pids = self
+            .known_pids
+            .write_or_recover("Process PIDs");
+        let (started, exited) = known_pids
+            .as_ref()
+            .map(|previous| diff_pids(previous, &current))
+            .unwrap_or_default();
+        *known_pids = Some(current);
+
+        let total_memory = sys.total_memory();
+        let boot_time = System::boot_time();
+        ProcessChanges {
+            started: started
+                .into_iter()
+                .filter_map(|pid| {
+                    let process = sys.process(Pid::from_u32(pid))?;
+                    Some(process_info(pid, process, total_memory, boot_time))
+                })
+                .collect(),
+            exited,
         }
     }
 
     pub fn kill_process(&self, pid: u32, force: bool) -> Result<bool, MonitorError> {
         let sys_guard = self
             .system
-            .read()
-            .expect("Process monitor RwLock poisoned - fatal error");
+            .read_or_recover("Process monitor");
 
         let sys = match sys_guard.as_ref() {
             Some(s) => s,
@@ -207,11 +523,37 @@ impl ProcessMonitor {
         }
     }
 
+    /// Signal every process whose name matches `name` exactly (case-insensitive)
+    /// Works on the last refreshed process list; empty names and critical system
+    /// processes are refused outright
+    pub fn kill_by_name(&self, name: &str, force: bool) -> Result<KillByNameResult, MonitorError> {
+        let name = validate_kill_name(name)?;
+
+        let sys_guard = self
+            .system
+            .read_or_recover("Process monitor");
+        let Some(sys) = sys_guard.as_ref() else {
+            return Err(MonitorError::SystemAccess(
+                "Process monitor not initialized".to_string(),
+            ));
+        };
+
+        let signal = if force { Signal::Kill } else { Signal::Term };
+        let candidates = sys
+            .processes()
+            .iter()
+            .map(|(pid, process)| (pid.as_u32(), process.name().to_string_lossy()));
+        Ok(kill_matching(candidates, name, std::process::id(), |pid| {
+            sys.process(Pid::from_u32(pid))
+                .map_or(Some(false), |process| process.kill_with(signal))
+        }))
+    }
+
     /// Set process priority (nice value)
     /// nice: -20 (highest priority) to 19 (lowest priority)
     /// Requires root/CAP_SYS_NICE for nice < 0
     pub fn set_priority(&self, pid: u32, nice: i32) -> Result<(), MonitorError> {
-        if nice < -20 || nice > 19 {
+        if !(-20..=19).contains(&nice) {
             return Err(MonitorError::PermissionDenied(
                 "Nice value must be between -20 and 19".to_string(),
             ));
@@ -239,8 +581,834 @@ impl ProcessMonitor {
     }
 }
 
+/// Trimmed kill_by_name target; empty names and critical system processes are refused
+fn validate_kill_name(name: &str) -> Result<&str, MonitorError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(MonitorError::PermissionDenied(
+            "Process name must not be empty".to_string(),
+        ));
+    }
+    if CRITICAL_PROCESS_NAMES
+        .iter()
+        .any(|critical| critical.eq_ignore_ascii_case(name))
+    {
+        return Err(MonitorError::PermissionDenied(format!(
+            "Refusing to kill critical process '{}'",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+/// Signal every (pid, name) candidate named `name` (case-insensitive) except `own_pid`
+/// `kill` reports like Process::kill_with: None when the signal isn't supported
+fn kill_matching<N: AsRef<str>>(
+    candidates: impl IntoIterator<Item = (u32, N)>,
+    name: &str,
+    own_pid: u32,
+    mut kill: impl FnMut(u32) -> Option<bool>,
+) -> KillByNameResult {
+    let mut result = KillByNameResult {
+        matched: 0,
+        killed: 0,
+        failures: Vec::new(),
+    };
+
+    for (pid, candidate) in candidates {
+        if pid == own_pid || !candidate.as_ref().eq_ignore_ascii_case(name) {
+            continue;
+        }
+        result.matched += 1;
+        match kill(pid) {
+            Some(true) => result.killed += 1,
+            Some(false) => result
+                .failures
+                .push((pid, "Permission denied or process already exited".to_string())),
+            None => result
+                .failures
+                .push((pid, "Signal not supported on this platform".to_string())),
+        }
+    }
+
+    result
+}
+
+/// Whether a process matches the text filter (already lowercased) and the query's minimums
+fn passes_filters(info: &ProcessInfo, filter: Option<&str>, query: &ProcessQuery) -> bool {
+    let matches_filter = filter.is_none_or(|needle| {
+        info.name.to_lowercase().contains(needle)
+            || info.command.join(" ").to_lowercase().contains(needle)
+    });
+    let below_minimum = query.min_cpu.is_some_and(|min| info.cpu_usage < min)
+        || query.min_memory.is_some_and(|min| info.memory_bytes < min);
+    matches_filter && !below_minimum
+}
+
+/// (started, exited) PIDs between two PID sets, each sorted ascending
+fn diff_pids(previous: &HashSet<u32>, current: &HashSet<u32>) -> (Vec<u32>, Vec<u32>) {
+    let mut started: Vec<u32> = current.difference(previous).copied().collect();
+    let mut exited: Vec<u32> = previous.difference(current).copied().collect();
+    started.sort_unstable();
+    exited.sort_unstable();
+    (started, exited)
+}
+
+/// Fields of /proc/<pid>/stat used by host-scope listings
+struct ProcStat {
+    name: String,
+    state: char,
+    parent_pid: u32,
+    cpu_ticks: u64, // utime + stime
+    nice: i32,
+    start_ticks: u64, // Since boot
+    rss_pages: u64,
+}
+
+/// Parse /proc/<pid>/stat; field numbers below follow proc(5)
+/// comm may contain spaces and ')', so it runs up to the last ')'
+fn parse_proc_stat(contents: &str) -> Option<ProcStat> {
+    let open = contents.find('(')?;
+    let close = contents.rfind(')')?;
+    let name = contents.get(open + 1..close)?.to_string();
+    // The fields after comm start at field 3 (state)
+    let fields: Vec<&str> = contents[close + 1..].split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3).copied();
+    let number = |n: usize| field(n).and_then(|v| v.parse::<u64>().ok());
+
+    Some(ProcStat {
+        name,
+        state: field(3)?.chars().next()?,
+        parent_pid: field(4)?.parse().ok()?,
+        cpu_ticks: number(14)? + number(15)?,
+        nice: field(19)?.parse().ok()?,
+        start_ticks: number(22)?,
+        rss_pages: number(24)?,
+    })
+}
+
+/// Real UID from the "Uid:" line of /proc/<pid>/status
+fn parse_status_uid(contents: &str) -> Option<String> {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|uids| uids.split_whitespace().next())
+        .map(str::to_string)
+}
+
+/// Build the ungrouped ProcessInfo for one process; CPU usage is per core (not normalized)
+/// and fd counts are left at 0
+fn process_info(pid: u32, process: &Process, total_memory: u64, boot_time: u64) -> ProcessInfo {
+    let memory = process.memory();
+    let memory_percent = if total_memory > 0 {
+        (memory as f32 / total_memory as f32) * 100.0
+    } else {
+        0.0
+    };
+
+    ProcessInfo {
+        pid,
+        parent_pid: process.parent().map(|p| p.as_u32()),
+        name: process.name().to_string_lossy().to_string(),
+        exe_path: process
+            .exe()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        command: process
+            .cmd()
+            .iter()
+            .map(|s| s.to_string_lossy().to_string())
+            .collect(),
+        status: process.status().into(),
+        cpu_usage: process.cpu_usage(),
+        memory_bytes: memory,
+        memory_percent,
+        start_time: process.start_time(),
+        start_timestamp: absolute_start_time(process.start_time(), boot_time),
+        run_time: process.run_time(),
+        user_id: process.user_id().map(|u| u.to_string()),
+        nice: unsafe {
+            // Clear errno
+            *libc::__errno_location() = 0;
+            let val = libc::getpriority(0, pid);
+            if val == -1 && *libc::__errno_location() != 0 {
+                0
+            } else {
+                val
+            }
+        },
+        open_fds: 0,
+        socket_count: 0,
+        cgroup: None,         // Filled in for the group representative
+        container_id: None,   // Filled in for the group representative
+        instance_count: None, // Will be set if grouped
+        pinned: false,
+    }
+}
+
+/// Merge processes sharing a grouping key into one row: CPU and fd counts are summed,
+/// and the lowest pid becomes the representative whose memory and start time are shown
+fn group_processes(processes: Vec<ProcessInfo>, key: GroupKey) -> Vec<ProcessInfo> {
+    let mut groups: HashMap<String, ProcessInfo> = HashMap::new();
+
+    for p in processes {
+        groups
+            .entry(key.key_for(&p))
+            .and_modify(|e| {
+                // CPU: sum usage
+                e.cpu_usage += p.cpu_usage;
+                // FDs: sum across instances
+                e.open_fds += p.open_fds;
+                e.socket_count += p.socket_count;
+                // Memory: keep existing (assuming main process/shared memory)
+                // Instance count: increment
+                e.instance_count = Some(e.instance_count.unwrap_or(1) + 1);
+                // Pinned: a group is pinned when any instance is
+                e.pinned |= p.pinned;
+
+                // Keep lowest PID as representative (usually main thread/process)
+                if p.pid < e.pid {
+                    e.pid = p.pid;
+                    e.memory_bytes = p.memory_bytes;
+                    e.memory_percent = p.memory_percent;
+                    e.start_time = p.start_time;
+                    e.start_timestamp = p.start_timestamp;
+                    e.user_id = p.user_id.clone();
+                }
+            })
+            .or_insert_with(|| {
+                let mut new_p = p.clone();
+                new_p.instance_count = Some(1);
+                new_p
+            });
+    }
+
+    groups.into_values().collect()
+}
+
+/// Name of a live process, or None if the pid doesn't exist
+/// Doesn't touch sysinfo, so it's cheap enough to re-check a pid right before a kill
+/// (the name guards against the pid having been recycled)
+pub fn process_name(pid: u32) -> Option<String> {
+    // 0 and values above i32::MAX would address process groups in kill()
+    if pid == 0 || pid > i32::MAX as u32 {
+        return None;
+    }
+    if let Ok(comm) = fs::read_to_string(format!("/proc/{}/comm", pid)) {
+        return Some(comm.trim().to_string());
+    }
+
+    // Without a readable /proc entry (hidepid, non-Linux) probe with signal 0;
+    // EPERM means the process exists but belongs to someone else
+    let alive = unsafe {
+        libc::kill(pid as libc::pid_t, 0) == 0 || *libc::__errno_location() == libc::EPERM
+    };
+    alive.then(String::new)
+}
+
+/// Command line and environment from /proc/<pid>/{cmdline,environ}
+/// With `redact_secrets`, secret-looking env values and flag arguments become "***"
+pub fn process_details(pid: u32, redact_secrets: bool) -> Result<ProcessDetails, MonitorError> {
+    let name = process_name(pid).ok_or(MonitorError::ProcessNotFound(pid))?;
+    let command = read_nul_separated(&format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    let environment = read_nul_separated(&format!("/proc/{}/environ", pid)).map(|vars| {
+        vars.iter()
+            .map(|var| match var.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (var.clone(), String::new()),
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let (command, environment) = if redact_secrets {
+        (redact_command(&command), environment.as_deref().map(redact_environment))
+    } else {
+        (command, environment)
+    };
+    let fd_limits = fs::read_to_string(format!("/proc/{}/limits", pid))
+        .ok()
+        .and_then(|limits| parse_fd_limits(&limits));
+
+    Ok(ProcessDetails {
+        pid,
+        name,
+        command,
+        environment,
+        fd_count: fs::read_dir(format!("/proc/{}/fd", pid))
+            .ok()
+            .map(|entries| entries.count() as u32),
+        fd_soft_limit: fd_limits.map(|(soft, _)| soft),
+        fd_hard_limit: fd_limits.map(|(_, hard)| hard),
+    })
+}
+
+/// (soft, hard) from the "Max open files" row of /proc/<pid>/limits
+/// Columns are fixed-width: "Max open files            1024                 524288    files"
+fn parse_fd_limits(limits: &str) -> Option<(u64, u64)> {
+    let row = limits.lines().find_map(|line| line.strip_prefix("Max open files"))?;
+    let mut values = row.split_whitespace().map(|value| match value {
+        "unlimited" => Some(u64::MAX),
+        _ => value.parse().ok(),
+    });
+    Some((values.next()??, values.next()??))
+}
+
+/// Read a NUL-separated /proc list (cmdline, environ); None if it can't be read
+fn read_nul_separated(path: &str) -> Option<Vec<String>> {
+    let data = fs::read(path).ok()?;
+    Some(
+        data.split(|b| *b == 0)
+            .filter(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).to_string())
+            .collect(),
+    )
+}
+
+/// Mask values of env vars whose name looks secret (GITHUB_TOKEN, DB_PASSWORD, ...)
+fn redact_environment(environment: &[(String, String)]) -> Vec<(String, String)> {
+    environment
+        .iter()
+        .map(|(key, value)| {
+            let value = if is_secret_name(key) { REDACTED.to_string() } else { value.clone() };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// Mask values of secret-looking flags, both "--password=x" and "--password x"
+fn redact_command(command: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(command.len());
+    let mut mask_next = false;
+    for arg in command {
+        if mask_next {
+            redacted.push(REDACTED.to_string());
+            mask_next = false;
+            continue;
+        }
+        let Some(flag) = arg.strip_prefix('-') else {
+            redacted.push(arg.clone());
+            continue;
+        };
+        match flag.split_once('=') {
+            Some((name, _)) if is_secret_name(name) => {
+                redacted.push(format!("{}={}", &arg[..=name.len()], REDACTED));
+            }
+            Some(_) => redacted.push(arg.clone()),
+            None => {
+                mask_next = is_secret_name(flag);
+                redacted.push(arg.clone());
+            }
+        }
+    }
+    redacted
+}
+
+/// Whether the name contains a secret part ("PGPASSWORD", "accessToken") or has a secret
+/// word ("AWS_ACCESS_KEY", "--api-key"). The shell's PWD is the working directory, not a
+/// password, so it's left alone
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    if name == "pwd" {
+        return false;
+    }
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+        || name.split(['_', '-']).any(|word| SECRET_NAME_WORDS.contains(&word))
+}
+
+/// Process start time as Unix seconds
+/// sysinfo already adds the boot time on Linux, but relative (seconds-since-boot) values
+/// are far below any plausible boot time, so those get it added here
+fn absolute_start_time(start_time: u64, boot_time: u64) -> u64 {
+    if start_time < boot_time {
+        boot_time + start_time
+    } else {
+        start_time
+    }
+}
+
+/// Convert per-core usage (100 = one busy core) into a share of the whole machine
+fn normalize_cpu_usage(usage: f32, logical_cores: usize) -> f32 {
+    usage / logical_cores.max(1) as f32
+}
+
+/// Count open file descriptors and how many of them are sockets
+/// Other users' fd directories need privilege, so unreadable ones count as (0, 0)
+fn count_fds(proc_root: &str, pid: u32) -> (u32, u32) {
+    let Ok(entries) = fs::read_dir(format!("{}/{}/fd", proc_root, pid)) else {
+        return (0, 0);
+    };
+
+    let mut open_fds = 0;
+    let mut socket_count = 0;
+    for entry in entries.flatten() {
+        open_fds += 1;
+        if fs::read_link(entry.path()).is_ok_and(|target| is_socket_link(&target)) {
+            socket_count += 1;
+        }
+    }
+    (open_fds, socket_count)
+}
+
+/// Read a process's cgroup path from <proc_root>/<pid>/cgroup
+fn read_cgroup_path(proc_root: &str, pid: u32) -> Option<String> {
+    fs::read_to_string(format!("{}/{}/cgroup", proc_root, pid))
+        .ok()
+        .and_then(|contents| parse_cgroup_path(&contents))
+}
+
+/// Pick the cgroup path out of /proc/<pid>/cgroup contents
+/// v2 has a single "0::<path>" line; v1 has one "id:controllers:<path>" line per
+/// hierarchy, where the systemd (or first non-root) hierarchy is the most descriptive
+fn parse_cgroup_path(contents: &str) -> Option<String> {
+    let entries: Vec<(&str, &str)> = contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let _id = parts.next()?;
+            Some((parts.next()?, parts.next()?))
+        })
+        .collect();
+
+    entries
+        .iter()
+        .find(|(controllers, _)| controllers.is_empty())
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|(controllers, _)| *controllers == "name=systemd")
+        })
+        .or_else(|| entries.iter().find(|(_, path)| *path != "/"))
+        .or_else(|| entries.first())
+        .map(|(_, path)| path.to_string())
+}
+
+/// Extract a 64-hex container id from a cgroup path
+/// Handles /docker/<id>, docker-<id>.scope, cri-containerd-<id>.scope and libpod-<id>.scope
+fn container_id_from_cgroup(path: &str) -> Option<String> {
+    path.rsplit('/').find_map(|segment| {
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = segment.rsplit(['-', ':']).next()?;
+        (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())).then(|| id.to_string())
+    })
+}
+
+/// fd symlinks for sockets point at "socket:[<inode>]"
+fn is_socket_link(target: &Path) -> bool {
+    target.to_string_lossy().starts_with("socket:[")
+}
+
 impl Default for ProcessMonitor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ScratchDir;
+
+    fn process(pid: u32, name: &str, exe_path: &str, user: &str, cpu_usage: f32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            parent_pid: Some(1),
+            name: name.to_string(),
+            exe_path: exe_path.to_string(),
+            command: vec![name.to_string()],
+            status: ProcStatus::Running,
+            cpu_usage,
+            memory_bytes: pid as u64 * 1024,
+            memory_percent: 0.0,
+            start_time: pid as u64,
+            start_timestamp: pid as u64,
+            run_time: 0,
+            user_id: Some(user.to_string()),
+            nice: 0,
+            open_fds: 2,
+            socket_count: 1,
+            cgroup: None,
+            container_id: None,
+            instance_count: None,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn counts_fds_and_classifies_socket_links() {
+        assert!(is_socket_link(Path::new("socket:[48213]")));
+        assert!(!is_socket_link(Path::new("pipe:[48214]")));
+        assert!(!is_socket_link(Path::new("anon_inode:[eventfd]")));
+        assert!(!is_socket_link(Path::new("/tmp/socket:[1]")));
+
+        let proc_root = ScratchDir::new("proc-fd");
+        let fd_dir = proc_root.join("4242/fd");
+        std::fs::create_dir_all(&fd_dir).unwrap();
+        for (fd, target) in [
+            ("0", "/dev/null"),
+            ("1", "pipe:[48214]"),
+            ("3", "socket:[48213]"),
+            ("4", "socket:[48215]"),
+            ("5", "anon_inode:[eventfd]"),
+        ] {
+            std::os::unix::fs::symlink(target, fd_dir.join(fd)).unwrap();
+        }
+        let proc_root = proc_root.to_str().unwrap();
+        assert_eq!(count_fds(proc_root, 4242), (5, 2));
+        // Unreadable (other user's) or vanished processes count as nothing open
+        assert_eq!(count_fds(proc_root, 4243), (0, 0));
+    }
+
+    #[test]
+    fn filters_by_name_command_line_and_minimums() {
+        let mut server = process(300, "python3", "/usr/bin/python3.12", "1000", 10.0);
+        server.command = ["python3", "-m", "http.server", "8080"].map(String::from).to_vec();
+        let everything = ProcessQuery::default();
+
+        assert!(passes_filters(&server, None, &everything));
+        assert!(passes_filters(&server, Some("pyth"), &everything));
+        // Matches anywhere in the joined command line
+        assert!(passes_filters(&server, Some("http.server 8080"), &everything));
+        assert!(!passes_filters(&server, Some("nginx"), &everything));
+
+        // memory_bytes is pid * 1024 in the fixture
+        let at_least = |min_cpu: Option<f32>, min_memory: Option<u64>| ProcessQuery {
+            min_cpu,
+            min_memory,
+            ..Default::default()
+        };
+        assert!(passes_filters(&server, None, &at_least(Some(10.0), None)));
+        assert!(!passes_filters(&server, None, &at_least(Some(10.5), None)));
+        assert!(passes_filters(&server, None, &at_least(None, Some(300 * 1024))));
+        assert!(!passes_filters(&server, None, &at_least(None, Some(300 * 1024 + 1))));
+        // Both must hold, along with the text filter
+        assert!(!passes_filters(&server, Some("python"), &at_least(Some(1.0), Some(u64::MAX))));
+    }
+
+    #[test]
+    fn parses_cgroup_v1_and_v2_files() {
+        let id = "4f8a1c2b3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8";
+
+        // v2: one unified line
+        let v2_docker = format!("0::/system.slice/docker-{}.scope\n", id);
+        let path = parse_cgroup_path(&v2_docker).unwrap();
+        assert_eq!(path, format!("/system.slice/docker-{}.scope", id));
+        assert_eq!(container_id_from_cgroup(&path).as_deref(), Some(id));
+
+        let v2_host = "0::/user.slice/user-1000.slice/session-2.scope\n";
+        let path = parse_cgroup_path(v2_host).unwrap();
+        assert_eq!(path, "/user.slice/user-1000.slice/session-2.scope");
+        assert_eq!(container_id_from_cgroup(&path), None);
+        assert_eq!(container_id_from_cgroup(&parse_cgroup_path("0::/\n").unwrap()), None);
+
+        // v1: one line per hierarchy; the systemd one wins
+        let v1_docker = format!(
+            "12:cpuset:/docker/{id}\n\
+             11:memory:/docker/{id}\n\
+             4:cpu,cpuacct:/docker/{id}\n\
+             1:name=systemd:/docker/{id}\n",
+            id = id
+        );
+        let path = parse_cgroup_path(&v1_docker).unwrap();
+        assert_eq!(path, format!("/docker/{}", id));
+        assert_eq!(container_id_from_cgroup(&path).as_deref(), Some(id));
+
+        // v1 without a systemd hierarchy: the first non-root path
+        let v1_pod = format!(
+            "3:blkio:/\n2:memory:/kubepods/besteffort/pod1234/cri-containerd-{}.scope\n1:cpu:/\n",
+            id
+        );
+        let path = parse_cgroup_path(&v1_pod).unwrap();
+        assert!(path.starts_with("/kubepods/besteffort/pod1234/"));
+        assert_eq!(container_id_from_cgroup(&path).as_deref(), Some(id));
+
+        // Hybrid hosts list both; the unified line is used
+        let hybrid = "1:name=systemd:/user.slice\n0::/user.slice/app.slice\n";
+        assert_eq!(parse_cgroup_path(hybrid).as_deref(), Some("/user.slice/app.slice"));
+        assert_eq!(parse_cgroup_path(""), None);
+
+        let proc_root = ScratchDir::new("proc-cgroup");
+        proc_root.write("77/cgroup", &v2_docker);
+        let proc_root = proc_root.to_str().unwrap();
+        assert_eq!(
+            read_cgroup_path(proc_root, 77),
+            Some(format!("/system.slice/docker-{}.scope", id))
+        );
+        assert_eq!(read_cgroup_path(proc_root, 78), None);
+    }
+
+    #[test]
+    fn normalizes_cpu_usage_by_core_count() {
+        // One fully busy thread
+        assert_eq!(normalize_cpu_usage(100.0, 4), 25.0);
+        assert_eq!(normalize_cpu_usage(100.0, 16), 6.25);
+        // Every core of an 8-core machine busy
+        assert_eq!(normalize_cpu_usage(800.0, 8), 100.0);
+        assert_eq!(normalize_cpu_usage(100.0, 1), 100.0);
+        // No CPU list (failed sysinfo refresh) leaves the value as is
+        assert_eq!(normalize_cpu_usage(42.0, 0), 42.0);
+    }
+
+    #[test]
+    fn start_times_become_unix_timestamps() {
+        let boot_time = 1_760_000_000;
+        // Seconds since boot get the boot time added
+        assert_eq!(absolute_start_time(3_600, boot_time), 1_760_003_600);
+        assert_eq!(absolute_start_time(0, boot_time), boot_time);
+        // Values sysinfo already made absolute are kept
+        assert_eq!(absolute_start_time(1_760_000_042, boot_time), 1_760_000_042);
+        assert_eq!(absolute_start_time(boot_time, boot_time), boot_time);
+    }
+
+    #[test]
+    fn kill_by_name_signals_every_match_but_itself() {
+        let processes = [
+            (100, "chrome"),
+            (101, "Chrome"),
+            (102, "chrome_crashpad"),
+            (103, "chrome"),
+            (104, "chrome"),
+            (900, "chrome"), // The monitor itself
+            (200, "firefox"),
+        ];
+        let mut signalled = Vec::new();
+        let result = kill_matching(processes, "CHROME", 900, |pid| {
+            signalled.push(pid);
+            match pid {
+                103 => Some(false), // Owned by another user
+                104 => None,
+                _ => Some(true),
+            }
+        });
+        assert_eq!(signalled, [100, 101, 103, 104]);
+        assert_eq!((result.matched, result.killed), (4, 2));
+        assert_eq!(result.failures.iter().map(|(pid, _)| *pid).collect::<Vec<_>>(), [103, 104]);
+
+        let none = kill_matching(processes, "slack", 900, |_| unreachable!());
+        assert_eq!((none.matched, none.killed), (0, 0));
+
+        assert_eq!(validate_kill_name("  chrome ").unwrap(), "chrome");
+        for refused in ["", "   ", "systemd", "INIT", "lsass.exe"] {
+            assert!(
+                matches!(validate_kill_name(refused), Err(MonitorError::PermissionDenied(_))),
+                "{:?}",
+                refused
+            );
+        }
+    }
+
+    #[test]
+    fn process_name_checks_a_pid_without_sysinfo() {
+        let own_name = std::fs::read_to_string("/proc/self/comm").unwrap();
+        assert_eq!(process_name(std::process::id()), Some(own_name.trim().to_string()));
+        // Above the kernel's pid_max, so it can't exist
+        assert_eq!(process_name(4_194_305), None);
+        // kill() would treat these as process groups
+        assert_eq!(process_name(0), None);
+        assert_eq!(process_name(u32::MAX), None);
+    }
+
+    #[test]
+    fn secret_names() {
+        for name in [
+            "PGPASSWORD", "MYSQL_PWD", "GITHUBTOKEN", "accessToken", "dbPassword", "API_KEY",
+            "api-key", "APIKEY", "AWS_SECRET_ACCESS_KEY", "GIT_CREDENTIALS", "passwd",
+        ] {
+            assert!(is_secret_name(name), "{} should be secret", name);
+        }
+        for name in ["KEYBOARD", "monkey", "PATH", "PWD", "OLDPWD", "HOME", "LANG"] {
+            assert!(!is_secret_name(name), "{} should not be secret", name);
+        }
+    }
+
+    #[test]
+    fn redacts_secret_env_values() {
+        let env = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        assert_eq!(
+            redact_environment(&env(&[("HOME", "/root"), ("GITHUB_TOKEN", "ghp_x")])),
+            env(&[("HOME", "/root"), ("GITHUB_TOKEN", REDACTED)])
+        );
+    }
+
+    #[test]
+    fn redacts_positional_and_assigned_flags() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|a| a.to_string()).collect() };
+        assert_eq!(
+            redact_command(&args(&["psql", "--password=hunter2", "-U", "admin"])),
+            args(&["psql", "--password=***", "-U", "admin"])
+        );
+        assert_eq!(
+            redact_command(&args(&["app", "--api-key", "abc123", "--verbose", "file"])),
+            args(&["app", "--api-key", "***", "--verbose", "file"])
+        );
+        // Positional arguments are never secrets on their own
+        assert_eq!(redact_command(&args(&["echo", "password"])), args(&["echo", "password"]));
+    }
+
+    fn fixture_processes() -> Vec<ProcessInfo> {
+        vec![
+            process(300, "python3", "/usr/bin/python3.12", "1000", 10.0),
+            process(120, "python3", "/opt/venv/bin/python3", "1000", 5.0),
+            process(250, "python3", "/usr/bin/python3.12", "0", 1.5),
+            process(2, "kworker/0:1", "", "0", 0.5),
+        ]
+    }
+
+    fn groups_by_key(groups: Vec<ProcessInfo>) -> Vec<(u32, Option<u32>, f32)> {
+        let mut rows: Vec<_> = groups
+            .into_iter()
+            .map(|p| (p.pid, p.instance_count, p.cpu_usage))
+            .collect();
+        rows.sort_by_key(|(pid, _, _)| *pid);
+        rows
+    }
+
+    #[test]
+    fn groups_by_name_with_the_lowest_pid_as_representative() {
+        let groups = group_processes(fixture_processes(), GroupKey::Name);
+        let python = groups.iter().find(|p| p.name == "python3").unwrap();
+        assert_eq!(python.pid, 120);
+        assert_eq!(python.instance_count, Some(3));
+        assert_eq!(python.cpu_usage, 16.5);
+        assert_eq!(python.open_fds, 6);
+        assert_eq!(python.socket_count, 3);
+        // Memory and start time belong to the representative, not the sum
+        assert_eq!(python.memory_bytes, 120 * 1024);
+        assert_eq!(python.start_timestamp, 120);
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn group_keys_split_rows_differently() {
+        assert_eq!(
+            groups_by_key(group_processes(fixture_processes(), GroupKey::ExePath)),
+            // The kernel thread has no exe and keeps its own row by name
+            [(2, Some(1), 0.5), (120, Some(1), 5.0), (250, Some(2), 11.5)]
+        );
+        assert_eq!(
+            groups_by_key(group_processes(fixture_processes(), GroupKey::User)),
+            [(2, Some(2), 2.0), (120, Some(2), 15.0)]
+        );
+        assert_eq!(
+            groups_by_key(group_processes(fixture_processes(), GroupKey::None)),
+            [(2, Some(1), 0.5), (120, Some(1), 5.0), (250, Some(1), 1.5), (300, Some(1), 10.0)]
+        );
+    }
+
+    #[test]
+    fn diffs_pid_sets_in_ascending_order() {
+        let previous: HashSet<u32> = [1, 50, 40, 7].into_iter().collect();
+        let current: HashSet<u32> = [1, 7, 90, 60, 3].into_iter().collect();
+        assert_eq!(diff_pids(&previous, &current), (vec![3, 60, 90], vec![40, 50]));
+        assert_eq!(diff_pids(&current, &current), (vec![], vec![]));
+        assert_eq!(diff_pids(&HashSet::new(), &previous).0, [1, 7, 40, 50]);
+    }
+
+    #[test]
+    fn first_take_changes_only_records_a_baseline() {
+        let monitor = ProcessMonitor::new();
+        // Nothing sampled yet
+        assert!(monitor.take_changes().started.is_empty());
+
+        monitor.refresh(&ProcessQuery::default());
+        let changes = monitor.take_changes();
+        assert!(changes.started.is_empty());
+        assert!(changes.exited.is_empty());
+    }
+
+    #[test]
+    fn pinned_processes_bypass_filters() {
+        let own_pid = std::process::id();
+        let monitor = ProcessMonitor::new();
+        // A pid above pid_max can't exist, so its pin is dropped on refresh
+        monitor.set_pinned(vec![own_pid, 4_194_305]);
+
+        let query = ProcessQuery {
+            filter: Some("no-such-process-name".to_string()),
+            min_cpu: Some(1000.0),
+            min_memory: Some(u64::MAX),
+            group_by: GroupKey::None,
+            ..Default::default()
+        };
+        let list = monitor.refresh(&query);
+        assert_eq!(list.total_count, 1);
+        assert_eq!(list.processes[0].pid, own_pid);
+        assert!(list.processes[0].pinned);
+        assert_eq!(*monitor.pinned.read().unwrap(), HashSet::from([own_pid]));
+
+        // Pins are namespace PIDs, so a host listing filters the process like any other
+        let list = monitor.refresh(&ProcessQuery {
+            scope: ProcessScope::Host,
+            ..query
+        });
+        assert_eq!(list.total_count, 0);
+    }
+
+    #[test]
+    fn top_returns_the_n_busiest_sorted() {
+        let monitor = ProcessMonitor::new();
+        let by_memory = monitor.top(3, SortKey::Memory);
+        assert_eq!(by_memory.len(), 3);
+        assert!(by_memory.windows(2).all(|pair| pair[0].memory_bytes >= pair[1].memory_bytes));
+        assert!(by_memory.iter().all(|p| p.instance_count.is_none()));
+
+        // Nothing outside the top 3 uses more memory than the third entry
+        let everything = monitor.top(usize::MAX, SortKey::Memory);
+        assert!(everything.len() >= 3);
+        assert!(everything[3..].iter().all(|p| p.memory_bytes <= by_memory[2].memory_bytes));
+
+        let by_cpu = monitor.top(2, SortKey::Cpu);
+        assert_eq!(by_cpu.len(), 2);
+        assert!(by_cpu[0].cpu_usage >= by_cpu[1].cpu_usage);
+        assert!(monitor.top(0, SortKey::Cpu).is_empty());
+    }
+
+    #[test]
+    fn parses_max_open_files_limits() {
+        let limits = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+Max locked memory         8388608              8388608              bytes
+";
+        assert_eq!(parse_fd_limits(limits), Some((1024, 524288)));
+        assert_eq!(
+            parse_fd_limits("Max open files  unlimited  unlimited  files\n"),
+            Some((u64::MAX, u64::MAX))
+        );
+        assert_eq!(parse_fd_limits("Max cpu time  unlimited  unlimited  seconds\n"), None);
+        assert_eq!(parse_fd_limits("Max open files  1024\n"), None);
+    }
+
+    #[test]
+    fn proc_root_follows_scope() {
+        let scratch = ScratchDir::new("host-proc");
+        let host_proc = scratch.to_str().unwrap();
+
+        assert_eq!(ProcessScope::Namespace.proc_root_with(host_proc), "/proc");
+        assert_eq!(ProcessScope::Host.proc_root_with(host_proc), host_proc);
+        assert_eq!(ProcessScope::Host.proc_root_with("/nonexistent/host/proc"), "/proc");
+    }
+
+    #[test]
+    fn serializes_camel_case_field_names() {
+        let nginx = process(42, "nginx", "/usr/sbin/nginx", "33", 1.0);
+        let json = serde_json::to_value(nginx).unwrap();
+        for key in [
+            "parentPid",
+            "exePath",
+            "cpuUsage",
+            "memoryBytes",
+            "startTimestamp",
+            "userId",
+            "openFds",
+            "socketCount",
+            "containerId",
+            "instanceCount",
+        ] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+        assert!(json.as_object().unwrap().keys().all(|key| !key.contains('_')));
+    }
+}
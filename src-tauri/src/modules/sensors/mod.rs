@@ -1,10 +1,25 @@
 // Sensors Monitoring Module
 // Provides temperature, fan speed, and sensor readings from hardware
 
+#[cfg(target_os = "macos")]
+mod smc;
+#[cfg(windows)]
+mod wmi;
+
 use serde::{Deserialize, Serialize};
 use sysinfo::Components;
-use std::sync::RwLock;
-use std::time::{Duration, Instant};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(windows)]
+use crate::modules::{CommandRunner, SystemCommandRunner};
+use crate::modules::{
+    gpu::{GpuMonitor, GpusInfo},
+    LockRecover, TemperatureUnit,
+};
 
 /// Sensor reading type
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,23 +31,129 @@ pub enum SensorType {
     Unknown,
 }
 
+impl SensorType {
+    /// Display unit for readings of this type
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorType::Temperature => "\u{00B0}C",
+            SensorType::Fan => "RPM",
+            SensorType::Voltage => "V",
+            SensorType::Power => "W",
+            SensorType::Unknown => "",
+        }
+    }
+}
+
 /// Individual sensor reading
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SensorReading {
     pub label: String,
+    pub chip: Option<String>, // hwmon chip name (e.g. "nct6798") when the source has one
     pub sensor_type: SensorType,
     pub value: f32,
     pub max_value: Option<f32>,
     pub critical_value: Option<f32>,
     pub unit: String,
+    pub session_min: f32, // Lowest value seen since startup
+    pub session_max: f32, // Highest value seen since startup
 }
 
-/// All sensor readings
+/// Recorded readings for one sensor
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorHistory {
+    pub sensor_type: SensorType,
+    pub unit: String,
+    pub min: f32,
+    pub max: f32,
+    pub samples: Vec<(u64, f32)>, // (timestamp ms, value), oldest first
+}
+
+impl SensorHistory {
+    /// Render a temperature series in the given unit; other sensor types are left alone
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        if !matches!(self.sensor_type, SensorType::Temperature) {
+            return self;
+        }
+        self.min = unit.convert(self.min);
+        self.max = unit.convert(self.max);
+        for (_, value) in &mut self.samples {
+            *value = unit.convert(*value);
+        }
+        self.unit = unit.symbol().to_string();
+        self
+    }
+}
+
+/// Running min/max and recent samples for one sensor
+struct SensorTrack {
+    sensor_type: SensorType,
+    min: f32,
+    max: f32,
+    samples: VecDeque<(u64, f32)>,
+}
+
+/// All sensor readings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SensorsInfo {
     pub sensors: Vec<SensorReading>,
     pub cpu_temp: Option<f32>,
     pub gpu_temp: Option<f32>,
+    pub gpu_utilization: Option<u32>,    // Percent, busiest GPU; None without a GPU monitor
+    pub gpu_memory_percent: Option<u32>, // Percent of VRAM used on that same GPU
+    pub errors: Vec<String>,
+}
+
+impl SensorsInfo {
+    /// Fold the GPU monitor's view into the summary: raise gpu_temp to the hottest sensor
+    /// (NVML has no hwmon) and report load and VRAM use of the busiest GPU
+    pub fn merge_gpu_info(&mut self, gpus: &GpusInfo) {
+        let hottest = gpus.gpus.iter().map(|g| g.max_temperature()).max();
+        if let Some(hottest) = hottest.filter(|t| *t > 0) {
+            let hottest = hottest as f32;
+            self.gpu_temp = Some(self.gpu_temp.map_or(hottest, |t| t.max(hottest)));
+        }
+
+        if let Some(busiest) = gpus.gpus.iter().max_by_key(|g| g.utilization_gpu) {
+            self.gpu_utilization = Some(busiest.utilization_gpu);
+            self.gpu_memory_percent = (busiest.memory_total > 0).then(|| {
+                (busiest.memory_used as f64 / busiest.memory_total as f64 * 100.0).round() as u32
+            });
+        }
+    }
+
+    /// Render temperatures (readings, cpu_temp, gpu_temp) in the given unit
+    pub fn in_unit(mut self, unit: TemperatureUnit) -> Self {
+        if unit == TemperatureUnit::Celsius {
+            return self;
+        }
+
+        for sensor in self
+            .sensors
+            .iter_mut()
+            .filter(|s| matches!(s.sensor_type, SensorType::Temperature))
+        {
+            sensor.value = unit.convert(sensor.value);
+            sensor.max_value = sensor.max_value.map(|v| unit.convert(v));
+            sensor.critical_value = sensor.critical_value.map(|v| unit.convert(v));
+            sensor.session_min = unit.convert(sensor.session_min);
+            sensor.session_max = unit.convert(sensor.session_max);
+            sensor.unit = unit.symbol().to_string();
+        }
+        self.cpu_temp = self.cpu_temp.map(|t| unit.convert(t));
+        self.gpu_temp = self.gpu_temp.map(|t| unit.convert(t));
+        self
+    }
+}
+
+/// Every reading of one hwmon chip, uncurated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HwmonChip {
+    pub name: String, // hwmon "name" attribute, e.g. "nct6798", "nvme"
+    pub readings: Vec<SensorReading>,
 }
 
 /// Cached sensor data to prevent frequent rescanning
@@ -45,25 +166,107 @@ struct SensorCache {
 pub struct SensorsMonitor {
     components: RwLock<Option<Components>>,
     cache: RwLock<Option<SensorCache>>,
+    history: RwLock<HashMap<String, SensorTrack>>,
+    gpu: Option<Arc<GpuMonitor>>, // Source of the GPU summary; only its cached data is read
+    #[cfg(windows)]
+    runner: Box<dyn CommandRunner>, // Runs the PowerShell WMI query
 }
 
 // Minimum time between full sensor refreshes (2 seconds)
 const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
 
+// Number of samples kept per sensor (60 refreshes = 2 minutes at the cache interval)
+const HISTORY_LEN: usize = 60;
+
 impl SensorsMonitor {
     pub fn new() -> Self {
         // Don't initialize components here - do it lazily on first refresh
         Self {
             components: RwLock::new(None),
             cache: RwLock::new(None),
+            history: RwLock::new(HashMap::new()),
+            gpu: None,
+            #[cfg(windows)]
+            runner: Box::new(SystemCommandRunner),
+        }
+    }
+
+    /// Create a monitor that runs the WMI query through the given runner
+    #[cfg(windows)]
+    pub fn with_runner(runner: Box<dyn CommandRunner>) -> Self {
+        Self {
+            runner,
+            ..Self::new()
+        }
+    }
+
+    /// Fill the GPU summary from the GPU monitor's last refresh
+    /// Reading its cache instead of refreshing it keeps GPU history at one sample per tick
+    pub fn with_gpu(gpu: Arc<GpuMonitor>) -> Self {
+        Self {
+            gpu: Some(gpu),
+            ..Self::new()
+        }
+    }
+
+    /// Per-sensor min/max and recent samples, keyed as by `history_keys`
+    pub fn history(&self) -> HashMap<String, SensorHistory> {
+        self.history
+            .read_or_recover("Sensors history")
+            .iter()
+            .map(|(label, track)| {
+                (label.clone(), SensorHistory {
+                    sensor_type: track.sensor_type.clone(),
+                    unit: track.sensor_type.unit().to_string(),
+                    min: track.min,
+                    max: track.max,
+                    samples: track.samples.iter().copied().collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// Record fresh readings and fill in their session min/max
+    /// Only called on a real rescan, so the 2s cache also bounds the sampling rate
+    fn record_history(&self, sensors: &mut [SensorReading]) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let keys = history_keys(sensors);
+        let mut history = self.history.write_or_recover("Sensors history");
+        for (sensor, key) in sensors.iter_mut().zip(keys) {
+            let track = history.entry(key).or_insert_with(|| SensorTrack {
+                sensor_type: sensor.sensor_type.clone(),
+                min: sensor.value,
+                max: sensor.value,
+                samples: VecDeque::with_capacity(HISTORY_LEN),
+            });
+            track.min = track.min.min(sensor.value);
+            track.max = track.max.max(sensor.value);
+            if track.samples.len() >= HISTORY_LEN {
+                track.samples.pop_front();
+            }
+            track.samples.push_back((timestamp, sensor.value));
+
+            sensor.session_min = track.min;
+            sensor.session_max = track.max;
         }
     }
 
     pub fn refresh(&self) -> SensorsInfo {
+        let mut info = self.read_sensors();
+        if let Some(gpus) = self.gpu.as_ref().and_then(|gpu| gpu.cached()) {
+            info.merge_gpu_info(&gpus);
+        }
+        info
+    }
+
+    fn read_sensors(&self) -> SensorsInfo {
         // Check cache first
         {
-            let cache = self.cache.read()
-                .expect("Sensors cache RwLock poisoned");
+            let cache = self.cache.read_or_recover("Sensors cache");
             if let Some(ref cached) = *cache {
                 if cached.last_update.elapsed() < MIN_REFRESH_INTERVAL {
                     return cached.data.clone();
@@ -72,8 +275,7 @@ impl SensorsMonitor {
         }
 
         // Need to refresh - get write lock
-        let mut components_guard = self.components.write()
-            .expect("Sensors monitor RwLock poisoned");
+        let mut components_guard = self.components.write_or_recover("Sensors monitor");
         
         // Initialize components lazily if needed
         if components_guard.is_none() {
@@ -109,38 +311,65 @@ impl SensorsMonitor {
                 cpu_temp = Some(temperature);
             }
             
-            // GPU temperature detection
-            if gpu_temp.is_none() && (
-                label_lower.contains("gpu") ||
+            // GPU temperature detection - keep the hottest GPU sensor (hotspot/memory
+            // usually run hotter than edge and throttle first)
+            let is_gpu_sensor = label_lower.contains("gpu") ||
                 label_lower.contains("edge") ||
                 label_lower.contains("junction") ||
                 label_lower.contains("radeon") ||
                 label_lower.contains("nvidia") ||
-                label_lower.contains("amdgpu")
-            ) {
-                gpu_temp = Some(temperature);
+                label_lower.contains("amdgpu");
+            if is_gpu_sensor {
+                gpu_temp = Some(gpu_temp.map_or(temperature, |t: f32| t.max(temperature)));
             }
 
+            let sensor_type = SensorType::Temperature;
+            // sysinfo already prefixes the label with the chip name
             sensors.push(SensorReading {
                 label,
-                sensor_type: SensorType::Temperature,
+                chip: None,
                 value: temperature,
                 max_value: Some(max),
                 critical_value: critical,
-                unit: "°C".to_string(),
+                unit: sensor_type.unit().to_string(),
+                sensor_type,
+                session_min: temperature,
+                session_max: temperature,
             });
         }
 
+        // Fans aren't exposed through sysinfo, so they come straight from hwmon (or the SMC)
+        sensors.extend(read_fan_sensors());
+        #[cfg(target_os = "macos")]
+        sensors.extend(smc::read_smc_fans());
+
+        let mut errors = Vec::new();
+
+        // sysinfo finds few or no components on Windows; WMI fills the gap
+        #[cfg(windows)]
+        {
+            let wmi = wmi::read_wmi_sensors(self.runner.as_ref(), &mut errors);
+            cpu_temp = cpu_temp.or(wmi.cpu_temp);
+            sensors.extend(wmi.readings);
+        }
+
+        if sensors.is_empty() && errors.is_empty() {
+            errors.push("Sensors: No sensor backend available on this system".to_string());
+        }
+        self.record_history(&mut sensors);
+
         let result = SensorsInfo {
             sensors,
             cpu_temp,
             gpu_temp,
+            gpu_utilization: None,
+            gpu_memory_percent: None,
+            errors,
         };
 
         // Update cache
         {
-            let mut cache = self.cache.write()
-                .expect("Sensors cache RwLock poisoned");
+            let mut cache = self.cache.write_or_recover("Sensors cache");
             *cache = Some(SensorCache {
                 data: result.clone(),
                 last_update: Instant::now(),
@@ -151,8 +380,363 @@ impl SensorsMonitor {
     }
 }
 
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+// hwmon channel kinds: (file prefix, type, divisor from the raw sysfs unit)
+// temp: millidegrees C, fan: RPM, in: millivolts, power: microwatts
+const HWMON_CHANNELS: [(&str, SensorType, f32); 4] = [
+    ("temp", SensorType::Temperature, 1000.0),
+    ("fan", SensorType::Fan, 1.0),
+    ("in", SensorType::Voltage, 1000.0),
+    ("power", SensorType::Power, 1_000_000.0),
+];
+
+/// Read fan speeds from every hwmon device (fanN_input files)
+/// Labels come from the adjacent fanN_label, falling back to "fanN"
+pub fn read_fan_sensors() -> Vec<SensorReading> {
+    read_fan_sensors_in(Path::new(HWMON_PATH))
+}
+
+fn read_fan_sensors_in(hwmon_root: &Path) -> Vec<SensorReading> {
+    let Ok(hwmons) = fs::read_dir(hwmon_root) else {
+        return Vec::new();
+    };
+
+    hwmons
+        .flatten()
+        .flat_map(|hwmon| read_hwmon_channels(&hwmon.path(), "fan", SensorType::Fan, 1.0))
+        .collect()
+}
+
+/// Raw dump of every hwmon chip with all temp/fan/in/power channels
+/// Complements the curated SensorsMonitor::refresh, which misses chipset/VRM/NVMe sensors
+pub fn read_all_hwmon() -> Vec<HwmonChip> {
+    read_all_hwmon_in(Path::new(HWMON_PATH))
+}
+
+fn read_all_hwmon_in(hwmon_root: &Path) -> Vec<HwmonChip> {
+    let Ok(hwmons) = fs::read_dir(hwmon_root) else {
+        return Vec::new();
+    };
+
+    let mut dirs: Vec<_> = hwmons.flatten().map(|e| e.path()).collect();
+    dirs.sort();
+
+    dirs.iter()
+        .map(|dir| HwmonChip {
+            name: hwmon_name(dir),
+            readings: HWMON_CHANNELS
+                .iter()
+                .flat_map(|(prefix, sensor_type, divisor)| {
+                    read_hwmon_channels(dir, prefix, sensor_type.clone(), *divisor)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// hwmon "name" attribute, falling back to the directory name (hwmonN)
+fn hwmon_name(dir: &Path) -> String {
+    fs::read_to_string(dir.join("name"))
+        .map(|n| n.trim().to_string())
+        .unwrap_or_else(|_| dir.file_name().unwrap_or_default().to_string_lossy().to_string())
+}
+
+/// Read every `<prefix>N` channel of one hwmon directory, in channel order
+/// Numbering can be sparse (temp1, temp3), so channels come from the files present.
+/// Labels come from `<prefix>N_label`, falling back to "<prefix>N"; max/crit are optional.
+fn read_hwmon_channels(
+    dir: &Path,
+    prefix: &str,
+    sensor_type: SensorType,
+    divisor: f32,
+) -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let chip = hwmon_name(dir);
+
+    // Power meters without an instantaneous reading only expose powerN_average
+    let mut channels: Vec<(u32, bool, String)> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter_map(|name| {
+            let channel = name
+                .strip_suffix("_input")
+                .or_else(|| name.strip_suffix("_average").filter(|_| prefix == "power"))?;
+            let number = channel.strip_prefix(prefix)?.parse::<u32>().ok()?;
+            Some((number, name.ends_with("_average"), name))
+        })
+        .collect();
+    // Sorting puts _input ahead of _average, so it wins the dedup
+    channels.sort();
+    channels.dedup_by_key(|(number, _, _)| *number);
+
+    channels
+        .into_iter()
+        .filter_map(|(number, _, input)| {
+            let channel = format!("{}{}", prefix, number);
+            let read_value = |file: &str| -> Option<f32> {
+                let raw: f32 = fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()?;
+                Some(raw / divisor)
+            };
+
+            let value = read_value(&input)?;
+            let label = fs::read_to_string(dir.join(format!("{}_label", channel)))
+                .map(|l| l.trim().to_string())
+                .ok()
+                .filter(|l| !l.is_empty())
+                .unwrap_or_else(|| channel.clone());
+
+            Some(SensorReading {
+                label,
+                chip: Some(chip.clone()),
+                value,
+                max_value: read_value(&format!("{}_max", channel)),
+                critical_value: read_value(&format!("{}_crit", channel)),
+                unit: sensor_type.unit().to_string(),
+                sensor_type: sensor_type.clone(),
+                session_min: value,
+                session_max: value,
+            })
+        })
+        .collect()
+}
+
+/// History key of each reading: "<chip>/<label>", or the label alone without a chip.
+/// Identical chips (two NVMe drives) still repeat a key, so later repeats within one
+/// refresh get " #2", " #3", ... in scan order, which is stable between refreshes.
+fn history_keys(sensors: &[SensorReading]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    sensors
+        .iter()
+        .map(|sensor| {
+            let key = match &sensor.chip {
+                Some(chip) => format!("{}/{}", chip, sensor.label),
+                None => sensor.label.clone(),
+            };
+            let count = seen.entry(key.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                key
+            } else {
+                format!("{} #{}", key, count)
+            }
+        })
+        .collect()
+}
+
 impl Default for SensorsMonitor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::gpu::GpuInfo;
+    use crate::modules::ScratchDir;
+
+    fn reading(chip: Option<&str>, label: &str, value: f32) -> SensorReading {
+        let sensor_type = SensorType::Fan;
+        SensorReading {
+            label: label.to_string(),
+            chip: chip.map(str::to_string),
+            unit: sensor_type.unit().to_string(),
+            sensor_type,
+            value,
+            max_value: None,
+            critical_value: None,
+            session_min: value,
+            session_max: value,
+        }
+    }
+
+    #[test]
+    fn history_is_keyed_by_chip_and_label() {
+        let sensors = [
+            reading(Some("nct6798"), "fan1", 900.0),
+            reading(Some("amdgpu"), "fan1", 1500.0),
+            reading(None, "nvme Composite", 40.0),
+            reading(None, "nvme Composite", 45.0),
+        ];
+        assert_eq!(
+            history_keys(&sensors),
+            ["nct6798/fan1", "amdgpu/fan1", "nvme Composite", "nvme Composite #2"]
+        );
+
+        let monitor = SensorsMonitor::new();
+        let mut sensors = sensors.to_vec();
+        monitor.record_history(&mut sensors);
+        sensors[0].value = 700.0;
+        monitor.record_history(&mut sensors);
+
+        let history = monitor.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history["nct6798/fan1"].min, 700.0);
+        assert_eq!(history["amdgpu/fan1"].min, 1500.0);
+        assert_eq!(history["amdgpu/fan1"].samples.len(), 2);
+        assert_eq!(sensors[0].session_max, 900.0);
+    }
+
+    #[test]
+    fn temperature_unit_is_degree_sign_and_c() {
+        assert_eq!(SensorType::Temperature.unit().as_bytes(), [0xC2, 0xB0, b'C']);
+        assert_eq!(SensorType::Fan.unit(), "RPM");
+    }
+
+    #[test]
+    fn reads_fans_from_a_fake_hwmon_tree() {
+        let root = ScratchDir::new("sensors-fans");
+        root.write("hwmon0/name", "k10temp\n");
+        root.write("hwmon0/temp1_input", "45000\n");
+        root.write("hwmon1/name", "nct6798\n");
+        root.write("hwmon1/fan1_input", "1200\n");
+        root.write("hwmon1/fan1_label", "CPU Fan\n");
+        root.write("hwmon1/fan2_input", "850\n");
+        root.write("hwmon1/fan2_max", "2000\n");
+        // A header with its tach disconnected reads garbage and is skipped
+        root.write("hwmon1/fan3_input", "\n");
+
+        let mut fans = read_fan_sensors_in(&root);
+        fans.sort_by(|a, b| a.label.cmp(&b.label));
+        let summary: Vec<(&str, f32, Option<f32>)> = fans
+            .iter()
+            .map(|f| (f.label.as_str(), f.value, f.max_value))
+            .collect();
+        assert_eq!(summary, [("CPU Fan", 1200.0, None), ("fan2", 850.0, Some(2000.0))]);
+        assert!(fans.iter().all(|f| f.unit == "RPM" && f.chip.as_deref() == Some("nct6798")));
+        assert!(fans.iter().all(|f| matches!(f.sensor_type, SensorType::Fan)));
+
+        assert!(read_fan_sensors_in(&root.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn renders_temperatures_in_either_unit() {
+        let cpu = SensorReading {
+            label: "Package id 0".to_string(),
+            chip: Some("coretemp".to_string()),
+            sensor_type: SensorType::Temperature,
+            value: 50.0,
+            max_value: Some(80.0),
+            critical_value: Some(100.0),
+            unit: SensorType::Temperature.unit().to_string(),
+            session_min: 35.0,
+            session_max: 65.0,
+        };
+        let info = SensorsInfo {
+            sensors: vec![cpu, reading(Some("nct6798"), "fan1", 900.0)],
+            cpu_temp: Some(50.0),
+            gpu_temp: Some(0.0),
+            ..Default::default()
+        };
+
+        let celsius = info.clone().in_unit(TemperatureUnit::Celsius);
+        assert_eq!(celsius.sensors[0].value, 50.0);
+        assert_eq!(celsius.sensors[0].unit, "\u{00B0}C");
+
+        let fahrenheit = info.in_unit(TemperatureUnit::Fahrenheit);
+        let cpu = &fahrenheit.sensors[0];
+        assert_eq!(
+            (cpu.value, cpu.max_value, cpu.critical_value),
+            (122.0, Some(176.0), Some(212.0))
+        );
+        assert_eq!((cpu.session_min, cpu.session_max), (95.0, 149.0));
+        assert_eq!(cpu.unit, "\u{00B0}F");
+        assert_eq!((fahrenheit.cpu_temp, fahrenheit.gpu_temp), (Some(122.0), Some(32.0)));
+        // Fans keep their RPM
+        let fan = &fahrenheit.sensors[1];
+        assert_eq!((fan.value, fan.unit.as_str()), (900.0, "RPM"));
+
+        let history = |sensor_type: SensorType, value: f32| SensorHistory {
+            unit: sensor_type.unit().to_string(),
+            sensor_type,
+            min: value,
+            max: value,
+            samples: vec![(1_000, value)],
+        };
+        let temp = history(SensorType::Temperature, 100.0).in_unit(TemperatureUnit::Fahrenheit);
+        assert_eq!((temp.min, temp.samples[0].1, temp.unit.as_str()), (212.0, 212.0, "\u{00B0}F"));
+        let fan = history(SensorType::Fan, 900.0).in_unit(TemperatureUnit::Fahrenheit);
+        assert_eq!((fan.max, fan.samples[0].1, fan.unit.as_str()), (900.0, 900.0, "RPM"));
+    }
+
+    #[test]
+    fn dumps_every_chip_with_sparse_channels() {
+        let root = ScratchDir::new("sensors-hwmon");
+        root.write("hwmon0/name", "nvme\n");
+        root.write("hwmon0/temp1_input", "38850\n");
+        root.write("hwmon0/temp1_label", "Composite\n");
+        root.write("hwmon0/temp1_crit", "84850\n");
+        // temp2 is missing; temp3 must still be found
+        root.write("hwmon0/temp3_input", "41850\n");
+        root.write("hwmon1/name", "amdgpu\n");
+        root.write("hwmon1/in0_input", "1150\n");
+        root.write("hwmon1/in0_label", "vddgfx\n");
+        // power1 only has an average; power2 has both and the instant reading wins
+        root.write("hwmon1/power1_average", "35000000\n");
+        root.write("hwmon1/power2_average", "1000000\n");
+        root.write("hwmon1/power2_input", "2000000\n");
+        root.write("hwmon1/fan1_input", "0\n");
+        // No name attribute: the directory name stands in
+        root.write("hwmon2/temp1_input", "30000\n");
+
+        let chips = read_all_hwmon_in(&root);
+        let names: Vec<&str> = chips.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["nvme", "amdgpu", "hwmon2"]);
+
+        let summary = |chip: &HwmonChip| -> Vec<(String, f32, String)> {
+            chip.readings
+                .iter()
+                .map(|r| (r.label.clone(), r.value, r.unit.clone()))
+                .collect()
+        };
+        let reading = |label: &str, value: f32, unit: &str| {
+            (label.to_string(), value, unit.to_string())
+        };
+        assert_eq!(
+            summary(&chips[0]),
+            [reading("Composite", 38.85, "\u{00B0}C"), reading("temp3", 41.85, "\u{00B0}C")]
+        );
+        assert_eq!(chips[0].readings[0].critical_value, Some(84.85));
+        assert_eq!(
+            summary(&chips[1]),
+            [
+                reading("fan1", 0.0, "RPM"),
+                reading("vddgfx", 1.15, "V"),
+                reading("power1", 35.0, "W"),
+                reading("power2", 2.0, "W"),
+            ]
+        );
+        assert_eq!(chips[2].readings[0].chip.as_deref(), Some("hwmon2"));
+    }
+
+    #[test]
+    fn gpu_summary_reads_the_cached_refresh() {
+        let gpu = Arc::new(GpuMonitor::new());
+        let sensors = SensorsMonitor::with_gpu(Arc::clone(&gpu));
+        assert_eq!(sensors.refresh().gpu_utilization, None);
+
+        gpu.set_cached(GpusInfo {
+            gpus: vec![
+                GpuInfo { utilization_gpu: 10, temperature: 70, ..Default::default() },
+                GpuInfo {
+                    utilization_gpu: 85,
+                    temperature: 60,
+                    memory_total: 8_000,
+                    memory_used: 2_000,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+        let info = sensors.refresh();
+        assert_eq!(info.gpu_utilization, Some(85));
+        assert_eq!(info.gpu_memory_percent, Some(25));
+        assert!(info.gpu_temp.is_some_and(|t| t >= 70.0));
+        // Summarizing never samples the GPU
+        assert!(gpu.history().is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+// macOS sensor backend
+// Reads fan speeds from the System Management Controller (AppleSMC) via IOKit
+// Temperatures already come from sysinfo::Components on macOS
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+
+use super::{SensorReading, SensorType};
+
+type KernReturn = i32;
+type MachPort = u32;
+
+const KERN_SUCCESS: KernReturn = 0;
+// kIOMainPortDefault
+const IO_MAIN_PORT_DEFAULT: MachPort = 0;
+
+// AppleSMC user client selector and commands
+const KERNEL_INDEX_SMC: u32 = 2;
+const SMC_CMD_READ_BYTES: u8 = 5;
+const SMC_CMD_READ_KEYINFO: u8 = 9;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    static mach_task_self_: MachPort;
+
+    fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+    fn IOServiceGetMatchingService(main_port: MachPort, matching: *mut c_void) -> MachPort;
+    fn IOServiceOpen(
+        service: MachPort,
+        owning_task: MachPort,
+        connect_type: u32,
+        connect: *mut MachPort,
+    ) -> KernReturn;
+    fn IOServiceClose(connect: MachPort) -> KernReturn;
+    fn IOObjectRelease(object: MachPort) -> KernReturn;
+    fn IOConnectCallStructMethod(
+        connection: MachPort,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> KernReturn;
+}
+
+// SMCKeyData_t and its members, laid out exactly like the kernel structure (80 bytes)
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcVersion {
+    major: u8,
+    minor: u8,
+    build: u8,
+    reserved: u8,
+    release: u16,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcPLimitData {
+    version: u16,
+    length: u16,
+    cpu_p_limit: u32,
+    gpu_p_limit: u32,
+    mem_p_limit: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcKeyInfo {
+    data_size: u32,
+    data_type: u32,
+    data_attributes: u8,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcKeyData {
+    key: u32,
+    vers: SmcVersion,
+    p_limit_data: SmcPLimitData,
+    key_info: SmcKeyInfo,
+    result: u8,
+    status: u8,
+    data8: u8,
+    data32: u32,
+    bytes: [u8; 32],
+}
+
+/// Four-character SMC codes (keys and data types) are big-endian u32s
+fn fourcc(code: &str) -> u32 {
+    let bytes = code.as_bytes();
+    if bytes.len() != 4 {
+        return 0;
+    }
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// An open connection to AppleSMC, closed on drop
+struct SmcConnection {
+    connection: MachPort,
+}
+
+impl SmcConnection {
+    fn open() -> Option<Self> {
+        let name = CString::new("AppleSMC").ok()?;
+        unsafe {
+            let service =
+                IOServiceGetMatchingService(IO_MAIN_PORT_DEFAULT, IOServiceMatching(name.as_ptr()));
+            if service == 0 {
+                return None;
+            }
+
+            let mut connection: MachPort = 0;
+            let result = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+            IOObjectRelease(service);
+            (result == KERN_SUCCESS).then_some(Self { connection })
+        }
+    }
+
+    fn call(&self, input: &SmcKeyData) -> Option<SmcKeyData> {
+        let mut output = SmcKeyData::default();
+        let mut output_size = std::mem::size_of::<SmcKeyData>();
+        let result = unsafe {
+            IOConnectCallStructMethod(
+                self.connection,
+                KERNEL_INDEX_SMC,
+                input as *const SmcKeyData as *const c_void,
+                std::mem::size_of::<SmcKeyData>(),
+                &mut output as *mut SmcKeyData as *mut c_void,
+                &mut output_size,
+            )
+        };
+        (result == KERN_SUCCESS && output.result == 0).then_some(output)
+    }
+
+    /// Read a key, returning its data type and raw bytes
+    fn read_key(&self, key: &str) -> Option<(u32, Vec<u8>)> {
+        let mut input = SmcKeyData {
+            key: fourcc(key),
+            data8: SMC_CMD_READ_KEYINFO,
+            ..Default::default()
+        };
+        let info = self.call(&input)?.key_info;
+
+        input.key_info.data_size = info.data_size;
+        input.data8 = SMC_CMD_READ_BYTES;
+        let output = self.call(&input)?;
+
+        let size = (info.data_size as usize).min(output.bytes.len());
+        Some((info.data_type, output.bytes[..size].to_vec()))
+    }
+
+    /// Read a numeric key; Intel Macs use fpe2/ui8, Apple Silicon uses flt
+    fn read_number(&self, key: &str) -> Option<f32> {
+        let (data_type, bytes) = self.read_key(key)?;
+        match data_type {
+            t if t == fourcc("fpe2") && bytes.len() >= 2 => {
+                // Unsigned 14.2 fixed point, big-endian
+                Some(u16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 4.0)
+            }
+            t if t == fourcc("flt ") && bytes.len() >= 4 => {
+                Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            t if t == fourcc("ui8 ") && !bytes.is_empty() => Some(bytes[0] as f32),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for SmcConnection {
+    fn drop(&mut self) {
+        unsafe {
+            IOServiceClose(self.connection);
+        }
+    }
+}
+
+/// Fan speeds from the SMC (FNum fans, F<n>Ac actual / F<n>Mx max RPM)
+/// Fanless Macs and failed SMC access yield an empty list
+pub fn read_smc_fans() -> Vec<SensorReading> {
+    let Some(smc) = SmcConnection::open() else {
+        return Vec::new();
+    };
+
+    let fan_count = smc.read_number("FNum").unwrap_or(0.0) as u32;
+    (0..fan_count)
+        .filter_map(|i| {
+            let rpm = smc.read_number(&format!("F{}Ac", i))?;
+            let sensor_type = SensorType::Fan;
+            Some(SensorReading {
+                label: format!("Fan {}", i + 1),
+                chip: None,
+                unit: sensor_type.unit().to_string(),
+                sensor_type,
+                value: rpm,
+                max_value: smc.read_number(&format!("F{}Mx", i)),
+                critical_value: None,
+                session_min: rpm,
+                session_max: rpm,
+            })
+        })
+        .collect()
+}
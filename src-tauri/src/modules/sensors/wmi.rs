@@ -0,0 +1,155 @@
+// Windows sensor backend
+// Reads ACPI thermal zones and, when running, OpenHardwareMonitor's WMI sensors
+
+use serde::Deserialize;
+
+use super::{SensorReading, SensorType};
+use crate::modules::CommandRunner;
+
+// Each namespace is queried on its own so a missing one doesn't hide the other
+const WMI_QUERY: &str = r#"
+$r = @{ acpi = @(); ohm = @() }
+try { $r.acpi = @(Get-CimInstance -Namespace root/wmi -ClassName MSAcpi_ThermalZoneTemperature -ErrorAction Stop | Select-Object InstanceName, CurrentTemperature, CriticalTripPoint) } catch {}
+try { $r.ohm = @(Get-CimInstance -Namespace root/OpenHardwareMonitor -ClassName Sensor -ErrorAction Stop | Select-Object Name, SensorType, Value, Max) } catch {}
+$r | ConvertTo-Json -Depth 3 -Compress
+"#;
+
+#[derive(Debug, Default, Deserialize)]
+struct WmiResult {
+    #[serde(default)]
+    acpi: Vec<AcpiThermalZone>,
+    #[serde(default)]
+    ohm: Vec<OhmSensor>,
+}
+
+/// MSAcpi_ThermalZoneTemperature row; temperatures are in tenths of Kelvin
+#[derive(Debug, Deserialize)]
+struct AcpiThermalZone {
+    #[serde(rename = "InstanceName")]
+    instance_name: Option<String>,
+    #[serde(rename = "CurrentTemperature")]
+    current_temperature: Option<f64>,
+    #[serde(rename = "CriticalTripPoint")]
+    critical_trip_point: Option<f64>,
+}
+
+/// OpenHardwareMonitor Sensor row
+#[derive(Debug, Deserialize)]
+struct OhmSensor {
+    #[serde(rename = "Name")]
+    name: Option<String>,
+    #[serde(rename = "SensorType")]
+    sensor_type: Option<String>,
+    #[serde(rename = "Value")]
+    value: Option<f32>,
+    #[serde(rename = "Max")]
+    max: Option<f32>,
+}
+
+/// Sensors found through WMI
+pub struct WmiSensors {
+    pub readings: Vec<SensorReading>,
+    pub cpu_temp: Option<f32>,
+}
+
+fn deci_kelvin_to_celsius(value: f64) -> f32 {
+    (value / 10.0 - 273.15) as f32
+}
+
+fn reading(label: String, sensor_type: SensorType, value: f32, max: Option<f32>) -> SensorReading {
+    SensorReading {
+        label,
+        chip: None,
+        unit: sensor_type.unit().to_string(),
+        sensor_type,
+        value,
+        max_value: max,
+        critical_value: None,
+        session_min: value,
+        session_max: value,
+    }
+}
+
+/// Query WMI sensors through PowerShell; failures are reported through `errors`
+pub fn read_wmi_sensors(runner: &dyn CommandRunner, errors: &mut Vec<String>) -> WmiSensors {
+    let mut result = WmiSensors {
+        readings: Vec::new(),
+        cpu_temp: None,
+    };
+
+    let output = runner.run(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", WMI_QUERY],
+    );
+
+    let wmi: WmiResult = match output {
+        Ok(output) if output.status.success() => match serde_json::from_slice(&output.stdout) {
+            Ok(wmi) => wmi,
+            Err(e) => {
+                errors.push(format!("Sensors: Failed to parse WMI output: {}", e));
+                return result;
+            }
+        },
+        Ok(output) => {
+            errors.push(format!("Sensors: WMI query exited with {}", output.status));
+            return result;
+        }
+        Err(e) => {
+            errors.push(format!("Sensors: Failed to run PowerShell for WMI: {}", e));
+            return result;
+        }
+    };
+
+    // OpenHardwareMonitor gives real per-component readings when it's running
+    for sensor in wmi.ohm {
+        let (Some(name), Some(value)) = (sensor.name, sensor.value) else {
+            continue;
+        };
+        let sensor_type = match sensor.sensor_type.as_deref() {
+            Some("Temperature") => SensorType::Temperature,
+            Some("Fan") => SensorType::Fan,
+            Some("Voltage") => SensorType::Voltage,
+            Some("Power") => SensorType::Power,
+            _ => continue,
+        };
+
+        if matches!(sensor_type, SensorType::Temperature)
+            && result.cpu_temp.is_none()
+            && name.to_lowercase().contains("cpu")
+        {
+            result.cpu_temp = Some(value);
+        }
+        result
+            .readings
+            .push(reading(name, sensor_type, value, sensor.max));
+    }
+
+    // ACPI thermal zones are coarse but available without extra software
+    for zone in wmi.acpi {
+        let Some(current) = zone.current_temperature else {
+            continue;
+        };
+        let value = deci_kelvin_to_celsius(current);
+        let label = zone
+            .instance_name
+            .unwrap_or_else(|| "ACPI Thermal Zone".to_string());
+
+        // The first thermal zone usually tracks the CPU package
+        if result.cpu_temp.is_none() {
+            result.cpu_temp = Some(value);
+        }
+        let mut thermal = reading(label, SensorType::Temperature, value, None);
+        thermal.critical_value = zone.critical_trip_point.map(deci_kelvin_to_celsius);
+        result.readings.push(thermal);
+    }
+
+    if result.readings.is_empty() {
+        errors.push(
+            "Sensors: No WMI sensors found (ACPI thermal zones need administrator rights; \
+             run OpenHardwareMonitor for full sensor data)"
+                .to_string(),
+        );
+    }
+
+    result
+}
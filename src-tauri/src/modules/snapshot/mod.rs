@@ -0,0 +1,51 @@
+// Snapshot Module
+// Bundles one refresh of every monitor into a single payload
+
+use serde::{Deserialize, Serialize};
+
+use crate::modules::{
+    cpu::CpuInfo, disk::DisksInfo, gpu::GpusInfo, memory::MemoryInfo, network::NetworkInfo,
+    process::ProcessList, sensors::SensorsInfo, system::SystemInfo, TemperatureUnit,
+};
+
+/// All metrics from a single refresh
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub disk: DisksInfo,
+    pub network: NetworkInfo,
+    pub process: Option<ProcessList>, // None when excluded via SnapshotOptions
+    pub gpu: GpusInfo,
+    pub sensors: SensorsInfo,
+    pub system: SystemInfo,
+}
+
+impl Snapshot {
+    /// Render every temperature in the snapshot in the given unit
+    pub fn in_unit(self, unit: TemperatureUnit) -> Self {
+        Self {
+            cpu: self.cpu.in_unit(unit),
+            disk: self.disk.in_unit(unit),
+            gpu: self.gpu.in_unit(unit),
+            sensors: self.sensors.in_unit(unit),
+            ..self
+        }
+    }
+}
+
+/// What to include in a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SnapshotOptions {
+    pub include_processes: bool, // The process list is by far the heaviest part
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            include_processes: true,
+        }
+    }
+}
@@ -1,38 +1,316 @@
 // System Information Module
-// Provides hostname, OS, kernel, and uptime information
+// Provides hostname, OS, kernel, uptime and virtualization information
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Output;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 use sysinfo::System;
 
+use crate::modules::{command_exists, CommandRunner, MonitorError, SystemCommandRunner};
+
 /// System information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SystemInfo {
     pub hostname: String,
     pub os_name: String,
     pub os_version: String,
+    pub distro_name: String, // PRETTY_NAME from os-release, e.g. "Ubuntu 24.04 LTS"
+    pub distro_version: String, // VERSION_ID from os-release
     pub kernel_version: String,
     pub architecture: String,
     pub uptime: u64, // seconds
     pub boot_time: u64, // Unix timestamp
+    pub current_time: u64, // Unix timestamp, the backend clock uptime is measured against
+    pub idle_time: Option<u64>, // Seconds, summed over all CPUs (/proc/uptime)
+    pub suspend_supported: bool, // Suspend-to-RAM or s2idle offered by /sys/power/state
+    pub virtualization: Option<String>, // Hypervisor name, None on bare metal
+    pub is_container: bool,
+    pub timezone: Option<String>, // IANA name, e.g. "Europe/Berlin"
+    pub locale: Option<String>,   // LANG, e.g. "en_US.UTF-8"
+}
+
+/// A logged-in user session from utmp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSession {
+    pub name: String,
+    pub tty: String,
+    pub host: Option<String>, // Remote host, None for local logins
+    pub login_time: u64, // Unix timestamp
+}
+
+/// A loaded kernel module from /proc/modules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelModule {
+    pub name: String,
+    pub size_bytes: u64,
+    pub used_by_count: u32,
+    pub used_by: Vec<String>,
+    pub state: String, // Live, Loading, Unloading
+}
+
+/// Motherboard, BIOS and product details from DMI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareInfo {
+    pub board_vendor: String,
+    pub board_name: String,
+    pub board_version: String,
+    pub bios_vendor: String,
+    pub bios_version: String,
+    pub bios_date: String,
+    pub product_name: String,
+    pub serial: Option<String>, // Usually root-only
+}
+
+/// Boot phase durations from systemd-analyze, in milliseconds
+/// Phases systemd couldn't measure (firmware/loader without EFI, containers) are None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootPerformance {
+    pub firmware_ms: Option<u64>,
+    pub loader_ms: Option<u64>,
+    pub kernel_ms: Option<u64>,
+    pub userspace_ms: Option<u64>,
+    pub total_ms: u64,
+    pub slowest_units: Vec<(String, u64)>, // (unit, ms to start), slowest first
+}
+
+// Number of units reported from systemd-analyze blame (10)
+const SLOWEST_UNITS: usize = 10;
+
+/// A journald log record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: u64, // Unix timestamp in milliseconds
+    pub priority: u8, // Syslog level, 0 (emerg) to 7 (debug)
+    pub unit: Option<String>, // systemd unit, None for kernel and unmanaged messages
+    pub message: String,
+}
+
+// Most log entries returned per request (1000)
+const MAX_LOG_ENTRIES: usize = 1000;
+
+// Messages longer than this are cut off (2048 characters)
+const MAX_LOG_MESSAGE_CHARS: usize = 2048;
+
+/// What the current process is allowed to do, so the UI can disable actions that would fail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub is_root: bool, // Effective UID 0
+    pub can_renice_negative: bool, // CAP_SYS_NICE: raise priority (nice < 0)
+    pub can_kill_others: bool, // CAP_KILL: signal other users' processes
+    pub has_smartctl: bool, // smartmontools on PATH
+    pub has_nvml: bool, // NVIDIA management library loaded
+    pub can_set_gpu: bool, // NVML fan/power limit changes; NVML requires root for those
+}
+
+// Capability bit numbers from linux/capability.h
+const CAP_KILL: u32 = 5;
+const CAP_SYS_NICE: u32 = 23;
+
+/// Detected runtime environment (doesn't change while running)
+#[derive(Debug, Clone)]
+struct Environment {
+    virtualization: Option<String>,
+    is_container: bool,
+    os_release: HashMap<String, String>,
+    timezone: Option<String>,
+    locale: Option<String>,
+}
+
+/// System power actions, each a separate systemctl verb
+#[derive(Debug, Clone, Copy)]
+pub enum PowerAction {
+    Reboot,
+    Shutdown,
+    Suspend,
+}
+
+impl PowerAction {
+    fn systemctl_verb(self) -> &'static str {
+        match self {
+            PowerAction::Reboot => "reboot",
+            PowerAction::Shutdown => "poweroff",
+            PowerAction::Suspend => "suspend",
+        }
+    }
 }
 
 /// System Info Monitor
-pub struct SystemMonitor;
+pub struct SystemMonitor {
+    environment: OnceLock<Environment>,
+    hardware: OnceLock<HardwareInfo>,
+    runner: Box<dyn CommandRunner>,
+}
 
 impl SystemMonitor {
     pub fn new() -> Self {
-        Self
+        Self::with_runner(Box::new(SystemCommandRunner))
+    }
+
+    /// Create a monitor that runs systemctl/systemd-analyze through the given runner
+    pub fn with_runner(runner: Box<dyn CommandRunner>) -> Self {
+        Self {
+            environment: OnceLock::new(),
+            hardware: OnceLock::new(),
+            runner,
+        }
+    }
+
+    /// Reboot, power off or suspend via systemctl (logind decides through polkit)
+    /// Users without polkit rights get PermissionDenied with systemctl's message
+    pub fn power_action(&self, action: PowerAction) -> Result<(), MonitorError> {
+        // --no-ask-password: fail instead of blocking on an interactive polkit prompt
+        let output =
+            self.run_tool("systemctl", &["--no-ask-password", action.systemctl_verb()])?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let lower = stderr.to_lowercase();
+        if lower.contains("access denied")
+            || lower.contains("authentication required")
+            || lower.contains("not authorized")
+        {
+            Err(MonitorError::PermissionDenied(stderr))
+        } else {
+            Err(MonitorError::SystemAccess(format!(
+                "systemctl {} failed: {}",
+                action.systemctl_verb(),
+                stderr
+            )))
+        }
+    }
+
+    /// Boot phase timings and the slowest units, from systemd-analyze and its blame view
+    pub fn boot_performance(&self) -> Result<BootPerformance, MonitorError> {
+        let init = fs::read_to_string("/proc/1/comm").unwrap_or_default();
+        if init.trim() != "systemd" {
+            return Err(MonitorError::Unsupported(
+                "Boot timings require systemd as the init system".to_string(),
+            ));
+        }
+
+        let summary = self.run_analyze(&["time"])?;
+        let mut performance = parse_analyze_time(&summary).ok_or_else(|| {
+            MonitorError::SystemAccess(format!(
+                "Unexpected systemd-analyze output: {}",
+                summary.trim()
+            ))
+        })?;
+
+        let blame = self.run_analyze(&["blame", "--no-pager"])?;
+        performance.slowest_units = parse_analyze_blame(&blame, SLOWEST_UNITS);
+        Ok(performance)
+    }
+
+    /// The last `count` journal entries at `min_priority` or more severe, oldest first
+    /// Without the systemd-journal group only the user's own entries are visible
+    pub fn recent_logs(
+        &self,
+        count: usize,
+        min_priority: u8,
+    ) -> Result<Vec<LogEntry>, MonitorError> {
+        let count = count.clamp(1, MAX_LOG_ENTRIES).to_string();
+        let priority = min_priority.min(7).to_string();
+        let output = self.run_tool(
+            "journalctl",
+            &["--no-pager", "-o", "json", "-n", &count, "-p", &priority],
+        )?;
+        if !output.status.success() {
+            return Err(MonitorError::SystemAccess(format!(
+                "journalctl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_journal_json(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Run systemd-analyze, returning stdout or its error message
+    /// (e.g. "Bootup is not yet finished" while units are still starting)
+    fn run_analyze(&self, args: &[&str]) -> Result<String, MonitorError> {
+        let output = self.run_tool("systemd-analyze", args)?;
+        if !output.status.success() {
+            return Err(MonitorError::SystemAccess(format!(
+                "systemd-analyze failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Run a systemd tool through the runner, mapping a missing binary to Unsupported
+    fn run_tool(&self, program: &str, args: &[&str]) -> Result<Output, MonitorError> {
+        self.runner.run(program, args).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                MonitorError::Unsupported(format!("{} is not available", program))
+            }
+            _ => MonitorError::SystemAccess(format!("Failed to run {}: {}", program, e)),
+        })
+    }
+
+    /// DMI hardware details, read once since they can't change at runtime
+    pub fn hardware_info(&self) -> HardwareInfo {
+        self.hardware
+            .get_or_init(|| read_hardware_info(Path::new(DMI_ID_PATH)))
+            .clone()
     }
 
     pub fn refresh(&self) -> SystemInfo {
+        let environment = self.environment.get_or_init(|| Environment {
+            virtualization: detect_hypervisor(self.runner.as_ref()),
+            is_container: detect_container(),
+            os_release: read_os_release(),
+            timezone: read_timezone(),
+            locale: std::env::var("LANG").ok().filter(|l| !l.is_empty()),
+        });
+
+        let os_name = System::name().unwrap_or_else(|| "Unknown".to_string());
+        let os_version = System::os_version().unwrap_or_else(|| "Unknown".to_string());
+        let os_release = &environment.os_release;
+
         SystemInfo {
             hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
-            os_name: System::name().unwrap_or_else(|| "Unknown".to_string()),
-            os_version: System::os_version().unwrap_or_else(|| "Unknown".to_string()),
+            distro_name: os_release.get("PRETTY_NAME")
+                .or_else(|| os_release.get("NAME"))
+                .cloned()
+                .unwrap_or_else(|| os_name.clone()),
+            distro_version: os_release.get("VERSION_ID")
+                .cloned()
+                .unwrap_or_else(|| os_version.clone()),
+            os_name,
+            os_version,
             kernel_version: System::kernel_version().unwrap_or_else(|| "Unknown".to_string()),
             architecture: System::cpu_arch().unwrap_or_else(|| "Unknown".to_string()),
             uptime: System::uptime(),
             boot_time: System::boot_time(),
+            current_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            idle_time: fs::read_to_string("/proc/uptime")
+                .ok()
+                .and_then(|contents| parse_uptime(&contents))
+                .map(|(_, idle)| idle),
+            suspend_supported: fs::read_to_string("/sys/power/state")
+                .map(|states| states.split_whitespace().any(|s| s == "mem" || s == "freeze"))
+                .unwrap_or(false),
+            virtualization: environment.virtualization.clone(),
+            is_container: environment.is_container,
+            timezone: environment.timezone.clone(),
+            locale: environment.locale.clone(),
         }
     }
 }
@@ -42,3 +320,696 @@ impl Default for SystemMonitor {
         Self::new()
     }
 }
+
+/// Parse the systemd-analyze summary line:
+/// "Startup finished in 7.1s (firmware) + 3s (loader) + 2.5s (kernel) + 4.2s (userspace) = 16.9s"
+/// Phases missing from the line (no EFI, containers) stay None; initrd is left out
+fn parse_analyze_time(output: &str) -> Option<BootPerformance> {
+    let line = output.lines().find(|l| l.starts_with("Startup finished in "))?;
+    let (phases, total) = line.trim_start_matches("Startup finished in ").rsplit_once(" = ")?;
+
+    let mut performance = BootPerformance {
+        firmware_ms: None,
+        loader_ms: None,
+        kernel_ms: None,
+        userspace_ms: None,
+        total_ms: parse_timespan_ms(total)?,
+        slowest_units: Vec::new(),
+    };
+
+    for phase in phases.split(" + ") {
+        let phase = phase.trim().strip_suffix(')');
+        let Some((time, label)) = phase.and_then(|p| p.rsplit_once(" (")) else {
+            continue;
+        };
+        let slot = match label {
+            "firmware" => &mut performance.firmware_ms,
+            "loader" => &mut performance.loader_ms,
+            "kernel" => &mut performance.kernel_ms,
+            "userspace" => &mut performance.userspace_ms,
+            _ => continue,
+        };
+        *slot = parse_timespan_ms(time);
+    }
+
+    Some(performance)
+}
+
+/// Parse `journalctl -o json` output, one JSON object per line
+/// Records without a message are skipped; PRIORITY defaults to 6 (info) like journald's
+fn parse_journal_json(output: &str) -> Vec<LogEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let serde_json::Value::Object(fields) = serde_json::from_str(line).ok()? else {
+                return None;
+            };
+            let text = |key: &str| fields.get(key).and_then(|v| v.as_str());
+
+            let mut message = journal_field_text(fields.get("MESSAGE")?)?;
+            if let Some((cut, _)) = message.char_indices().nth(MAX_LOG_MESSAGE_CHARS) {
+                message.truncate(cut);
+                message.push('…');
+            }
+
+            Some(LogEntry {
+                timestamp: text("__REALTIME_TIMESTAMP")
+                    .and_then(|us| us.parse::<u64>().ok())
+                    .map(|us| us / 1000)
+                    .unwrap_or(0),
+                priority: text("PRIORITY").and_then(|p| p.parse().ok()).unwrap_or(6),
+                unit: text("_SYSTEMD_UNIT")
+                    .or_else(|| text("_SYSTEMD_USER_UNIT"))
+                    .map(str::to_string),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Journal field as text: JSON output uses a string, or a byte array for non-UTF-8 values
+fn journal_field_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect();
+            Some(String::from_utf8_lossy(&bytes).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Parse systemd-analyze blame lines ("1min 2.345s foo.service"), which come slowest first
+fn parse_analyze_blame(output: &str, limit: usize) -> Vec<(String, u64)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (time, unit) = line.trim().rsplit_once(char::is_whitespace)?;
+            Some((unit.to_string(), parse_timespan_ms(time)?))
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Parse a systemd timespan ("1.234s", "456ms", "1min 2.5s", "1h 3min", "250us") into
+/// milliseconds, rounded; None if any component isn't a number followed by a known unit
+fn parse_timespan_ms(span: &str) -> Option<u64> {
+    let mut total_ms = 0.0;
+    let mut components = 0;
+
+    for part in span.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (value, unit) = part.split_at(split);
+        let value: f64 = value.parse().ok()?;
+        let unit_ms = match unit {
+            "us" | "µs" | "μs" => 0.001,
+            "ms" => 1.0,
+            "s" => 1_000.0,
+            "min" => 60_000.0,
+            "h" => 3_600_000.0,
+            "d" => 86_400_000.0,
+            "w" => 604_800_000.0,
+            "month" => 2_629_800_000.0,
+            "y" => 31_557_600_000.0,
+            _ => return None,
+        };
+        total_ms += value * unit_ms;
+        components += 1;
+    }
+
+    (components > 0).then(|| total_ms.round() as u64)
+}
+
+/// Read /etc/os-release, falling back to /usr/lib/os-release
+fn read_os_release() -> HashMap<String, String> {
+    fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .map(|contents| parse_os_release(&contents))
+        .unwrap_or_default()
+}
+
+/// Parse os-release KEY=value lines; values may be single/double quoted or bare
+fn parse_os_release(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|&q| value.strip_prefix(q).and_then(|v| v.strip_suffix(q)))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), unquoted.replace("\\\"", "\"")))
+        })
+        .collect()
+}
+
+/// System timezone from /etc/timezone (Debian) or the /etc/localtime symlink target
+fn read_timezone() -> Option<String> {
+    fs::read_to_string("/etc/timezone")
+        .ok()
+        .map(|tz| tz.trim().to_string())
+        .filter(|tz| !tz.is_empty())
+        .or_else(|| {
+            let target = fs::read_link("/etc/localtime").ok()?;
+            zone_from_localtime_target(&target.to_string_lossy())
+        })
+}
+
+/// Zone name from a localtime symlink target, e.g. "../usr/share/zoneinfo/Europe/Berlin"
+fn zone_from_localtime_target(target: &str) -> Option<String> {
+    let (_, zone) = target.rsplit_once("zoneinfo/")?;
+    // Some distros link into zoneinfo/posix/ or zoneinfo/right/ variants
+    let zone = zone
+        .strip_prefix("posix/")
+        .or_else(|| zone.strip_prefix("right/"))
+        .unwrap_or(zone);
+    (!zone.is_empty()).then(|| zone.to_string())
+}
+
+/// Parse /proc/uptime "<uptime> <idle>" (fractional seconds) into whole seconds
+fn parse_uptime(contents: &str) -> Option<(u64, u64)> {
+    let mut fields = contents.split_whitespace();
+    let mut next = || fields.next()?.parse::<f64>().ok().map(|secs| secs as u64);
+    Some((next()?, next()?))
+}
+
+/// Privileges of this process and the optional tools it can use
+/// `has_nvml` comes from the GPU monitor, which owns the NVML handle
+pub fn get_capabilities(has_nvml: bool) -> Capabilities {
+    // SAFETY: geteuid has no preconditions and can't fail
+    let is_root = unsafe { libc::geteuid() } == 0;
+    // Root inside a container can be missing capabilities, so CapEff decides; without
+    // /proc (other platforms) root is assumed to have them all
+    let cap_eff = fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_cap_eff(&status));
+    let has_cap = |cap: u32| cap_eff.map_or(is_root, |mask| mask & (1 << cap) != 0);
+
+    Capabilities {
+        is_root,
+        can_renice_negative: has_cap(CAP_SYS_NICE),
+        can_kill_others: has_cap(CAP_KILL),
+        has_smartctl: command_exists("smartctl"),
+        has_nvml,
+        can_set_gpu: has_nvml && is_root,
+    }
+}
+
+/// Effective capability mask from the hex "CapEff:" line of /proc/<pid>/status
+fn parse_cap_eff(status: &str) -> Option<u64> {
+    let mask = status.lines().find_map(|line| line.strip_prefix("CapEff:"))?;
+    u64::from_str_radix(mask.trim(), 16).ok()
+}
+
+/// Loaded kernel modules, largest first; empty when /proc/modules is unavailable
+pub fn get_kernel_modules() -> Vec<KernelModule> {
+    let mut modules: Vec<KernelModule> = fs::read_to_string("/proc/modules")
+        .map(|contents| contents.lines().filter_map(parse_module_line).collect())
+        .unwrap_or_default();
+    modules.sort_by_key(|m| std::cmp::Reverse(m.size_bytes));
+    modules
+}
+
+/// Parse one /proc/modules line: "name size refcount deps state address"
+/// deps is "-" when unused, otherwise a comma list with a trailing comma
+fn parse_module_line(line: &str) -> Option<KernelModule> {
+    let mut fields = line.split_whitespace();
+    let name = fields.next()?.to_string();
+    let size_bytes = fields.next()?.parse().ok()?;
+    let used_by_count = fields.next()?.parse().ok()?;
+    let used_by = match fields.next()? {
+        "-" => Vec::new(),
+        deps => deps
+            .split(',')
+            .filter(|d| !d.is_empty())
+            .map(str::to_string)
+            .collect(),
+    };
+    let state = fields.next().unwrap_or("Unknown").to_string();
+
+    Some(KernelModule {
+        name,
+        size_bytes,
+        used_by_count,
+        used_by,
+        state,
+    })
+}
+
+// glibc utmp record layout (Linux, 64-bit and 32-bit share it)
+const UTMP_PATH: &str = "/var/run/utmp";
+const UTMP_RECORD_SIZE: usize = 384;
+const UT_USER_PROCESS: i16 = 7;
+const UT_LINE: (usize, usize) = (8, 32); // (offset, length)
+const UT_USER: (usize, usize) = (44, 32);
+const UT_HOST: (usize, usize) = (76, 256);
+const UT_TV_SEC: usize = 340;
+
+/// Logged-in user sessions; empty if utmp is missing or unreadable
+pub fn get_logged_in_users() -> Vec<UserSession> {
+    fs::read(UTMP_PATH)
+        .map(|data| parse_utmp(&data))
+        .unwrap_or_default()
+}
+
+/// Parse raw utmp records, keeping only USER_PROCESS entries
+fn parse_utmp(data: &[u8]) -> Vec<UserSession> {
+    let field = |record: &[u8], (offset, len): (usize, usize)| -> String {
+        let bytes = &record[offset..offset + len];
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(len);
+        String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+    };
+
+    data.chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == UT_USER_PROCESS)
+        .map(|record| {
+            let host = field(record, UT_HOST);
+            let tv_sec = &record[UT_TV_SEC..UT_TV_SEC + 4];
+            UserSession {
+                name: field(record, UT_USER),
+                tty: field(record, UT_LINE),
+                host: (!host.is_empty()).then_some(host),
+                login_time: u32::from_ne_bytes([tv_sec[0], tv_sec[1], tv_sec[2], tv_sec[3]])
+                    as u64,
+            }
+        })
+        .collect()
+}
+
+/// Detect the hypervisor: systemd-detect-virt, then DMI strings, then the CPUID hypervisor flag
+fn detect_hypervisor(runner: &dyn CommandRunner) -> Option<String> {
+    // Exits non-zero and prints "none" on bare metal; a missing binary falls through
+    if let Ok(output) = runner.run("systemd-detect-virt", &["--vm"]) {
+        let virt = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !virt.is_empty() && virt != "none" {
+            return Some(virt);
+        }
+        if virt == "none" {
+            return None;
+        }
+    }
+
+    let dmi = ["product_name", "sys_vendor", "board_vendor"]
+        .iter()
+        .filter_map(|f| read_dmi(Path::new(DMI_ID_PATH), f))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(name) = hypervisor_from_dmi(&dmi) {
+        return Some(name.to_string());
+    }
+
+    let has_hypervisor_flag = fs::read_to_string("/proc/cpuinfo")
+        .map(|info| {
+            info.lines()
+                .filter(|l| l.starts_with("flags"))
+                .any(|l| l.split_whitespace().any(|f| f == "hypervisor"))
+        })
+        .unwrap_or(false);
+    has_hypervisor_flag.then(|| "unknown".to_string())
+}
+
+const DMI_ID_PATH: &str = "/sys/class/dmi/id";
+
+/// Read a DMI attribute from `dmi_dir`, None if missing, unreadable or blank
+fn read_dmi(dmi_dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dmi_dir.join(name))
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn read_hardware_info(dmi_dir: &Path) -> HardwareInfo {
+    let read = |name: &str| read_dmi(dmi_dir, name).unwrap_or_else(|| "Unknown".to_string());
+
+    HardwareInfo {
+        board_vendor: read("board_vendor"),
+        board_name: read("board_name"),
+        board_version: read("board_version"),
+        bios_vendor: read("bios_vendor"),
+        bios_version: read("bios_version"),
+        bios_date: read("bios_date"),
+        product_name: read("product_name"),
+        serial: read_dmi(dmi_dir, "product_serial").or_else(|| read_dmi(dmi_dir, "board_serial")),
+    }
+}
+
+/// Map DMI product/vendor strings to a hypervisor name
+fn hypervisor_from_dmi(dmi: &str) -> Option<&'static str> {
+    // (substring, hypervisor), matched case-insensitively
+    const DMI_SIGNATURES: &[(&str, &str)] = &[
+        ("kvm", "kvm"),
+        ("qemu", "qemu"),
+        ("vmware", "vmware"),
+        ("virtualbox", "oracle"),
+        ("innotek", "oracle"),
+        ("xen", "xen"),
+        ("microsoft corporation virtual machine", "microsoft"),
+        ("parallels", "parallels"),
+        ("bochs", "bochs"),
+        ("amazon ec2", "amazon"),
+        ("google compute engine", "google"),
+    ];
+
+    let dmi = dmi.to_lowercase();
+    DMI_SIGNATURES
+        .iter()
+        .find(|(signature, _)| dmi.contains(signature))
+        .map(|(_, name)| *name)
+}
+
+/// Detect whether we run inside a container (Docker, Podman, LXC, Kubernetes)
+fn detect_container() -> bool {
+    if Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists() {
+        return true;
+    }
+
+    fs::read_to_string("/proc/1/cgroup")
+        .map(|cgroup| is_container_cgroup(&cgroup))
+        .unwrap_or(false)
+}
+
+/// Check cgroup contents for container runtime paths
+fn is_container_cgroup(cgroup: &str) -> bool {
+    const CONTAINER_MARKERS: &[&str] = &["docker", "kubepods", "lxc", "containerd", "libpod"];
+    cgroup
+        .lines()
+        .any(|line| CONTAINER_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::{FakeCommandRunner, ScratchDir};
+
+    #[test]
+    fn maps_dmi_strings_to_hypervisors() {
+        assert_eq!(hypervisor_from_dmi("Standard PC (Q35 + ICH9, 2009) QEMU"), Some("qemu"));
+        assert_eq!(hypervisor_from_dmi("VirtualBox innotek GmbH"), Some("oracle"));
+        assert_eq!(hypervisor_from_dmi("VMware7,1 VMware, Inc."), Some("vmware"));
+        assert_eq!(
+            hypervisor_from_dmi("Virtual Machine Microsoft Corporation Virtual Machine"),
+            Some("microsoft")
+        );
+        assert_eq!(hypervisor_from_dmi("ThinkPad X1 Carbon LENOVO"), None);
+        assert_eq!(hypervisor_from_dmi(""), None);
+    }
+
+    #[test]
+    fn detects_container_cgroups() {
+        assert!(is_container_cgroup("0::/system.slice/docker-4f1c2a3b.scope\n"));
+        assert!(is_container_cgroup("12:pids:/kubepods/besteffort/pod1234/abcd\n0::/\n"));
+        assert!(!is_container_cgroup("0::/init.scope\n"));
+        assert!(!is_container_cgroup("0::/user.slice/user-1000.slice/session-2.scope\n"));
+    }
+
+    fn utmp_record(kind: i16, line: &str, user: &str, host: &str, tv_sec: u32) -> Vec<u8> {
+        let mut record = vec![0u8; UTMP_RECORD_SIZE];
+        record[..2].copy_from_slice(&kind.to_ne_bytes());
+        for ((offset, _), text) in [(UT_LINE, line), (UT_USER, user), (UT_HOST, host)] {
+            record[offset..offset + text.len()].copy_from_slice(text.as_bytes());
+        }
+        record[UT_TV_SEC..UT_TV_SEC + 4].copy_from_slice(&tv_sec.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn parses_user_process_utmp_records() {
+        let mut data = utmp_record(2, "~", "reboot", "6.8.0-generic", 1_700_000_000);
+        data.extend(utmp_record(UT_USER_PROCESS, "tty2", "alice", "", 1_700_000_100));
+        data.extend(utmp_record(UT_USER_PROCESS, "pts/0", "bob", "10.0.0.7", 1_700_000_200));
+        data.extend(utmp_record(8, "pts/1", "", "", 1_700_000_300)); // DEAD_PROCESS
+        data.extend_from_slice(&[0u8; 100]); // Truncated trailing record
+
+        let sessions = parse_utmp(&data);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "alice");
+        assert_eq!(sessions[0].tty, "tty2");
+        assert_eq!(sessions[0].host, None);
+        assert_eq!(sessions[0].login_time, 1_700_000_100);
+        assert_eq!(sessions[1].name, "bob");
+        assert_eq!(sessions[1].tty, "pts/0");
+        assert_eq!(sessions[1].host.as_deref(), Some("10.0.0.7"));
+        assert_eq!(sessions[1].login_time, 1_700_000_200);
+    }
+
+    #[test]
+    fn parses_os_release() {
+        let release = parse_os_release(
+            "# Generated\n\
+             NAME=\"Ubuntu\"\n\
+             VERSION_ID=\"24.04\"\n\
+             PRETTY_NAME='Ubuntu 24.04 LTS'\n\
+             ID=ubuntu\n\
+             \n\
+             VARIANT=\"Server \\\"minimal\\\"\"\n",
+        );
+        assert_eq!(release.get("NAME").map(String::as_str), Some("Ubuntu"));
+        assert_eq!(release.get("VERSION_ID").map(String::as_str), Some("24.04"));
+        assert_eq!(release.get("PRETTY_NAME").map(String::as_str), Some("Ubuntu 24.04 LTS"));
+        assert_eq!(release.get("ID").map(String::as_str), Some("ubuntu"));
+        assert_eq!(release.get("VARIANT").map(String::as_str), Some("Server \"minimal\""));
+        assert_eq!(release.len(), 5);
+    }
+
+    #[test]
+    fn parses_proc_modules_lines() {
+        let module = parse_module_line(
+            "snd_hda_intel 61440 4 snd_hda_codec_hdmi,snd_hda_codec, Live 0xffffffffc0a1b000",
+        )
+        .unwrap();
+        assert_eq!(module.name, "snd_hda_intel");
+        assert_eq!(module.size_bytes, 61440);
+        assert_eq!(module.used_by_count, 4);
+        assert_eq!(module.used_by, ["snd_hda_codec_hdmi", "snd_hda_codec"]);
+        assert_eq!(module.state, "Live");
+
+        let unused = parse_module_line("crc32_pclmul 12288 0 - Live 0x0000000000000000").unwrap();
+        assert!(unused.used_by.is_empty());
+        assert_eq!(unused.used_by_count, 0);
+
+        assert!(parse_module_line("broken notanumber 0 - Live").is_none());
+        assert!(parse_module_line("").is_none());
+    }
+
+    #[test]
+    fn reads_hardware_info_from_dmi_dir() {
+        let dir = ScratchDir::new("dmi");
+        for (name, value) in [
+            ("board_vendor", "ASUSTeK COMPUTER INC.\n"),
+            ("board_name", "PRIME X570-PRO\n"),
+            ("board_version", "Rev X.0x\n"),
+            ("bios_vendor", "American Megatrends Inc.\n"),
+            ("bios_version", "4021\n"),
+            ("bios_date", "08/09/2021\n"),
+            ("product_name", "   \n"),
+            ("board_serial", "201176726101234\n"),
+        ] {
+            dir.write(name, value);
+        }
+
+        let info = read_hardware_info(&dir);
+
+        assert_eq!(info.board_vendor, "ASUSTeK COMPUTER INC.");
+        assert_eq!(info.board_name, "PRIME X570-PRO");
+        assert_eq!(info.board_version, "Rev X.0x");
+        assert_eq!(info.bios_vendor, "American Megatrends Inc.");
+        assert_eq!(info.bios_version, "4021");
+        assert_eq!(info.bios_date, "08/09/2021");
+        // Blank attributes read as missing; the serial falls back to the board's
+        assert_eq!(info.product_name, "Unknown");
+        assert_eq!(info.serial.as_deref(), Some("201176726101234"));
+    }
+
+    #[test]
+    fn hypervisor_comes_from_systemd_detect_virt() {
+        let runner = FakeCommandRunner::default();
+        runner.respond("systemd-detect-virt --vm", 0, "kvm\n");
+        assert_eq!(detect_hypervisor(&runner).as_deref(), Some("kvm"));
+
+        // Bare metal: a definite "none" skips the DMI and CPUID fallbacks
+        runner.respond("systemd-detect-virt --vm", 1, "none\n");
+        assert_eq!(detect_hypervisor(&runner), None);
+        assert_eq!(runner.calls(), ["systemd-detect-virt --vm", "systemd-detect-virt --vm"]);
+    }
+
+    #[test]
+    fn parses_proc_uptime() {
+        assert_eq!(parse_uptime("350735.47 1232287.27\n"), Some((350735, 1232287)));
+        assert_eq!(parse_uptime("12.99 0.50"), Some((12, 0)));
+        assert_eq!(parse_uptime("350735.47"), None);
+        assert_eq!(parse_uptime(""), None);
+    }
+
+    #[test]
+    fn extracts_zone_from_localtime_target() {
+        assert_eq!(
+            zone_from_localtime_target("../usr/share/zoneinfo/Europe/Berlin").as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(
+            zone_from_localtime_target("/usr/share/zoneinfo/posix/America/New_York").as_deref(),
+            Some("America/New_York")
+        );
+        assert_eq!(
+            zone_from_localtime_target("/usr/share/zoneinfo/right/UTC").as_deref(),
+            Some("UTC")
+        );
+        assert_eq!(zone_from_localtime_target("/usr/share/zoneinfo/"), None);
+        assert_eq!(zone_from_localtime_target("/etc/localtime.bak"), None);
+    }
+
+    #[test]
+    fn power_actions_run_systemctl_without_prompting() {
+        let runner = FakeCommandRunner::default();
+        let monitor = SystemMonitor::with_runner(Box::new(runner.clone()));
+        runner.respond("systemctl", 0, "");
+        for action in [PowerAction::Reboot, PowerAction::Shutdown, PowerAction::Suspend] {
+            monitor.power_action(action).unwrap();
+        }
+        assert_eq!(
+            runner.calls(),
+            [
+                "systemctl --no-ask-password reboot",
+                "systemctl --no-ask-password poweroff",
+                "systemctl --no-ask-password suspend",
+            ]
+        );
+
+        let denied = "Failed to reboot system via logind: Interactive authentication required.";
+        runner.respond_with_stderr("systemctl", 1, "", denied);
+        match monitor.power_action(PowerAction::Reboot) {
+            Err(MonitorError::PermissionDenied(message)) => assert_eq!(message, denied),
+            other => panic!("expected PermissionDenied, got {:?}", other),
+        }
+        runner.respond_with_stderr("systemctl", 1, "", "System has not been booted with systemd");
+        assert!(matches!(
+            monitor.power_action(PowerAction::Suspend),
+            Err(MonitorError::SystemAccess(_))
+        ));
+
+        // No systemctl at all
+        let monitor = SystemMonitor::with_runner(Box::new(FakeCommandRunner::default()));
+        assert!(matches!(
+            monitor.power_action(PowerAction::Shutdown),
+            Err(MonitorError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn parses_systemd_timespans() {
+        assert_eq!(parse_timespan_ms("1.234s"), Some(1234));
+        assert_eq!(parse_timespan_ms("456ms"), Some(456));
+        assert_eq!(parse_timespan_ms("1min 2.5s"), Some(62_500));
+        assert_eq!(parse_timespan_ms("1h 3min"), Some(3_780_000));
+        assert_eq!(parse_timespan_ms("250us"), Some(0));
+        assert_eq!(parse_timespan_ms("1500µs"), Some(2));
+        assert_eq!(parse_timespan_ms(""), None);
+        assert_eq!(parse_timespan_ms("5 parsecs"), None);
+        assert_eq!(parse_timespan_ms("3fortnights"), None);
+    }
+
+    #[test]
+    fn parses_analyze_time_summary() {
+        let performance = parse_analyze_time(
+            "Startup finished in 7.100s (firmware) + 3s (loader) + 2.512s (kernel) + \
+             1.2s (initrd) + 4.201s (userspace) = 18.013s\n\
+             graphical.target reached after 4.150s in userspace.\n",
+        )
+        .unwrap();
+        assert_eq!(performance.firmware_ms, Some(7100));
+        assert_eq!(performance.loader_ms, Some(3000));
+        assert_eq!(performance.kernel_ms, Some(2512));
+        assert_eq!(performance.userspace_ms, Some(4201));
+        assert_eq!(performance.total_ms, 18_013);
+
+        // Containers and non-EFI boots only report some phases
+        let performance = parse_analyze_time(
+            "Startup finished in 1.918s (kernel) + 12.306s (userspace) = 14.224s\n",
+        )
+        .unwrap();
+        assert_eq!(performance.firmware_ms, None);
+        assert_eq!(performance.loader_ms, None);
+        assert_eq!(performance.kernel_ms, Some(1918));
+        assert_eq!(performance.total_ms, 14_224);
+
+        assert!(parse_analyze_time("Bootup is not yet finished.\n").is_none());
+    }
+
+    #[test]
+    fn parses_analyze_blame_slowest_first() {
+        let blame = "1min 2.345s plymouth-quit-wait.service\n\
+                     \x20    5.101s NetworkManager-wait-online.service\n\
+                     \x20     812ms udisks2.service\n\
+                     \x20      99ms snapd.apparmor.service\n";
+        assert_eq!(
+            parse_analyze_blame(blame, 3),
+            [
+                ("plymouth-quit-wait.service".to_string(), 62_345),
+                ("NetworkManager-wait-online.service".to_string(), 5101),
+                ("udisks2.service".to_string(), 812),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_journal_json_records() {
+        use serde_json::json;
+
+        let records = [
+            json!({
+                "__REALTIME_TIMESTAMP": "1700000000123456",
+                "PRIORITY": "3",
+                "_SYSTEMD_UNIT": "nginx.service",
+                "MESSAGE": "bind() failed",
+            }),
+            json!({
+                "__REALTIME_TIMESTAMP": "1700000001000000",
+                "_SYSTEMD_USER_UNIT": "pipewire.service",
+                "MESSAGE": [104, 105, 255],
+            }),
+            json!({ "__REALTIME_TIMESTAMP": "1700000002000000", "PRIORITY": "6" }),
+            json!({ "PRIORITY": "0", "MESSAGE": "kernel panic" }),
+            json!({ "MESSAGE": "x".repeat(MAX_LOG_MESSAGE_CHARS + 10) }),
+        ];
+        let mut output: String = records.iter().map(|record| format!("{}\n", record)).collect();
+        output.push_str("not json\n");
+
+        let entries = parse_journal_json(&output);
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].timestamp, 1_700_000_000_123);
+        assert_eq!(entries[0].priority, 3);
+        assert_eq!(entries[0].unit.as_deref(), Some("nginx.service"));
+        assert_eq!(entries[0].message, "bind() failed");
+
+        // Non-UTF-8 messages come as byte arrays; missing PRIORITY means info
+        assert_eq!(entries[1].priority, 6);
+        assert_eq!(entries[1].unit.as_deref(), Some("pipewire.service"));
+        assert_eq!(entries[1].message, "hi\u{fffd}");
+
+        assert_eq!(entries[2].timestamp, 0);
+        assert_eq!(entries[2].priority, 0);
+        assert_eq!(entries[2].unit, None);
+
+        assert_eq!(entries[3].message.chars().count(), MAX_LOG_MESSAGE_CHARS + 1);
+        assert!(entries[3].message.ends_with('…'));
+    }
+
+    #[test]
+    fn parses_effective_capabilities() {
+        let status = "Name:\tnova\nCapInh:\t0000000000000000\nCapPrm:\t000001ffffffffff\n\
+                      CapEff:\t0000000000800020\nCapBnd:\t000001ffffffffff\n";
+        let mask = parse_cap_eff(status).unwrap();
+        assert_eq!(mask, 0x800020);
+        assert_ne!(mask & (1 << CAP_KILL), 0);
+        assert_ne!(mask & (1 << CAP_SYS_NICE), 0);
+
+        // Unprivileged process
+        assert_eq!(parse_cap_eff("CapEff:\t0000000000000000\n"), Some(0));
+        assert_eq!(parse_cap_eff("Name:\tnova\n"), None);
+        assert_eq!(parse_cap_eff("CapEff:\tnot-hex\n"), None);
+    }
+}
@@ -112,7 +112,7 @@ export async function fetchCpuInfo() {
 
         // Update History
         pushTimestamp(cpuHistory.timestamps);
-        pushHistory(cpuHistory.global, data.global_usage ?? 0);
+        pushHistory(cpuHistory.global, data.globalUsage ?? 0);
 
         // Initialize core history arrays if needed
         if (Array.isArray(data.cores)) {
@@ -145,8 +145,8 @@ export async function fetchMemoryInfo() {
 
         // Update History with safe division
         pushTimestamp(memoryHistory.timestamps);
-        const ramPercent = data.total_memory > 0 ? (data.used_memory / data.total_memory) * 100 : 0;
-        const swapPercent = data.total_swap > 0 ? (data.used_swap / data.total_swap) * 100 : 0;
+        const ramPercent = data.totalMemory > 0 ? (data.usedMemory / data.totalMemory) * 100 : 0;
+        const swapPercent = data.totalSwap > 0 ? (data.usedSwap / data.totalSwap) * 100 : 0;
         pushHistory(memoryHistory.ram_used, ramPercent);
         pushHistory(memoryHistory.swap_used, swapPercent);
 
@@ -189,8 +189,8 @@ export async function fetchDiskInfo() {
         let totalWrite = 0;
         if (data.disks && Array.isArray(data.disks)) {
             data.disks.forEach((disk: any) => {
-                totalRead += disk.read_bytes || 0;
-                totalWrite += disk.written_bytes || 0;
+                totalRead += disk.readBytes || 0;
+                totalWrite += disk.writtenBytes || 0;
             });
         }
 
@@ -219,8 +219,8 @@ export async function fetchNetworkInfo() {
 
         // Update History
         pushTimestamp(networkHistory.timestamps);
-        pushHistory(networkHistory.download, data.total_download_rate ?? 0);
-        pushHistory(networkHistory.upload, data.total_upload_rate ?? 0);
+        pushHistory(networkHistory.download, data.totalDownloadRate ?? 0);
+        pushHistory(networkHistory.upload, data.totalUploadRate ?? 0);
 
     } catch (e: any) {
         networkInfo.error = e?.message || e?.toString() || 'Unknown error';